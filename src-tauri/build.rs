@@ -1,7 +1,11 @@
 fn main() {
-    use tauri_build::Attributes;
-    if let Err(e) = tauri_build::try_build(Attributes::new()) {
-        eprintln!("tauri-build error: {e}");
-        std::process::exit(1);
+    // The headless CLI builds without the desktop feature (and without tauri-build).
+    #[cfg(feature = "desktop")]
+    {
+        use tauri_build::Attributes;
+        if let Err(e) = tauri_build::try_build(Attributes::new()) {
+            eprintln!("tauri-build error: {e}");
+            std::process::exit(1);
+        }
     }
 }
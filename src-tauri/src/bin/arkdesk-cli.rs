@@ -0,0 +1,506 @@
+// src/bin/arkdesk-cli.rs
+//! Headless ArkDesk for CI machines and SSH sessions: node control, logs, RPC,
+//! install and self-test on top of the same backend as the desktop app.
+use arkdesk_lib::{
+  bundle,
+  cleanup::{self, UninstallPlan},
+  config,
+  disk,
+  events::{EventSink, Sink},
+  home,
+  install,
+  integrity,
+  manifest::default_manifest_path,
+  node_control::{self, LogTailer, NodeProc},
+  presets,
+  pyenv,
+  resources::Resources,
+  runner,
+  selftest::{self, EVT_SELFTEST_CHECK},
+  settings::{ark_home, load_settings},
+  snapshots,
+  state::NodeBridge,
+  status,
+  subprocess,
+  versions,
+};
+use serde_json::{json, Value};
+use std::{process::ExitCode, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+const USAGE: &str = "\
+usage: arkdesk-cli [overrides] <command> [options]
+
+overrides (over ARK_HOME etc. and config.json):
+  --ark-home DIR  --run-dir DIR  --arkd FILE  --rpc-path PATH  --rpc-port N  --bin-dir DIR  --python EXE
+  --resources DIR  --integrity-pubkey HEX
+
+commands:
+  status                          node process, RPC and peer summary (JSON)
+  start [--profile NAME] [--auto-ports] [--detach] [-- ARGS...]
+                                  start arkd; supervises in the foreground unless --detach, and
+                                  stops it when free space drops below diskStopMib
+  stop                            stop the running arkd (admin shutdown, then signal)
+  logs [-n LINES] [--follow|-f]   print arkd log files under the Arknet home
+  rpc <method> [params-json]      JSON-RPC call against the running node
+  install                         install the bundled payload into the Arknet home (resumable)
+  selftest                        start arkd on scratch data and run the installed-node checks
+  uninstall [--dry-run] [--keep-data] [--keep-keys]
+                                  remove what ArkDesk installed, itemized with sizes (JSON)
+  reset <scope>... [--dry-run]    clear node data, keeping config and keys; scopes: chain, mempool,
+                                  peers, logs, pyenv, run (JSON)
+  disk                            sizes of bin, versions, data, logs, pyenv, snapshots and free
+                                  space on the data volume (JSON)
+  snapshot [create [--label L]|restore ID|verify ID|delete ID]
+                                  list, take or restore checksummed archives of data/; a running
+                                  arkd is shut down via the admin API and left stopped (JSON)
+  versions [activate VER|rollback]
+                                  list installed arkd versions, switch or roll back (JSON)
+  bundle import <file.zip>        install an offline update bundle as a new arkd version (JSON)
+  bundle create <out.zip> <resources-dir> --version V [--abi N] [--platform P] [--key FILE]
+                                  zip bin/<platform>, wheels/, bootstrap/ as an update bundle
+  bootstrap fetch [--url U] [--ref R] [--dir D]
+                                  check out Arknet sources at a ref (default main) (JSON)
+  bootstrap build [--dir D]       build the checkout and install it as an arkd version (JSON)
+  pyenv [verify|upgrade|repair]   inspect the managed ArkPy venv, check it against the lock file,
+                                  reinstall from the bundled wheels or rebuild it (JSON)
+  settings export <file.json> [--include-secrets]
+                                  write settings, launch profiles and endpoints to one file
+  settings import <file.json>     merge such a file into this machine's configuration (JSON)
+  home [set DIR [--no-migrate]]   show the Arknet home, or move it (and its content) to DIR (JSON)
+  config                          every layered setting and where its value came from (JSON)
+  integrity verify                check the bundled payload against its signed manifest (JSON)
+  integrity sign <dir> <keyfile>  write <dir>/integrity.json{,.sig} over bin/, wheels/, bootstrap/;
+                                  keyfile holds a hex ed25519 seed (openssl rand -hex 32)
+
+environment (same as the overrides):
+  ARKDESK_RESOURCES               bundled resources dir (default: ./resources next to the binary)
+  ARKDESK_INTEGRITY_PUBKEY        trusted signing key (hex); embedded at build time, runtime override in debug builds";
+
+/// Prints node log lines as-is and every other event as `event {json}` on stderr.
+struct TermSink;
+
+impl EventSink for TermSink {
+  fn emit_value(&self, event: &str, payload: Value) {
+    match event {
+      node_control::EVT_LOG => {
+        let stream = payload.get("stream").and_then(Value::as_str).unwrap_or("");
+        let line = payload.get("line").and_then(Value::as_str).unwrap_or("");
+        println!("[{stream}] {line}");
+      }
+      install::EVT_INSTALL_PROGRESS => {
+        let field = |k: &str| payload.get(k).and_then(Value::as_str).unwrap_or("").to_string();
+        let pct = payload.get("pct").and_then(Value::as_u64).unwrap_or(0);
+        let ms = payload.get("elapsedMs").and_then(Value::as_u64).unwrap_or(0);
+        match field("kind").as_str() {
+          "start" => eprintln!("install: [{pct:>3}%] {}", field("label")),
+          "finish" => eprintln!("install: [{pct:>3}%]   done in {:.1}s", ms as f64 / 1000.0),
+          "skip" => eprintln!("install: [{pct:>3}%] {}", field("label")),
+          "fail" => {
+            for l in payload.get("output").and_then(Value::as_array).into_iter().flatten() {
+              eprintln!("install:   | {}", l.as_str().unwrap_or(""));
+            }
+            eprintln!("install: FAILED {}: {}", field("label"), field("error"));
+          }
+          "summary" => eprintln!("install: {} ({:.1}s)", field("label"), ms as f64 / 1000.0),
+          _ => {}
+        }
+      }
+      subprocess::EVT_PROC_LOG => {
+        let field = |k: &str| payload.get(k).and_then(Value::as_str).unwrap_or("").to_string();
+        eprintln!("  {} | {}", field("id"), field("line"));
+      }
+      EVT_SELFTEST_CHECK => {
+        let field = |k: &str| payload.get(k).and_then(Value::as_str).unwrap_or("").to_string();
+        if field("state") != "running" {
+          eprintln!("selftest: {:<4} {} {}", field("state"), field("label"), field("detail"));
+        }
+      }
+      selftest::EVT_SELFTEST_DONE => {}
+      _ => eprintln!("{event} {payload}"),
+    }
+  }
+}
+
+fn print_json(v: &impl serde::Serialize) -> Result<(), String> {
+  println!("{}", serde_json::to_string_pretty(v).map_err(|e| e.to_string())?);
+  Ok(())
+}
+
+async fn cmd_status() -> Result<(), String> {
+  let probe = node_control::probe_external_node();
+  let st = status::get_status().await?;
+  let manifest = default_manifest_path();
+  print_json(&json!({
+    "process": probe,
+    "status": st,
+    "manifest": manifest.to_string_lossy(),
+    "manifestPresent": manifest.is_file(),
+  }))
+}
+
+async fn cmd_start(opts: &[String]) -> Result<(), String> {
+  let mut profile = None;
+  let mut auto_ports = false;
+  let mut detach = false;
+  let mut extra: Option<Vec<String>> = None;
+
+  let mut it = opts.iter();
+  while let Some(a) = it.next() {
+    match a.as_str() {
+      "--profile" => profile = Some(it.next().ok_or("--profile needs a name")?.clone()),
+      "--auto-ports" => auto_ports = true,
+      "--detach" => detach = true,
+      "--" => { extra = Some(it.by_ref().cloned().collect()); }
+      other => return Err(format!("start: unknown option {other}")),
+    }
+  }
+
+  let launch = node_control::launch_for(profile, extra)?;
+  let res = Resources::discover();
+  let proc = NodeProc::default();
+
+  if detach {
+    let log_file = ark_home().join("logs").join("arkd.out");
+    let pid = proc.start_detached(&TermSink, Some(&res), launch, auto_ports, &log_file).await?;
+    println!("arkd started pid={} log={}", pid.map(|p| p.to_string()).unwrap_or_else(|| "?".into()), log_file.display());
+    return Ok(());
+  }
+
+  let sink: Sink = Arc::new(TermSink);
+  proc.start(sink.clone(), Some(&res), launch, auto_ports).await?;
+  let mut disk_watch = disk::DiskWatch::default();
+  let mut disk_every = tokio::time::interval(disk::DISK_EVERY);
+  loop {
+    tokio::select! {
+      _ = disk_every.tick() => {
+        disk_watch.tick(&*sink, Some(&proc)).await;
+      }
+      _ = tokio::signal::ctrl_c() => {
+        proc.stop(Some(&*sink)).await?;
+        return Ok(());
+      }
+      _ = sleep(Duration::from_millis(500)) => {
+        if proc.pid().is_none() {
+          return Err("arkd exited".into());
+        }
+      }
+    }
+  }
+}
+
+async fn cmd_stop() -> Result<(), String> {
+  let bridge = NodeBridge::new(default_manifest_path());
+  let stopped = node_control::stop_external(&bridge, &ark_home()).await?;
+  if stopped.running {
+    println!("arkd stopped pid={}", stopped.pid.map(|p| p.to_string()).unwrap_or_else(|| "?".into()));
+  } else {
+    println!("arkd is not running");
+  }
+  Ok(())
+}
+
+async fn cmd_logs(opts: &[String]) -> Result<(), String> {
+  let mut lines = 200usize;
+  let mut follow = false;
+  let mut it = opts.iter();
+  while let Some(a) = it.next() {
+    match a.as_str() {
+      "-f" | "--follow" => follow = true,
+      "-n" => lines = it.next().and_then(|n| n.parse().ok()).ok_or("-n needs a number")?,
+      other => return Err(format!("logs: unknown option {other}")),
+    }
+  }
+
+  let dir = ark_home().join("logs");
+  let mut tailer = LogTailer::new(dir.clone());
+  let first = tailer.poll();
+  if first.is_empty() && !follow {
+    return Err(format!("no arkd logs in {}", dir.display()));
+  }
+  for (fname, line) in &first[first.len().saturating_sub(lines)..] {
+    println!("{fname}: {line}");
+  }
+  if !follow { return Ok(()); }
+
+  loop {
+    tokio::select! {
+      _ = tokio::signal::ctrl_c() => return Ok(()),
+      _ = sleep(Duration::from_millis(700)) => {
+        for (fname, line) in tailer.poll() { println!("{fname}: {line}"); }
+      }
+    }
+  }
+}
+
+async fn cmd_rpc(opts: &[String]) -> Result<(), String> {
+  let method = opts.first().ok_or("rpc: missing method")?;
+  let params: Value = match opts.get(1) {
+    Some(p) => serde_json::from_str(p).map_err(|e| format!("rpc: params are not JSON: {e}"))?,
+    None => json!({}),
+  };
+  let bridge = NodeBridge::new(default_manifest_path());
+  let rpc = bridge.rpc().map_err(|e| e.to_string())?;
+  let out = rpc.call_value(method, params).await.map_err(|e| format!("{e:#}"))?;
+  print_json(&out.result.unwrap_or(out.raw))
+}
+
+/// Ctrl-C cancels; finished steps stay staged and the next run resumes.
+async fn cmd_install() -> Result<(), String> {
+  let cancel = install::Cancel::default();
+  let on_interrupt = cancel.clone();
+  tokio::spawn(async move {
+    if tokio::signal::ctrl_c().await.is_ok() {
+      eprintln!("install: cancelling; rerun to resume");
+      on_interrupt.cancel();
+    }
+  });
+  install::install(&Resources::discover(), &TermSink, &cancel).await
+}
+
+/// Full report on stdout; fails if any check failed.
+async fn cmd_selftest() -> Result<(), String> {
+  let report = selftest::run(&Resources::discover(), &TermSink).await;
+  print_json(&report)?;
+  if report.ok { Ok(()) } else { Err("selftest failed".into()) }
+}
+
+async fn cmd_uninstall(opts: &[String]) -> Result<(), String> {
+  let mut plan = UninstallPlan::default();
+  for a in opts {
+    match a.as_str() {
+      "--dry-run" | "-n" => plan.dry_run = true,
+      "--keep-data" => plan.keep_data = true,
+      "--keep-keys" => plan.keep_keys = true,
+      other => return Err(format!("uninstall: unknown option {other}")),
+    }
+  }
+  // detached nodes are not supervised here; run_uninstall refuses while one runs from the home
+  print_json(&cleanup::run_uninstall(&plan, None, None).await?)
+}
+
+fn cmd_reset(opts: &[String]) -> Result<(), String> {
+  let mut scopes = Vec::new();
+  let mut dry_run = false;
+  for a in opts {
+    match a.as_str() {
+      "--dry-run" | "-n" => dry_run = true,
+      s => scopes.push(serde_json::from_value(json!(s)).map_err(|_| format!("reset: unknown scope {s}"))?),
+    }
+  }
+  // detached nodes are not supervised here; reset_data refuses while one runs from the home
+  print_json(&cleanup::reset_data(&scopes, dry_run, None)?)
+}
+
+async fn cmd_snapshot(opts: &[String]) -> Result<(), String> {
+  let home = ark_home();
+  let bridge = NodeBridge::new(default_manifest_path());
+  let sink: Sink = Arc::new(TermSink);
+  match (opts.first().map(String::as_str), opts.get(1)) {
+    (None | Some("list"), _) => print_json(&snapshots::list(&home)),
+    (Some("create"), label) => {
+      let label = match (label.map(String::as_str), opts.get(2)) {
+        (None, _) => None,
+        (Some("--label"), Some(l)) => Some(l.clone()),
+        (Some(other), _) => return Err(format!("snapshot create: unknown option {other}")),
+      };
+      // detached nodes are not supervised here; they stay stopped afterwards
+      print_json(&snapshots::create(label, None, &bridge, sink, None).await?)
+    }
+    (Some("restore"), Some(id)) => print_json(&snapshots::restore(id, None, sink, None).await?),
+    (Some("verify"), Some(id)) => {
+      let check = snapshots::verify(&home, id)?;
+      print_json(&check)?;
+      if check.ok { Ok(()) } else { Err(format!("snapshot {id} does not match its checksum")) }
+    }
+    (Some("delete"), Some(id)) => snapshots::delete(&home, id),
+    (Some("restore" | "verify" | "delete"), None) => Err("snapshot: missing id".into()),
+    (Some(other), _) => Err(format!("snapshot: unknown subcommand {other}")),
+  }
+}
+
+fn cmd_versions(opts: &[String]) -> Result<(), String> {
+  let home = ark_home();
+  match (opts.first().map(String::as_str), opts.get(1)) {
+    (None | Some("list"), _) => {}
+    (Some("activate"), Some(v)) => versions::activate(&home, v)?,
+    (Some("activate"), None) => return Err("versions activate: missing version".into()),
+    (Some("rollback"), _) => { versions::rollback(&home)?; }
+    (Some(other), _) => return Err(format!("versions: unknown subcommand {other}")),
+  }
+  print_json(&versions::list(&home, load_settings().keep_versions as usize))
+}
+
+async fn cmd_bundle(opts: &[String]) -> Result<(), String> {
+  match opts.first().map(String::as_str) {
+    Some("import") => {
+      let file = opts.get(1).ok_or("bundle import: missing file")?;
+      print_json(&bundle::import(file.as_ref(), &ark_home(), &install::Cancel::default()).await?)
+    }
+    Some("create") => {
+      let (Some(out), Some(res)) = (opts.get(1), opts.get(2)) else {
+        return Err("bundle create: usage: bundle create <out.zip> <resources-dir> --version V".into());
+      };
+      let (mut version, mut abi, mut platform, mut key) = (None, *bundle::SUPPORTED_ABI_REVS.end(), None, None);
+      let mut it = opts[3..].iter();
+      while let Some(a) = it.next() {
+        let mut val = || it.next().cloned().ok_or(format!("{a} needs a value"));
+        match a.as_str() {
+          "--version" => version = Some(val()?),
+          "--abi" => abi = val()?.parse().map_err(|_| "--abi needs a number")?,
+          "--platform" => platform = Some(val()?),
+          "--key" => key = Some(integrity::read_signing_key(val()?.as_ref())?),
+          other => return Err(format!("bundle create: unknown option {other}")),
+        }
+      }
+      let version = version.ok_or("bundle create: --version is required")?;
+      let platform = platform.unwrap_or_else(|| {
+        if cfg!(windows) { "windows" } else if cfg!(target_os = "macos") { "darwin" } else { "linux" }.to_string()
+      });
+      let meta = bundle::create(res.as_ref(), &version, abi, &platform, key.as_ref(), out.as_ref())?;
+      if key.is_none() { eprintln!("warning: unsigned bundle; only debug builds will import it"); }
+      println!("wrote {out} (arkd {} abiRev {} {})", meta.version, meta.abi_rev, meta.platform);
+      Ok(())
+    }
+    _ => Err("bundle: expected `import` or `create`".into()),
+  }
+}
+
+async fn cmd_bootstrap(opts: &[String]) -> Result<(), String> {
+  let (mut url, mut git_ref, mut dir) = (None, None, None);
+  let mut it = opts.iter().skip(1);
+  while let Some(a) = it.next() {
+    let mut val = || it.next().cloned().ok_or(format!("{a} needs a value"));
+    match a.as_str() {
+      "--url" => url = Some(val()?),
+      "--ref" => git_ref = Some(val()?),
+      "--dir" => dir = Some(val()?),
+      other => return Err(format!("bootstrap: unknown option {other}")),
+    }
+  }
+  let dir = dir.map(std::path::PathBuf::from).unwrap_or_else(runner::default_src_dir);
+  let cancel = install::Cancel::default();
+  match opts.first().map(String::as_str) {
+    Some("fetch") => {
+      let url = url.as_deref().unwrap_or(runner::DEFAULT_REPO);
+      print_json(&runner::fetch(url, git_ref.as_deref().unwrap_or(runner::DEFAULT_REF), &dir, &TermSink, &cancel).await?)
+    }
+    Some("build") => print_json(&runner::build(&dir, &ark_home(), &TermSink, &cancel).await?),
+    _ => Err("bootstrap: expected `fetch` or `build`".into()),
+  }
+}
+
+/// `verify` fails on any mismatch so it can gate scripts.
+async fn cmd_pyenv(opts: &[String]) -> Result<(), String> {
+  let (res, home, cancel) = (Resources::discover(), ark_home(), install::Cancel::default());
+  match opts.first().map(String::as_str) {
+    None | Some("inspect") => print_json(&pyenv::inspect(&home)),
+    Some("verify") => {
+      let report = pyenv::verify(&res, &home);
+      print_json(&report)?;
+      if report.ok { Ok(()) } else { Err("pyenv does not match the lock file".into()) }
+    }
+    Some("upgrade") => print_json(&pyenv::upgrade(&res, &home, &TermSink, &cancel).await?),
+    Some("repair") => print_json(&pyenv::repair(&res, &home, &TermSink, &cancel).await?),
+    Some(other) => Err(format!("pyenv: unknown subcommand {other}")),
+  }
+}
+
+fn cmd_settings(opts: &[String]) -> Result<(), String> {
+  match (opts.first().map(String::as_str), opts.get(1)) {
+    (Some("export"), Some(file)) => {
+      let secrets = match opts.get(2).map(String::as_str) {
+        None => false,
+        Some("--include-secrets") => true,
+        Some(other) => return Err(format!("settings export: unknown option {other}")),
+      };
+      let b = presets::export(file.as_ref(), secrets)?;
+      println!("wrote {file} ({} launch profile(s), {} endpoint(s){})",
+        b.launch_profiles.items.len(), b.endpoints.items.len(), if b.redacted { ", headers redacted" } else { "" });
+      Ok(())
+    }
+    (Some("import"), Some(file)) => print_json(&presets::import(file.as_ref())?),
+    (Some("export" | "import"), None) => Err("settings: missing file".into()),
+    _ => Err("settings: expected `export` or `import`".into()),
+  }
+}
+
+async fn cmd_home(opts: &[String]) -> Result<(), String> {
+  match (opts.first().map(String::as_str), opts.get(1)) {
+    (None, _) => print_json(&home::info()),
+    (Some("set"), Some(dir)) => {
+      let migrate = match opts.get(2).map(String::as_str) {
+        None => true,
+        Some("--no-migrate") => false,
+        Some(other) => return Err(format!("home set: unknown option {other}")),
+      };
+      // detached nodes are not supervised here; change refuses while one runs from the home
+      print_json(&home::change(dir.as_ref(), migrate, None, None).await?)
+    }
+    (Some("set"), None) => Err("home set: missing directory".into()),
+    (Some(other), _) => Err(format!("home: unknown subcommand {other}")),
+  }
+}
+
+fn cmd_integrity(opts: &[String]) -> Result<(), String> {
+  match opts.first().map(String::as_str) {
+    Some("verify") => {
+      let report = install::verify_payload(&Resources::discover());
+      print_json(&report)?;
+      if report.ok { Ok(()) } else { Err(format!("{} integrity issue(s)", report.issues.len())) }
+    }
+    Some("sign") => {
+      let (Some(dir), Some(keyfile)) = (opts.get(1), opts.get(2)) else {
+        return Err("integrity sign: usage: integrity sign <resources-dir> <keyfile>".into());
+      };
+      let key = integrity::read_signing_key(keyfile.as_ref())?;
+      let m = integrity::sign(dir.as_ref(), &["bin", "wheels", "bootstrap"], &key).map_err(|e| e.to_string())?;
+      println!("signed {} file(s) in {dir}", m.files.len());
+      println!("public key: {}", hex::encode(key.verifying_key().to_bytes()));
+      Ok(())
+    }
+    _ => Err("integrity: expected `verify` or `sign`".into()),
+  }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  let args = match config::take_flags(&args) {
+    Ok(rest) => rest,
+    Err(e) => { eprintln!("error: {e}"); return ExitCode::FAILURE; }
+  };
+  let Some((cmd, rest)) = args.split_first() else {
+    eprintln!("{USAGE}");
+    return ExitCode::FAILURE;
+  };
+
+  let res = match cmd.as_str() {
+    "status" => cmd_status().await,
+    "start" => cmd_start(rest).await,
+    "stop" => cmd_stop().await,
+    "logs" => cmd_logs(rest).await,
+    "rpc" => cmd_rpc(rest).await,
+    "install" => cmd_install().await,
+    "selftest" => cmd_selftest().await,
+    "uninstall" => cmd_uninstall(rest).await,
+    "reset" => cmd_reset(rest),
+    "snapshot" => cmd_snapshot(rest).await,
+    "disk" => print_json(&disk::usage(&ark_home())),
+    "versions" => cmd_versions(rest),
+    "bundle" => cmd_bundle(rest).await,
+    "bootstrap" => cmd_bootstrap(rest).await,
+    "pyenv" => cmd_pyenv(rest).await,
+    "settings" => cmd_settings(rest),
+    "home" => cmd_home(rest).await,
+    "config" => print_json(&config::effective()),
+    "integrity" => cmd_integrity(rest),
+    "help" | "-h" | "--help" => { println!("{USAGE}"); Ok(()) }
+    other => Err(format!("unknown command: {other}\n\n{USAGE}")),
+  };
+
+  match res {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => { eprintln!("error: {e}"); ExitCode::FAILURE }
+  }
+}
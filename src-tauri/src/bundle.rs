@@ -0,0 +1,296 @@
+// src/bundle.rs
+//! Offline update bundles: a zip carrying a new arkd/ArkPy payload for machines
+//! without network access.
+//!
+//! Layout (all paths `/`-separated):
+//!   bundle.json                  `BundleMeta`
+//!   integrity.json{,.sig}        signed manifest over everything below and bundle.json
+//!   bin/<platform>/...           arkd and friends, as in app resources
+//!   wheels/*.whl                 optional ArkPy wheels
+//!   bootstrap/requirements.lock.txt   optional
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use std::{
+  fs,
+  io::{self, Read, Write},
+  ops::RangeInclusive,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::cleanup;
+use crate::events::NullSink;
+use crate::install::{self, Cancel};
+use crate::integrity::{self, TrustPolicy, MANIFEST_FILE, SIG_FILE};
+use crate::pyenv;
+use crate::resources::Resources;
+use crate::settings::load_settings;
+use crate::subprocess::Transcript;
+use crate::versions::{self, VersionList};
+
+pub const META_FILE: &str = "bundle.json";
+const FORMAT_VERSION: u32 = 1;
+/// Admin/RPC ABI revisions this build can drive (`abiRev` in node.json / healthz).
+pub const SUPPORTED_ABI_REVS: RangeInclusive<u32> = 1..=1;
+/// Refuse archives that unpack to more than this.
+const MAX_UNPACKED: u64 = 2 << 30;
+const TOP_DIRS: &[&str] = &["bin", "wheels", "bootstrap"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleMeta {
+  pub format: u32,
+  pub version: String,
+  pub abi_rev: u32,
+  /// `bin/<platform>` directory name: linux, darwin or windows.
+  pub platform: String,
+  #[serde(default)]
+  pub created_at: u64,
+  #[serde(default)]
+  pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+  pub bundle: BundleMeta,
+  /// Installed (sanitized) version name, now current.
+  pub version: String,
+  pub signature_ok: bool,
+  pub arkpy_updated: bool,
+  pub notes: Vec<String>,
+  pub versions: VersionList,
+}
+
+fn now_ms() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+fn zip_err(e: zip::result::ZipError) -> String { format!("bad bundle: {e}") }
+
+/// Unpack `archive` into `dest`, rejecting paths that escape it or fall outside the layout.
+fn extract(archive: &Path, dest: &Path) -> Result<(), String> {
+  let file = fs::File::open(archive).map_err(|e| format!("{}: {e}", archive.display()))?;
+  let mut zip = ZipArchive::new(file).map_err(zip_err)?;
+
+  let mut total = 0u64;
+  for i in 0..zip.len() {
+    let mut entry = zip.by_index(i).map_err(zip_err)?;
+    let rel = entry.enclosed_name().ok_or_else(|| format!("bad bundle: unsafe path {}", entry.name()))?;
+    let top = rel.components().next().map(|c| c.as_os_str().to_string_lossy().to_string()).unwrap_or_default();
+    let allowed = TOP_DIRS.contains(&top.as_str()) || [META_FILE, MANIFEST_FILE, SIG_FILE].contains(&top.as_str());
+    if !allowed { return Err(format!("bad bundle: unexpected entry {}", entry.name())); }
+
+    let out = dest.join(&rel);
+    if entry.is_dir() {
+      fs::create_dir_all(&out).map_err(|e| e.to_string())?;
+      continue;
+    }
+    if let Some(parent) = out.parent() { fs::create_dir_all(parent).map_err(|e| e.to_string())?; }
+    let mut f = fs::File::create(&out).map_err(|e| format!("{}: {e}", out.display()))?;
+    // count what actually comes out, not the sizes the headers claim
+    let mut capped = entry.by_ref().take(MAX_UNPACKED - total + 1);
+    total += io::copy(&mut capped, &mut f).map_err(|e| format!("{}: {e}", out.display()))?;
+    if total > MAX_UNPACKED { return Err("bad bundle: unpacks to more than 2 GiB".into()); }
+    #[cfg(unix)]
+    if let Some(mode) = entry.unix_mode() {
+      use std::os::unix::fs::PermissionsExt;
+      fs::set_permissions(&out, fs::Permissions::from_mode(mode & 0o755)).map_err(|e| e.to_string())?;
+    }
+  }
+  Ok(())
+}
+
+fn read_meta(root: &Path) -> Result<BundleMeta, String> {
+  let bytes = fs::read(root.join(META_FILE)).map_err(|_| format!("bad bundle: {META_FILE} missing"))?;
+  let meta: BundleMeta = serde_json::from_slice(&bytes).map_err(|e| format!("bad bundle: {META_FILE}: {e}"))?;
+  if meta.format != FORMAT_VERSION {
+    return Err(format!("unsupported bundle format {} (expected {FORMAT_VERSION})", meta.format));
+  }
+  if !install::platform_dirs().contains(&meta.platform.as_str()) {
+    return Err(format!("bundle is for {}, this machine needs {}", meta.platform, install::platform_dirs()[0]));
+  }
+  if !SUPPORTED_ABI_REVS.contains(&meta.abi_rev) {
+    return Err(format!(
+      "bundle abiRev {} is not supported by this ArkDesk (supports {}..={})",
+      meta.abi_rev, SUPPORTED_ABI_REVS.start(), SUPPORTED_ABI_REVS.end()
+    ));
+  }
+  if versions::sanitize(&meta.version) != meta.version {
+    return Err(format!("bad bundle: version {:?} is not a valid name", meta.version));
+  }
+  Ok(meta)
+}
+
+/// Validate `archive` and install its payload into `home` as a new current version;
+/// ArkPy is upgraded from the bundled wheels in a copy of the venv that replaces
+/// the live one only once the upgrade succeeded.
+pub async fn import(archive: &Path, home: &Path, cancel: &Cancel) -> Result<ImportReport, String> {
+  fs::create_dir_all(versions::versions_dir(home)).map_err(|e| e.to_string())?;
+  let root = versions::versions_dir(home).join(format!(".import-{}", std::process::id()));
+  if root.exists() { fs::remove_dir_all(&root).map_err(|e| e.to_string())?; }
+  fs::create_dir_all(&root).map_err(|e| e.to_string())?;
+
+  let out = import_from(archive, &root, home, cancel).await;
+  let _ = fs::remove_dir_all(&root);
+  out
+}
+
+async fn import_from(archive: &Path, root: &Path, home: &Path, cancel: &Cancel) -> Result<ImportReport, String> {
+  {
+    let (archive, root) = (archive.to_path_buf(), root.to_path_buf());
+    install::blocking(move || extract(&archive, &root)).await?;
+  }
+  let meta = read_meta(root)?;
+  let src_bin = root.join("bin").join(&meta.platform);
+  if !src_bin.is_dir() { return Err(format!("bad bundle: bin/{} missing", meta.platform)); }
+
+  // the whole payload, bundle.json included, must match the signed manifest
+  let res = Resources::new(Some(root.to_path_buf()));
+  let wheels = root.join("wheels");
+  let mut files = vec![root.join(META_FILE)];
+  files.extend(install::bin_payload_files(&src_bin)?);
+  if wheels.is_dir() { files.extend(install::wheel_payload_files(&res, &wheels)); }
+  let report = integrity::verify(Some(root), &files, &TrustPolicy::for_build());
+  if !report.ok {
+    return Err(format!("Refusing to import bundle: integrity check failed: {}", report.describe()));
+  }
+
+  // ArkPy is upgraded in a copy of the venv, so nothing goes live until it succeeded
+  let mut notes = report.notes;
+  let live = pyenv::venv_dir(home);
+  let staged = root.join("pyenv");
+  let arkpy_updated = if !wheels.is_dir() {
+    false
+  } else if !install::py_in_venv(&live).is_file() {
+    notes.push("ArkPy venv not installed; bundled wheels skipped (run install first)".into());
+    false
+  } else {
+    {
+      let (live, staged) = (live.clone(), staged.clone());
+      install::blocking(move || cleanup::copy_tree(&live, &staged).map_err(|e| format!("copy {}: {e}", live.display()))).await?;
+    }
+    let py = install::py_in_venv(&staged);
+    install::install_arkpy(&res, &py, &NullSink, cancel, &mut Transcript::logged("bundle.arkPy", home)).await?;
+    true
+  };
+
+  cancel.check()?;
+  let old = root.join("pyenv.old");
+  if arkpy_updated { swap_dir(&live, &staged, &old)?; }
+  let version = {
+    let (res, src_bin, home, name) = (res.clone(), src_bin.clone(), home.to_path_buf(), meta.version.clone());
+    install::blocking(move || install::install_version_named(&res, &src_bin, &home, Some(&name))).await
+  };
+  let version = match version {
+    // put the previous venv back so the still-current version keeps its ArkPy
+    Err(e) if arkpy_updated => return Err(match swap_dir(&live, &old, &staged) {
+      Ok(()) => e,
+      Err(r) => format!("{e}; {r}"),
+    }),
+    v => v?,
+  };
+
+  Ok(ImportReport {
+    bundle: meta,
+    version,
+    signature_ok: report.signature_ok,
+    arkpy_updated,
+    notes,
+    versions: versions::list(home, load_settings().keep_versions as usize),
+  })
+}
+
+/// Replace `dest` with `with`, moving the current `dest` aside to `aside`.
+fn swap_dir(dest: &Path, with: &Path, aside: &Path) -> Result<(), String> {
+  if aside.exists() { fs::remove_dir_all(aside).map_err(|e| format!("remove {}: {e}", aside.display()))?; }
+  fs::rename(dest, aside).map_err(|e| format!("replace {} failed (in use?): {e}", dest.display()))?;
+  if let Err(e) = fs::rename(with, dest) {
+    let _ = fs::rename(aside, dest);
+    return Err(format!("move {} -> {} failed: {e}", with.display(), dest.display()));
+  }
+  Ok(())
+}
+
+/* ── BUILDING ────────────────────────────────────────────────────────────── */
+
+fn copy_tree(src: &Path, dst: &Path) -> io::Result<()> {
+  fs::create_dir_all(dst)?;
+  for e in fs::read_dir(src)? {
+    let e = e?;
+    let to = dst.join(e.file_name());
+    if e.file_type()?.is_dir() { copy_tree(&e.path(), &to)?; } else { fs::copy(e.path(), &to)?; }
+  }
+  Ok(())
+}
+
+fn zip_dir(root: &Path, out: &Path) -> Result<(), String> {
+  fn walk(dir: &Path, acc: &mut Vec<PathBuf>) -> io::Result<()> {
+    for e in fs::read_dir(dir)? {
+      let p = e?.path();
+      if p.is_dir() { walk(&p, acc)?; } else { acc.push(p); }
+    }
+    Ok(())
+  }
+  let mut files = Vec::new();
+  walk(root, &mut files).map_err(|e| e.to_string())?;
+  files.sort();
+
+  let mut zip = ZipWriter::new(fs::File::create(out).map_err(|e| format!("{}: {e}", out.display()))?);
+  for p in files {
+    let rel = p.strip_prefix(root).unwrap();
+    let name = rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+    let exec = rel.starts_with("bin");
+    let opts = SimpleFileOptions::default()
+      .compression_method(CompressionMethod::Deflated)
+      .unix_permissions(if exec { 0o755 } else { 0o644 });
+    zip.start_file(name, opts).map_err(zip_err)?;
+    zip.write_all(&fs::read(&p).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+  }
+  zip.finish().map_err(zip_err)?;
+  Ok(())
+}
+
+/// Build a bundle at `out` from a staged resources dir (`bin/<platform>`, `wheels/`,
+/// `bootstrap/`). Without `key` the bundle is unsigned and only imports into debug builds.
+pub fn create(resources: &Path, version: &str, abi_rev: u32, platform: &str, key: Option<&SigningKey>, out: &Path) -> Result<BundleMeta, String> {
+  let src_bin = resources.join("bin").join(platform);
+  if !src_bin.is_dir() { return Err(format!("{} missing", src_bin.display())); }
+
+  let work = out.with_extension("staging");
+  if work.exists() { fs::remove_dir_all(&work).map_err(|e| e.to_string())?; }
+  let built = (|| {
+    copy_tree(&src_bin, &work.join("bin").join(platform)).map_err(|e| e.to_string())?;
+    for d in ["wheels", "bootstrap"] {
+      if resources.join(d).is_dir() { copy_tree(&resources.join(d), &work.join(d)).map_err(|e| e.to_string())?; }
+    }
+    let meta = BundleMeta {
+      format: FORMAT_VERSION,
+      version: version.to_string(),
+      abi_rev,
+      platform: platform.to_string(),
+      created_at: now_ms(),
+      notes: None,
+    };
+    fs::write(work.join(META_FILE), serde_json::to_vec_pretty(&meta).unwrap()).map_err(|e| e.to_string())?;
+    if let Some(key) = key {
+      integrity::sign(&work, &[META_FILE, "bin", "wheels", "bootstrap"], key).map_err(|e| e.to_string())?;
+    }
+    zip_dir(&work, out)?;
+    Ok(meta)
+  })();
+  let _ = fs::remove_dir_all(&work);
+  built
+}
+
+/* ── COMMANDS ────────────────────────────────────────────────────────────── */
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn install_import_bundle(ctl: tauri::State<'_, install::InstallCtl>, path: String) -> Result<ImportReport, String> {
+  let cancel = ctl.begin()?;
+  let out = import(Path::new(&path), &crate::settings::ark_home(), &cancel).await;
+  ctl.finish();
+  out
+}
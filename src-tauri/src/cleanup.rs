@@ -1,10 +1,13 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::{
     fs,
     io,
     path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
+use crate::events::EventSink;
+use crate::node_control::{external_node_in, refuse_external_node, NodeProc};
 use crate::settings::ark_home;
 
 #[derive(Debug, Serialize)]
@@ -16,8 +19,30 @@ pub struct Skip { pub path: String, pub reason: String }
 pub struct CleanupReport {
     pub removed: Vec<String>,
     pub skipped: Vec<Skip>,
+    pub dry_run: bool,
+    /// Every file under the targets, with sizes.
+    pub files: Vec<FileEntry>,
+    /// Freed, or with `dry_run` to be freed.
+    pub bytes_freed: u64,
+    /// The `.tar.zst` written before deleting, if `backup` was asked for.
+    pub archive: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEntry { pub path: String, pub bytes: u64 }
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CleanupOptions {
+    /// Only report what would be removed.
+    pub dry_run: bool,
+    /// Archive the targets into this directory first; nothing is deleted if that fails.
+    pub backup: Option<String>,
+}
+
+// Clearing the read-only bit is the point here (Windows refuses to delete such files).
+#[allow(clippy::permissions_set_readonly_false)]
 fn clear_readonly(p: &Path) -> io::Result<()> {
     if let Ok(meta) = fs::symlink_metadata(p) {
         let mut perm = meta.permissions();
@@ -35,11 +60,103 @@ fn clear_readonly(p: &Path) -> io::Result<()> {
     Ok(())
 }
 
-fn remove_tree(p: &Path) -> Result<(), String> {
+pub(crate) fn remove_tree(p: &Path) -> Result<(), String> {
     clear_readonly(p).ok();
     fs::remove_dir_all(p).map_err(|e| format!("{}: {}", p.display(), e))
 }
 
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_link(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+/// Copy keeping symlinks as links (the venv's python is one).
+pub(crate) fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+    let meta = fs::symlink_metadata(from)?;
+    if meta.file_type().is_symlink() { return copy_link(from, to); }
+    if !meta.is_dir() { return fs::copy(from, to).map(|_| ()); }
+    fs::create_dir_all(to)?;
+    for e in fs::read_dir(from)? {
+        let e = e?;
+        copy_tree(&e.path(), &to.join(e.file_name()))?;
+    }
+    fs::set_permissions(to, meta.permissions())
+}
+
+fn list_files(p: &Path, out: &mut Vec<FileEntry>) {
+    let Ok(meta) = fs::symlink_metadata(p) else { return };
+    if !meta.is_dir() {
+        out.push(FileEntry { path: p.display().to_string(), bytes: meta.len() });
+        return;
+    }
+    let Ok(rd) = fs::read_dir(p) else { return };
+    let mut entries: Vec<PathBuf> = rd.flatten().map(|e| e.path()).collect();
+    entries.sort();
+    for e in entries { list_files(&e, out); }
+}
+
+/// Write `targets` (by their own names, symlinks kept as links) to
+/// `<dir>/arknet-<label>-<unix secs>.tar.zst`.
+pub(crate) fn archive(targets: &[PathBuf], dir: &Path, label: &str) -> Result<PathBuf, String> {
+    let io_err = |p: &Path, e: io::Error| format!("{}: {e}", p.display());
+    if !dir.is_absolute() {
+        return Err(format!("backup location {} is not an absolute path", dir.display()));
+    }
+    if let Some(t) = targets.iter().find(|t| dir.starts_with(t)) {
+        return Err(format!("backup location {} is inside {}, which is being deleted", dir.display(), t.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| io_err(dir, e))?;
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let out = dir.join(format!("arknet-{label}-{secs}.tar.zst"));
+    let part = out.with_extension("zst.part");
+
+    let write = || -> io::Result<()> {
+        let mut tar = tar::Builder::new(zstd::Encoder::new(fs::File::create(&part)?, 3)?);
+        tar.follow_symlinks(false);
+        for t in targets {
+            let name = t.file_name().unwrap_or(t.as_os_str());
+            if t.is_dir() { tar.append_dir_all(name, t)?; } else { tar.append_path_with_name(t, name)?; }
+        }
+        tar.into_inner()?.finish()?.sync_all()
+    };
+    if let Err(e) = write() {
+        let _ = fs::remove_file(&part);
+        return Err(format!("backup failed, nothing deleted: {}", io_err(&out, e)));
+    }
+    fs::rename(&part, &out).map_err(|e| io_err(&out, e))?;
+    Ok(out)
+}
+
+/// List, optionally archive, then remove `targets`.
+fn clean(targets: Vec<PathBuf>, skipped: Vec<Skip>, opts: &CleanupOptions, label: &str) -> Result<CleanupReport, String> {
+    let mut files = Vec::new();
+    for t in &targets { list_files(t, &mut files); }
+    let mut report = CleanupReport {
+        removed: Vec::new(),
+        skipped,
+        dry_run: opts.dry_run,
+        bytes_freed: files.iter().map(|f| f.bytes).sum(),
+        files,
+        archive: None,
+    };
+    if opts.dry_run || targets.is_empty() {
+        return Ok(report);
+    }
+    if let Some(dir) = opts.backup.as_deref().filter(|d| !d.trim().is_empty()) {
+        report.archive = Some(archive(&targets, Path::new(dir), label)?.display().to_string());
+    }
+    for t in targets {
+        remove_tree(&t)?;
+        report.removed.push(t.display().to_string());
+    }
+    Ok(report)
+}
+
 fn candidate_spurious_paths() -> Vec<PathBuf> {
     let mut v = Vec::new();
     if let Ok(cwd) = std::env::current_dir() {
@@ -54,9 +171,9 @@ fn candidate_spurious_paths() -> Vec<PathBuf> {
 /// Remove wrongly-created Arknet folders in the current working directory.
 /// - force=false: only if empty
 /// - force=true: remove recursively
-#[tauri::command]
-pub fn cleanup_spurious_dirs(force: bool) -> Result<CleanupReport, String> {
-    let mut removed = Vec::new();
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn cleanup_spurious_dirs(force: bool, opts: Option<CleanupOptions>) -> Result<CleanupReport, String> {
+    let mut targets = Vec::new();
     let mut skipped = Vec::new();
 
     for p in candidate_spurious_paths() {
@@ -66,36 +183,404 @@ pub fn cleanup_spurious_dirs(force: bool) -> Result<CleanupReport, String> {
             continue;
         }
         if !force {
-            match fs::read_dir(&p) {
-                Ok(mut it) => {
-                    if it.next().is_none() {
-                        remove_tree(&p)?;
-                        removed.push(p.display().to_string());
-                    } else {
-                        skipped.push(Skip { path: p.display().to_string(), reason: "not empty (use force)".into() });
-                    }
-                }
+            match fs::read_dir(&p).map(|mut it| it.next().is_none()) {
+                Ok(true) => targets.push(p),
+                Ok(false) => skipped.push(Skip { path: p.display().to_string(), reason: "not empty (use force)".into() }),
                 Err(e) => skipped.push(Skip { path: p.display().to_string(), reason: e.to_string() }),
             }
         } else {
-            remove_tree(&p)?;
-            removed.push(p.display().to_string());
+            targets.push(p);
         }
     }
 
-    Ok(CleanupReport { removed, skipped })
+    clean(targets, skipped, &opts.unwrap_or_default(), "spurious")
 }
 
-/// Wipe the real Arknet home directory after an explicit confirmation token.
-#[tauri::command]
-pub fn wipe_ark_home(confirm: String) -> Result<CleanupReport, String> {
-    if confirm != "ARKNET-NUKE" {
+/// Wipe the real Arknet home directory after an explicit confirmation token
+/// (a `dry_run` needs none).
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn wipe_ark_home(confirm: String, opts: Option<CleanupOptions>) -> Result<CleanupReport, String> {
+    let opts = opts.unwrap_or_default();
+    if confirm != "ARKNET-NUKE" && !opts.dry_run {
         return Err("confirmation token mismatch".into());
     }
     let home = ark_home();
     if !home.exists() {
-        return Ok(CleanupReport { removed: vec![], skipped: vec![Skip{ path: home.display().to_string(), reason: "not found".into() }] });
+        let skipped = vec![Skip { path: home.display().to_string(), reason: "not found".into() }];
+        return clean(Vec::new(), skipped, &opts, "home");
+    }
+    // a half-finished install would otherwise resume into the wiped home
+    let stage = crate::install::staging_root(&home);
+    let targets = [home, stage].into_iter().filter(|p| p.exists()).collect();
+    clean(targets, Vec::new(), &opts, "home")
+}
+
+/* ── UNINSTALL ───────────────────────────────────────────────────────────── */
+
+/// Tauri app identifier; the app config dir (endpoints.json, the chosen home) is named after it.
+pub(crate) const APP_IDENTIFIER: &str = "io.arknet.arkdesk";
+/// Directory names treated as key material wherever they appear under data/var.
+const KEY_DIR_NAMES: &[&str] = &["keys", "keystore"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemKind { Binaries, Python, Staging, Logs, Data, Keys, Config }
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallPlan {
+    /// Only report what would happen.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Keep chain data (`data/`, `var/`).
+    #[serde(default)]
+    pub keep_data: bool,
+    /// Keep key directories, including those inside data.
+    #[serde(default)]
+    pub keep_keys: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallItem {
+    pub kind: ItemKind,
+    pub path: String,
+    /// For data dirs with kept keys inside, excludes the keys.
+    pub bytes: u64,
+    pub keep: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallReport {
+    pub dry_run: bool,
+    pub home: String,
+    /// Pid of the supervised node that was (or would be) stopped first.
+    pub stopped_node: Option<u32>,
+    pub items: Vec<UninstallItem>,
+    pub remove_bytes: u64,
+    pub keep_bytes: u64,
+    pub removed: Vec<String>,
+    /// Entries ArkDesk did not create, and removals that failed.
+    pub skipped: Vec<Skip>,
+    pub home_removed: bool,
+}
+
+/// `<config dir>/io.arknet.arkdesk/endpoints.json`, same place Tauri's `app_config_dir` resolves to.
+pub fn app_endpoints_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join(APP_IDENTIFIER).join("endpoints.json"))
+}
+
+pub(crate) fn tree_size(p: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(p) else { return 0 };
+    if !meta.is_dir() { return meta.len(); }
+    fs::read_dir(p).map(|rd| rd.flatten().map(|e| tree_size(&e.path())).sum()).unwrap_or(0)
+}
+
+fn find_key_dirs(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    let Ok(rd) = fs::read_dir(dir) else { return };
+    for e in rd.flatten() {
+        let p = e.path();
+        if !e.file_type().map(|t| t.is_dir()).unwrap_or(false) { continue; }
+        let name = e.file_name().to_string_lossy().to_lowercase();
+        if KEY_DIR_NAMES.contains(&name.as_str()) {
+            out.push(p);
+        } else if depth > 0 {
+            find_key_dirs(&p, depth - 1, out);
+        }
+    }
+}
+
+fn remove_path(p: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(p).map(|m| m.is_dir()).unwrap_or(false) {
+        remove_tree(p)
+    } else {
+        clear_readonly(p).ok();
+        fs::remove_file(p).map_err(|e| format!("{}: {}", p.display(), e))
+    }
+}
+
+/// Remove everything under `dir` except `keep` paths (and the dirs leading to them).
+fn remove_except(dir: &Path, keep: &[PathBuf]) -> Result<(), String> {
+    if !keep.iter().any(|k| k.starts_with(dir)) { return remove_path(dir); }
+    if keep.iter().any(|k| k == dir) { return Ok(()); }
+    for e in fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?.flatten() {
+        remove_except(&e.path(), keep)?;
+    }
+    Ok(())
+}
+
+/// Classify what ArkDesk created under `home`; anything else is reported, not touched.
+fn plan_items(home: &Path, plan: &UninstallPlan) -> (Vec<(UninstallItem, PathBuf)>, Vec<Skip>) {
+    let mut key_dirs = Vec::new();
+    if home.join("keys").is_dir() { key_dirs.push(home.join("keys")); }
+    for d in ["data", "var"] { find_key_dirs(&home.join(d), 4, &mut key_dirs); }
+
+    let mut items = Vec::new();
+    let mut push = |kind: ItemKind, p: PathBuf, bytes: u64| {
+        let keep = match kind {
+            ItemKind::Data => plan.keep_data,
+            ItemKind::Keys => plan.keep_keys,
+            _ => false,
+        };
+        items.push((UninstallItem { kind, path: p.display().to_string(), bytes, keep }, p));
+    };
+
+    let mut skipped = Vec::new();
+    if let Ok(rd) = fs::read_dir(home) {
+        let mut entries: Vec<PathBuf> = rd.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for p in entries {
+            let name = p.file_name().unwrap_or_default().to_string_lossy().to_string();
+            match name.as_str() {
+                "versions" | "bin" => push(ItemKind::Binaries, p.clone(), tree_size(&p)),
+                "pyenv" => push(ItemKind::Python, p.clone(), tree_size(&p)),
+                "logs" => push(ItemKind::Logs, p.clone(), tree_size(&p)),
+                "snapshots" => push(ItemKind::Data, p.clone(), tree_size(&p)),
+                "keys" => {}
+                "data" | "var" => {
+                    let inside: u64 = key_dirs.iter().filter(|k| k.starts_with(&p)).map(|k| tree_size(k)).sum();
+                    push(ItemKind::Data, p.clone(), tree_size(&p) - inside);
+                }
+                n if n.starts_with("config.json") || n.starts_with("profiles.json") => {
+                    push(ItemKind::Config, p.clone(), tree_size(&p))
+                }
+                _ => skipped.push(Skip { path: p.display().to_string(), reason: "not created by ArkDesk".into() }),
+            }
+        }
+    }
+    for k in &key_dirs { push(ItemKind::Keys, k.clone(), tree_size(k)); }
+
+    let stage = crate::install::staging_root(home);
+    if stage.exists() { push(ItemKind::Staging, stage.clone(), tree_size(&stage)); }
+    for p in [app_endpoints_path(), crate::home::saved_home_path()].into_iter().flatten().filter(|p| p.is_file()) {
+        push(ItemKind::Config, p.clone(), tree_size(&p));
+    }
+    (items, skipped)
+}
+
+/// Remove what ArkDesk put on this machine, itemized and sized; with `dry_run` only report.
+/// Stops the supervised node first and refuses while an arkd from this home is still running.
+pub async fn run_uninstall(plan: &UninstallPlan, proc: Option<&NodeProc>, sink: Option<&dyn EventSink>) -> Result<UninstallReport, String> {
+    let home = ark_home();
+    // check before planning: probing reads settings, which may set a broken config.json aside
+    let stopped_node = proc.and_then(NodeProc::pid);
+    if !plan.dry_run {
+        refuse_external_node(&home)?;
+        if let (Some(proc), Some(_)) = (proc, stopped_node) {
+            proc.stop(sink).await?;
+        }
+    }
+    let (items, mut skipped) = plan_items(&home, plan);
+
+    let kept_keys: Vec<PathBuf> = items.iter()
+        .filter(|(i, _)| i.kind == ItemKind::Keys && i.keep)
+        .map(|(_, p)| p.clone())
+        .collect();
+    let mut removed = Vec::new();
+    if !plan.dry_run {
+        for (item, p) in items.iter().filter(|(i, _)| !i.keep && i.kind != ItemKind::Data) {
+            // keys inside data/ go here too, before their parent
+            if !p.exists() { continue; }
+            match remove_path(p) {
+                Ok(()) => removed.push(item.path.clone()),
+                Err(e) => skipped.push(Skip { path: item.path.clone(), reason: e }),
+            }
+        }
+        for (item, p) in items.iter().filter(|(i, _)| !i.keep && i.kind == ItemKind::Data) {
+            match remove_except(p, &kept_keys) {
+                Ok(()) => removed.push(item.path.clone()),
+                Err(e) => skipped.push(Skip { path: item.path.clone(), reason: e }),
+            }
+        }
+        let empty = fs::read_dir(&home).map(|mut rd| rd.next().is_none()).unwrap_or(false);
+        if empty { let _ = fs::remove_dir(&home); }
+    }
+
+    let (keep, remove): (Vec<&UninstallItem>, Vec<&UninstallItem>) = items.iter().map(|(i, _)| i).partition(|i| i.keep);
+    Ok(UninstallReport {
+        dry_run: plan.dry_run,
+        home: home.display().to_string(),
+        stopped_node,
+        remove_bytes: remove.iter().map(|i| i.bytes).sum(),
+        keep_bytes: keep.iter().map(|i| i.bytes).sum(),
+        items: items.into_iter().map(|(i, _)| i).collect(),
+        removed,
+        skipped,
+        home_removed: !plan.dry_run && !home.exists(),
+    })
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn uninstall(
+    app: tauri::AppHandle,
+    proc: tauri::State<'_, NodeProc>,
+    plan: UninstallPlan,
+) -> Result<UninstallReport, String> {
+    run_uninstall(&plan, Some(&*proc), Some(&app)).await
+}
+
+/* ── RESET ───────────────────────────────────────────────────────────────── */
+
+/// What `reset_node_data` can clear; config, profiles, binaries and keys are never touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataScope {
+    /// Blocks and state: everything under `data/` except keys, mempool and peers.
+    Chain,
+    /// `mempool*` under `data/` and `var/`.
+    Mempool,
+    /// `peers*`, `addrbook*`, `banlist*` under `data/` and `var/`.
+    Peers,
+    /// Contents of `logs/`.
+    Logs,
+    /// The managed ArkPy venv.
+    Pyenv,
+    /// Contents of `var/<net>/run` (node.json, pid files).
+    #[serde(alias = "manifest")]
+    Run,
+}
+
+const PEER_PREFIXES: &[&str] = &["peers", "addrbook", "banlist"];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetItem { pub scope: DataScope, pub path: String, pub bytes: u64 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetReport {
+    pub dry_run: bool,
+    pub home: String,
+    /// Pid of a running arkd using this home; a dry run reports it, a reset refuses.
+    pub running_node: Option<u32>,
+    pub items: Vec<ResetItem>,
+    /// Freed, or with `dry_run` to be freed.
+    pub bytes_freed: u64,
+    pub removed: Vec<String>,
+    pub skipped: Vec<Skip>,
+}
+
+fn sorted_entries(dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(rd) = fs::read_dir(dir) else { return Vec::new() };
+    let mut v: Vec<(PathBuf, String)> = rd.flatten().map(|e| (e.path(), e.file_name().to_string_lossy().to_lowercase())).collect();
+    v.sort();
+    v
+}
+
+/// Mempool or peers by name; `None` for anything else.
+fn named_scope(name: &str) -> Option<DataScope> {
+    if name.starts_with("mempool") { return Some(DataScope::Mempool); }
+    PEER_PREFIXES.iter().any(|p| name.starts_with(p)).then_some(DataScope::Peers)
+}
+
+/// Key dirs or other scopes' entries somewhere below `dir`.
+fn holds_protected(dir: &Path, depth: usize) -> bool {
+    sorted_entries(dir).iter().any(|(p, name)| {
+        KEY_DIR_NAMES.contains(&name.as_str()) || named_scope(name).is_some() || (depth > 0 && p.is_dir() && holds_protected(p, depth - 1))
+    })
+}
+
+/// Chain data under `dir`: whole subtrees where possible, around keys and other scopes.
+fn chain_items(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    for (p, name) in sorted_entries(dir) {
+        if KEY_DIR_NAMES.contains(&name.as_str()) || named_scope(&name).is_some() { continue; }
+        if p.is_dir() && depth > 0 && holds_protected(&p, depth - 1) {
+            chain_items(&p, depth - 1, out);
+        } else {
+            out.push(p);
+        }
+    }
+}
+
+/// Entries under `dir` named for `scope`, never looking inside key dirs.
+fn named_items(dir: &Path, scope: DataScope, depth: usize, out: &mut Vec<PathBuf>) {
+    for (p, name) in sorted_entries(dir) {
+        if KEY_DIR_NAMES.contains(&name.as_str()) { continue; }
+        if named_scope(&name) == Some(scope) {
+            out.push(p);
+        } else if depth > 0 && p.is_dir() {
+            named_items(&p, scope, depth - 1, out);
+        }
     }
-    remove_tree(&home)?;
-    Ok(CleanupReport { removed: vec![home.display().to_string()], skipped: vec![] })
+}
+
+fn scope_items(home: &Path, scope: DataScope) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let children = |dir: PathBuf| sorted_entries(&dir).into_iter().map(|(p, _)| p).collect::<Vec<_>>();
+    match scope {
+        DataScope::Chain => chain_items(&home.join("data"), 4, &mut out),
+        DataScope::Mempool | DataScope::Peers => {
+            for d in ["data", "var"] { named_items(&home.join(d), scope, 4, &mut out); }
+        }
+        DataScope::Logs => out = children(home.join("logs")),
+        DataScope::Pyenv => out.extend(Some(home.join("pyenv")).filter(|p| p.exists())),
+        DataScope::Run => {
+            for (net, _) in sorted_entries(&home.join("var")) { out.extend(children(net.join("run"))); }
+        }
+    }
+    out
+}
+
+/// Clear `scopes` from the Arknet home, itemized and sized; with `dry_run` only
+/// report. Refuses while the supervised node, or an arkd from this home, runs.
+pub fn reset_data(scopes: &[DataScope], dry_run: bool, proc: Option<&NodeProc>) -> Result<ResetReport, String> {
+    let home = ark_home();
+    if scopes.is_empty() {
+        return Err("nothing to reset: pick at least one scope".into());
+    }
+    let supervised = proc.and_then(NodeProc::pid);
+    if !dry_run {
+        if let Some(pid) = supervised {
+            return Err(format!("arkd (pid {pid}) is running; stop the node before resetting its data"));
+        }
+        refuse_external_node(&home)?;
+    }
+    let running_node = supervised.or_else(|| external_node_in(&home));
+
+    let mut scopes = scopes.to_vec();
+    scopes.sort();
+    scopes.dedup();
+    let mut items = Vec::new();
+    for &scope in &scopes {
+        for p in scope_items(&home, scope) {
+            items.push((ResetItem { scope, path: p.display().to_string(), bytes: tree_size(&p) }, p));
+        }
+    }
+
+    let (mut removed, mut skipped) = (Vec::new(), Vec::new());
+    if !dry_run {
+        for (item, p) in &items {
+            match remove_path(p) {
+                Ok(()) => removed.push(item.path.clone()),
+                Err(e) => skipped.push(Skip { path: item.path.clone(), reason: e }),
+            }
+        }
+    }
+    let bytes_freed = items.iter().filter(|(i, _)| dry_run || removed.contains(&i.path)).map(|(i, _)| i.bytes).sum();
+    Ok(ResetReport {
+        dry_run,
+        home: home.display().to_string(),
+        running_node,
+        items: items.into_iter().map(|(i, _)| i).collect(),
+        bytes_freed,
+        removed,
+        skipped,
+    })
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn reset_node_data(
+    proc: tauri::State<'_, NodeProc>,
+    ctl: tauri::State<'_, crate::install::InstallCtl>,
+    scopes: Vec<DataScope>,
+    dry_run: Option<bool>,
+) -> Result<ResetReport, String> {
+    // the installer writes pyenv and logs; don't pull them out from under it
+    ctl.begin()?;
+    let r = reset_data(&scopes, dry_run.unwrap_or(false), Some(&*proc));
+    ctl.finish();
+    r
 }
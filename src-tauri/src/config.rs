@@ -0,0 +1,202 @@
+// src/config.rs
+//! Layered configuration: built-in defaults, then `config.json` (or, for the
+//! home itself, ArkDesk's saved choice), then the environment, then
+//! command-line flags. Code that used to read these env vars directly asks
+//! here, so every module agrees on the same value.
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::RwLock};
+
+use crate::home;
+use crate::integrity;
+use crate::manifest::default_run_dir;
+use crate::settings::{ark_home, default_ark_home, load_settings, Settings};
+
+/* ── KEYS ───────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Key {
+  /// Arknet home: config.json, installed binaries, data.
+  ArkHome,
+  /// Where arkd writes node.json.
+  RunDir,
+  /// arkd executable to launch instead of the installed one.
+  Arkd,
+  /// JSON-RPC path on the node's RPC port.
+  RpcPath,
+  RpcPort,
+  /// Directory to install arkd from instead of the bundled resources.
+  BinDir,
+  /// Python used to create the ArkPy venv.
+  Python,
+  /// Bundled resources dir for headless runs.
+  Resources,
+  /// Trusted payload signing key (hex); runtime values only count in debug builds.
+  IntegrityPubkey,
+}
+
+/// (key, env var, CLI flag, `config.json` field).
+const KEYS: &[(Key, &str, &str, Option<&str>)] = &[
+  (Key::ArkHome, "ARK_HOME", "--ark-home", None),
+  (Key::RunDir, "ARK_RUN_DIR", "--run-dir", None),
+  (Key::Arkd, "ARK_ARKD", "--arkd", None),
+  (Key::RpcPath, "ARK_RPC_PATH", "--rpc-path", None),
+  (Key::RpcPort, "ARK_RPC_PORT", "--rpc-port", Some("rpcPort")),
+  (Key::BinDir, "ARKDESK_BIN_DIR", "--bin-dir", None),
+  (Key::Python, "ARKDESK_PY", "--python", None),
+  (Key::Resources, "ARKDESK_RESOURCES", "--resources", None),
+  (Key::IntegrityPubkey, "ARKDESK_INTEGRITY_PUBKEY", "--integrity-pubkey", None),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+  Default,
+  /// ArkDesk's own config dir (the chosen Arknet home, see `home`).
+  App,
+  File,
+  Env,
+  Cli,
+}
+
+/// A resolved value and the layer it came from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+  pub key: Key,
+  /// `None`: unset, the consumer searches its usual places.
+  pub value: Option<String>,
+  pub source: Source,
+  pub env: &'static str,
+  pub flag: &'static str,
+  /// Higher-layer values that were set but unusable.
+  pub ignored: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+  pub config_file: String,
+  pub entries: Vec<Entry>,
+}
+
+/* ── LAYERS ─────────────────────────────────────────────────────────────── */
+
+static CLI: RwLock<BTreeMap<Key, String>> = RwLock::new(BTreeMap::new());
+
+/// Take leading `--flag value` / `--flag=value` overrides off `args`, install
+/// them as the CLI layer and return the rest. Stops at the first argument that
+/// is not one of the flags.
+pub fn take_flags(args: &[String]) -> Result<&[String], String> {
+  let mut flags = BTreeMap::new();
+  let mut i = 0;
+  while let Some(a) = args.get(i) {
+    let (flag, inline) = a.split_once('=').map_or((a.as_str(), None), |(f, v)| (f, Some(v.to_string())));
+    let Some(&(key, ..)) = KEYS.iter().find(|k| k.2 == flag) else { break };
+    let value = match inline {
+      Some(v) => v,
+      None => {
+        i += 1;
+        args.get(i).cloned().ok_or(format!("{flag} needs a value"))?
+      }
+    };
+    flags.insert(key, value);
+    i += 1;
+  }
+  set_cli(flags);
+  Ok(&args[i..])
+}
+
+/// Replace the CLI layer.
+pub fn set_cli(flags: BTreeMap<Key, String>) {
+  *CLI.write().unwrap() = flags;
+}
+
+fn usable(key: Key, v: &str) -> bool {
+  match key {
+    Key::RpcPort => v.parse::<u16>().is_ok_and(|p| p != 0),
+    Key::IntegrityPubkey => cfg!(debug_assertions) && integrity::parse_pubkey(v).is_some(),
+    _ => !v.trim().is_empty(),
+  }
+}
+
+/// The field as written in `config.json`, if the file sets it.
+fn file_value(field: &str) -> Option<String> {
+  let bytes = fs::read(ark_home().join("config.json")).ok()?;
+  let v: Value = serde_json::from_slice(&bytes).ok()?;
+  match v.get(field)? {
+    Value::String(s) => Some(s.clone()),
+    other => Some(other.to_string()),
+  }
+}
+
+fn default_value(key: Key) -> Option<String> {
+  let path = |p: PathBuf| Some(p.to_string_lossy().into_owned());
+  match key {
+    Key::ArkHome => path(default_ark_home()),
+    Key::RunDir => path(default_run_dir()),
+    Key::RpcPath => Some("/".into()),
+    Key::RpcPort => Some(Settings::default().rpc_port.to_string()),
+    // the key embedded at build time
+    Key::IntegrityPubkey => option_env!("ARKDESK_INTEGRITY_PUBKEY").map(str::to_string),
+    Key::Arkd | Key::BinDir | Key::Python | Key::Resources => None,
+  }
+}
+
+pub fn resolve(key: Key) -> Entry {
+  let &(_, env, flag, field) = KEYS.iter().find(|k| k.0 == key).unwrap();
+  let mut ignored = Vec::new();
+  let mut layers = vec![(Source::Cli, CLI.read().unwrap().get(&key).cloned(), flag)];
+  layers.push((Source::Env, std::env::var(env).ok(), env));
+  if let Some(field) = field {
+    layers.push((Source::File, file_value(field), field));
+  }
+  if key == Key::ArkHome {
+    layers.push((Source::App, home::saved_home().map(|p| p.to_string_lossy().into_owned()), home::HOME_FILE));
+  }
+  for (source, value, name) in layers {
+    let Some(v) = value else { continue };
+    if usable(key, &v) {
+      return Entry { key, value: Some(v), source, env, flag, ignored };
+    }
+    ignored.push(format!("{name}={v:?}"));
+  }
+  Entry { key, value: default_value(key), source: Source::Default, env, flag, ignored }
+}
+
+/* ── ACCESSORS ──────────────────────────────────────────────────────────── */
+
+pub fn get(key: Key) -> Option<String> {
+  resolve(key).value
+}
+
+pub fn path(key: Key) -> Option<PathBuf> {
+  get(key).map(PathBuf::from)
+}
+
+/// Saved settings with the env/CLI overrides applied: what the node is launched
+/// and probed with. Save paths keep using `load_settings`.
+pub fn settings() -> Settings {
+  let mut s = load_settings();
+  let port = resolve(Key::RpcPort);
+  if matches!(port.source, Source::Env | Source::Cli) {
+    s.rpc_port = port.value.and_then(|v| v.parse().ok()).unwrap_or(s.rpc_port);
+  }
+  s
+}
+
+pub fn effective() -> EffectiveConfig {
+  EffectiveConfig {
+    config_file: ark_home().join("config.json").to_string_lossy().into_owned(),
+    entries: KEYS.iter().map(|k| resolve(k.0)).collect(),
+  }
+}
+
+/* ── COMMANDS ───────────────────────────────────────────────────────────── */
+
+/// Every layered value and the layer it came from.
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn effective_config() -> Result<EffectiveConfig, String> {
+  Ok(effective())
+}
@@ -0,0 +1,153 @@
+// src/desktop.rs
+//! Tauri glue: command registration, poller startup and the app builder.
+use crate::cleanup::{cleanup_spurious_dirs, reset_node_data, uninstall, wipe_ark_home};
+use crate::install::{install_preflight, install_arknet_progress, install_cancel, reveal_ark_home};
+use crate::selftest::install_selftest;
+use crate::settings::{get_settings, save_settings, probe_install, install_arknet};
+use crate::status::get_status;
+use crate::validate::{validate_rpc, validate_settings};
+use crate::{bundle, config, disk, home, install, miner, node_control, presets, profiles, pyenv, reconfig, runner, snapshots, state, versions};
+
+use crate::manifest::{default_manifest_path, read_manifest};
+use crate::state::NodeBridge;
+use crate::events::Sink;
+use crate::pollers::{self, Stamps};
+
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::{Manager, State};
+
+/* ---- generic RPC passthrough used by UI ---- */
+
+#[tauri::command]
+async fn rpc_call(
+  bridge: State<'_, state::NodeBridge>,
+  method: String,
+  params: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+  // Ensure we’re using the freshest endpoint/headers
+  bridge.maybe_refresh();
+
+  let rpc = bridge.rpc().map_err(|e| e.to_string())?;
+  // Return full chain of errors for proper diagnostics
+  let out = rpc
+    .call_value(&method, params)
+    .await
+    .map_err(|e| format!("{:?}", e))?;
+
+  Ok(out.result.unwrap_or(out.raw))
+}
+
+#[tauri::command]
+async fn rpc_tx_lookup(state: State<'_, NodeBridge>, id: String) -> Result<Value, String> {
+  let rpc = state.rpc().map_err(|e| e.to_string())?;
+  let v: Value = rpc
+    .call("tx.get", json!({ "id": id }))
+    .await
+    .map_err(|e| e.to_string())?;
+  Ok(json!({ "raw": v, "id": id }))
+}
+
+/* ---- tauri bootstrap ---- */
+pub fn run() {
+  // leading `--ark-home DIR`-style flags override config.json and the environment
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  if let Err(e) = config::take_flags(&args) { eprintln!("arkdesk: {e}"); }
+
+  tauri::Builder::default()
+    .plugin(tauri_plugin_shell::init())
+    .plugin(tauri_plugin_dialog::init())
+    // .plugin(tauri_plugin_opener::init())
+    .setup(|app| {
+      // node process supervisor
+      app.manage(node_control::NodeProc::default());
+      // one installer run at a time, cancellable
+      app.manage(install::InstallCtl::default());
+
+      // resolve manifest path & bridge
+      let manifest_path = default_manifest_path();
+      let bridge = NodeBridge::new(manifest_path.clone());
+
+      if let Ok(m) = read_manifest(&manifest_path) {
+        bridge.update_manifest(m);
+      }
+
+      // share bridge
+      app.manage(bridge.clone());
+
+      // background workers
+      let stamps = Arc::new(Stamps::default());
+      let sink: Sink = Arc::new(app.handle().clone());
+
+      tauri::async_runtime::spawn(pollers::status_poller(sink.clone(), bridge.clone(), stamps.clone()));
+      tauri::async_runtime::spawn(pollers::rpc_poller(sink.clone(), bridge.clone(), stamps.clone()));
+      tauri::async_runtime::spawn(pollers::stale_emitter(sink.clone(), stamps));
+
+      // low-disk watchdog on the data volume; stops the supervised node when critical
+      let handle = app.handle().clone();
+      tauri::async_runtime::spawn(async move {
+        let proc = handle.state::<node_control::NodeProc>();
+        disk::disk_watchdog(sink, Some(&*proc)).await
+      });
+
+      Ok(())
+    })
+    .invoke_handler(tauri::generate_handler![
+      // settings / install
+      get_settings, save_settings, probe_install, install_arknet,
+      home::ark_home_info, home::ark_home_set,
+      reconfig::settings_preview, reconfig::settings_apply,
+      presets::settings_export, presets::settings_import,
+      // status / validate
+      get_status, validate_settings, validate_rpc, config::effective_config,
+      // cleanup
+      cleanup_spurious_dirs, wipe_ark_home, uninstall, reset_node_data,
+      // disk usage
+      disk::disk_usage,
+      // data snapshots
+      snapshots::snapshot_list,
+      snapshots::snapshot_create,
+      snapshots::snapshot_restore,
+      snapshots::snapshot_verify,
+      snapshots::snapshot_delete,
+      // installer
+      install_preflight, install_arknet_progress, install_cancel, reveal_ark_home, install_selftest,
+      versions::install_list_versions,
+      versions::install_activate,
+      versions::install_rollback,
+      bundle::install_import_bundle,
+      // build from source
+      runner::ark_bootstrap_fetch,
+      runner::ark_bootstrap_build,
+      // miner detection / setup
+      miner::host_probe,
+      miner::install_arkpy,
+      miner::locate_python,
+      miner::probe_bundled_clib,
+      // managed ArkPy venv
+      pyenv::pyenv_inspect,
+      pyenv::pyenv_verify,
+      pyenv::pyenv_repair,
+      pyenv::pyenv_upgrade,
+      // node control (start/stop/logs/etc.)
+      node_control::node_is_running,
+      node_control::node_pid,
+      node_control::node_exec_path,
+      node_control::node_log_tail,
+      node_control::node_log_clear,
+      node_control::node_start,
+      node_control::node_stop,
+      node_control::node_restart,
+      node_control::node_port_check,
+      // launch profiles
+      profiles::launch_profiles_list,
+      profiles::launch_profile_create,
+      profiles::launch_profile_update,
+      profiles::launch_profile_delete,
+      // rpc passthroughs
+      rpc_call,
+      rpc_tx_lookup,
+    ])
+    .run(tauri::generate_context!())
+    .expect("error while running tauri application");
+}
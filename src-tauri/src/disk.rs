@@ -0,0 +1,188 @@
+// src/disk.rs
+//! Disk usage of the Arknet home per area, and a watchdog on the volume holding
+//! `data/`: it warns below `diskWarnMib` free and stops the node below
+//! `diskStopMib`, before arkd runs out of space mid-write.
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::time::{sleep, Duration};
+
+use crate::cleanup::tree_size;
+use crate::events::{self, EventSink, Sink};
+use crate::node_control::{stop_node_for_home, NodeProc};
+use crate::settings::{ark_home, load_settings, Settings};
+
+pub const EVT_DISK_LOW: &str = "disk://low";
+pub const EVT_DISK_OK: &str = "disk://ok";
+
+pub const DISK_EVERY: Duration = Duration::from_secs(30);
+
+/// Top-level entries of the Arknet home reported on their own; the rest is `other_bytes`.
+pub const AREAS: &[&str] = &["bin", "versions", "data", "logs", "pyenv", "snapshots"];
+
+const MIB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiskLevel {
+  Ok,
+  /// Below `diskWarnMib`.
+  Low,
+  /// Below `diskStopMib`: the node is stopped and won't start.
+  Critical,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AreaUsage {
+  pub area: &'static str,
+  pub path: String,
+  pub bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+  pub home: String,
+  pub areas: Vec<AreaUsage>,
+  /// Config, var/ and anything else directly under the home.
+  pub other_bytes: u64,
+  pub total_bytes: u64,
+  /// Existing directory the free space was measured on.
+  pub volume: String,
+  pub free_bytes: Option<u64>,
+  pub warn_bytes: u64,
+  pub stop_bytes: u64,
+  pub level: DiskLevel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskAlert {
+  pub level: DiskLevel,
+  pub volume: String,
+  pub free_bytes: u64,
+  pub warn_bytes: u64,
+  pub stop_bytes: u64,
+  /// Pid of the arkd stopped because of this alert.
+  pub stopped_node: Option<u32>,
+  pub error: Option<String>,
+}
+
+/* ── MEASURE ────────────────────────────────────────────────────────────── */
+
+/// `data/` if it exists, else the nearest existing ancestor (the home may not exist yet).
+pub fn data_volume(home: &Path) -> PathBuf {
+  let data = home.join("data");
+  data.ancestors().find(|p| p.exists()).unwrap_or(&data).to_path_buf()
+}
+
+pub fn free_space(home: &Path) -> Option<u64> {
+  fs2::available_space(data_volume(home)).ok()
+}
+
+/// Warn and stop thresholds in bytes; a zero stop threshold never stops the node.
+pub fn thresholds(s: &Settings) -> (u64, u64) {
+  (s.disk_warn_mib as u64 * MIB, s.disk_stop_mib as u64 * MIB)
+}
+
+pub fn level(free: u64, s: &Settings) -> DiskLevel {
+  let (warn, stop) = thresholds(s);
+  if free < stop { DiskLevel::Critical } else if free < warn { DiskLevel::Low } else { DiskLevel::Ok }
+}
+
+/// Sizes of `AREAS` under `home`, the rest, and free space on the data volume.
+pub fn usage(home: &Path) -> DiskUsage {
+  let settings = load_settings();
+  let areas: Vec<AreaUsage> = AREAS.iter()
+    .map(|&area| {
+      let p = home.join(area);
+      AreaUsage { area, path: p.display().to_string(), bytes: tree_size(&p) }
+    })
+    .collect();
+  let total_bytes = tree_size(home);
+  let free_bytes = free_space(home);
+  let (warn_bytes, stop_bytes) = thresholds(&settings);
+  DiskUsage {
+    home: home.display().to_string(),
+    other_bytes: total_bytes.saturating_sub(areas.iter().map(|a| a.bytes).sum()),
+    areas,
+    total_bytes,
+    volume: data_volume(home).display().to_string(),
+    free_bytes,
+    warn_bytes,
+    stop_bytes,
+    level: free_bytes.map_or(DiskLevel::Ok, |f| level(f, &settings)),
+  }
+}
+
+/// Refuse to start arkd with less than `diskStopMib` free; the watchdog would only stop it again.
+pub fn start_guard(home: &Path) -> Result<(), String> {
+  let settings = load_settings();
+  match free_space(home) {
+    Some(free) if level(free, &settings) == DiskLevel::Critical => Err(format!(
+      "only {} MiB free on {}; arkd needs at least {} MiB (diskStopMib)",
+      free / MIB, data_volume(home).display(), settings.disk_stop_mib,
+    )),
+    _ => Ok(()),
+  }
+}
+
+/* ── WATCHDOG ───────────────────────────────────────────────────────────── */
+
+/// Remembers the last level so alerts fire on changes, not on every tick.
+#[derive(Default)]
+pub struct DiskWatch {
+  last: Option<DiskLevel>,
+}
+
+impl DiskWatch {
+  /// One check: emit `EVT_DISK_LOW` on entering a lower level (and whenever a
+  /// node had to be stopped), `EVT_DISK_OK` on recovering.
+  pub async fn tick(&mut self, sink: &dyn EventSink, proc: Option<&NodeProc>) -> Option<DiskLevel> {
+    let home = ark_home();
+    let settings = load_settings();
+    let free = free_space(&home)?;
+    let lvl = level(free, &settings);
+    let (warn_bytes, stop_bytes) = thresholds(&settings);
+    let mut alert = DiskAlert {
+      level: lvl,
+      volume: data_volume(&home).display().to_string(),
+      free_bytes: free,
+      warn_bytes,
+      stop_bytes,
+      stopped_node: None,
+      error: None,
+    };
+
+    if lvl == DiskLevel::Critical {
+      match stop_node_for_home(proc, &home, Some(sink)).await {
+        Ok(stopped) => alert.stopped_node = stopped.pid(),
+        Err(e) => alert.error = Some(e),
+      }
+    }
+    let changed = self.last != Some(lvl);
+    if lvl == DiskLevel::Ok {
+      if changed && self.last.is_some() { events::emit(sink, EVT_DISK_OK, &alert); }
+    } else if changed || alert.stopped_node.is_some() || alert.error.is_some() {
+      events::emit(sink, EVT_DISK_LOW, &alert);
+    }
+    self.last = Some(lvl);
+    Some(lvl)
+  }
+}
+
+pub async fn disk_watchdog(sink: Sink, proc: Option<&NodeProc>) {
+  let mut watch = DiskWatch::default();
+  loop {
+    watch.tick(&*sink, proc).await;
+    sleep(DISK_EVERY).await;
+  }
+}
+
+/* ── COMMANDS ───────────────────────────────────────────────────────────── */
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn disk_usage() -> Result<DiskUsage, String> {
+  let home = ark_home();
+  crate::install::blocking(move || Ok(usage(&home))).await
+}
@@ -1,7 +1,8 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fs, path::PathBuf};
-use tauri::{AppHandle, Manager};
+
+use crate::cleanup::app_endpoints_path;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Endpoint {
@@ -27,23 +28,19 @@ impl EndpointStore {
   }
 }
 
-fn file_path(app: &AppHandle) -> Result<PathBuf> {
-  let dir = app.path().app_config_dir()?; // Result<PathBuf, _> in Tauri v2
-  std::fs::create_dir_all(&dir).ok();
-  Ok(dir.join("endpoints.json"))
+fn file_path() -> Result<PathBuf> {
+  app_endpoints_path().ok_or_else(|| anyhow!("no config directory"))
 }
 
-pub fn load(app: &AppHandle) -> EndpointStore {
-  let path = match file_path(app) {
-    Ok(p) => p,
-    Err(_) => return EndpointStore::default(),
-  };
+pub fn load() -> EndpointStore {
+  let Ok(path) = file_path() else { return EndpointStore::default() };
   let Ok(bytes) = fs::read(path) else { return EndpointStore::default() };
   serde_json::from_slice(&bytes).unwrap_or_default()
 }
 
-pub fn save(app: &AppHandle, store: &EndpointStore) -> Result<()> {
-  let path = file_path(app)?;
+pub fn save(store: &EndpointStore) -> Result<()> {
+  let path = file_path()?;
+  if let Some(dir) = path.parent() { fs::create_dir_all(dir)?; }
   let data = serde_json::to_vec_pretty(store)?;
   fs::write(path, data)?;
   Ok(())
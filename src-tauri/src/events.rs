@@ -0,0 +1,38 @@
+// src/events.rs
+//! Event sink shared by the desktop app (Tauri events) and the CLI (terminal output).
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+pub trait EventSink: Send + Sync {
+  fn emit_value(&self, event: &str, payload: Value);
+}
+
+pub type Sink = Arc<dyn EventSink>;
+
+pub fn emit<T: Serialize + ?Sized>(sink: &dyn EventSink, event: &str, payload: &T) {
+  if let Ok(v) = serde_json::to_value(payload) {
+    sink.emit_value(event, v);
+  }
+}
+
+/// Drops everything; for callers that only want the return value.
+pub struct NullSink;
+
+impl EventSink for NullSink {
+  fn emit_value(&self, _event: &str, _payload: Value) {}
+}
+
+#[cfg(feature = "desktop")]
+impl<R: tauri::Runtime> EventSink for tauri::AppHandle<R> {
+  fn emit_value(&self, event: &str, payload: Value) {
+    let _ = tauri::Emitter::emit(self, event, payload);
+  }
+}
+
+#[cfg(feature = "desktop")]
+impl<R: tauri::Runtime> EventSink for tauri::Window<R> {
+  fn emit_value(&self, event: &str, payload: Value) {
+    let _ = tauri::Emitter::emit(self, event, payload);
+  }
+}
@@ -0,0 +1,189 @@
+// src/home.rs
+//! Choosing where the Arknet home lives. The choice is saved in ArkDesk's app
+//! config dir (the home itself holds `config.json`) and read back through
+//! `config`, so installer, supervisor, cleanup and manifest discovery all see
+//! it via `ark_home()`. Changing it moves the existing content.
+use serde::{Deserialize, Serialize};
+use std::{
+  fs,
+  io,
+  path::{Path, PathBuf},
+};
+
+use crate::cleanup::{copy_tree, remove_tree, tree_size, Skip, APP_IDENTIFIER};
+use crate::config::{self, Key, Source};
+use crate::events::EventSink;
+use crate::install::staging_root;
+use crate::node_control::{refuse_external_node, NodeProc};
+use crate::settings::{ark_home, default_ark_home};
+
+/// In the app config dir, next to endpoints.json.
+pub const HOME_FILE: &str = "arknet-home.json";
+
+#[derive(Serialize, Deserialize)]
+struct Saved {
+  path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeInfo {
+  pub path: String,
+  pub source: Source,
+  pub default: String,
+  pub saved: Option<String>,
+  pub exists: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeMove {
+  pub from: String,
+  pub to: String,
+  /// Existing content was moved over (false: none, or not asked to).
+  pub migrated: bool,
+  pub bytes: u64,
+  /// Pid of the supervised node stopped first.
+  pub stopped_node: Option<u32>,
+  /// Old copies left behind after moving across filesystems.
+  pub leftovers: Vec<Skip>,
+}
+
+/* ── SAVED CHOICE ───────────────────────────────────────────────────────── */
+
+pub fn saved_home_path() -> Option<PathBuf> {
+  Some(dirs::config_dir()?.join(APP_IDENTIFIER).join(HOME_FILE))
+}
+
+/// The saved home, if one was chosen (absolute paths only).
+pub fn saved_home() -> Option<PathBuf> {
+  let bytes = fs::read(saved_home_path()?).ok()?;
+  let saved: Saved = serde_json::from_slice(&bytes).ok()?;
+  Some(saved.path).filter(|p| p.is_absolute())
+}
+
+/// Save `home` as the choice; choosing the default forgets it instead.
+fn save_choice(home: &Path) -> Result<(), String> {
+  let file = saved_home_path().ok_or("no config directory")?;
+  if home == default_ark_home() {
+    return match fs::remove_file(&file) {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => Err(format!("{}: {e}", file.display())),
+      _ => Ok(()),
+    };
+  }
+  if let Some(dir) = file.parent() { fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?; }
+  let data = serde_json::to_vec_pretty(&Saved { path: home.to_path_buf() }).unwrap();
+  fs::write(&file, data).map_err(|e| format!("{}: {e}", file.display()))
+}
+
+pub fn info() -> HomeInfo {
+  let e = config::resolve(Key::ArkHome);
+  let path = ark_home();
+  HomeInfo {
+    exists: path.is_dir(),
+    path: path.display().to_string(),
+    source: e.source,
+    default: default_ark_home().display().to_string(),
+    saved: saved_home().map(|p| p.display().to_string()),
+  }
+}
+
+/* ── MOVING ─────────────────────────────────────────────────────────────── */
+
+fn is_empty_dir(p: &Path) -> bool {
+  fs::read_dir(p).map(|mut rd| rd.next().is_none()).unwrap_or(true)
+}
+
+/// Rename `from` to `to` (an empty or missing dir), copying across filesystems.
+/// Returns the old tree if it had to be copied and is left to remove.
+fn move_tree(from: &Path, to: &Path) -> Result<Option<PathBuf>, String> {
+  if let Some(parent) = to.parent() { fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?; }
+  let _ = fs::remove_dir(to);
+  if fs::rename(from, to).is_ok() { return Ok(None); }
+  if let Err(e) = copy_tree(from, to) {
+    let _ = remove_tree(to);
+    return Err(format!("copy {} to {}: {e}", from.display(), to.display()));
+  }
+  Ok(Some(from.to_path_buf()))
+}
+
+/// Make `to` the Arknet home. With `migrate`, the current home's content (and a
+/// pending install's staging dir) moves there first; the supervised node is
+/// stopped, and an arkd running from the old home is refused.
+pub async fn change(to: &Path, migrate: bool, proc: Option<&NodeProc>, sink: Option<&dyn EventSink>) -> Result<HomeMove, String> {
+  let entry = config::resolve(Key::ArkHome);
+  if matches!(entry.source, Source::Env | Source::Cli) {
+    return Err(format!("the Arknet home is set by {} / {}; unset it to choose one here", entry.env, entry.flag));
+  }
+  if !to.is_absolute() {
+    return Err(format!("{} is not an absolute path", to.display()));
+  }
+  let to: PathBuf = to.components().collect();
+  let from = ark_home();
+  let mut report = HomeMove {
+    from: from.display().to_string(),
+    to: to.display().to_string(),
+    migrated: false,
+    bytes: 0,
+    stopped_node: None,
+    leftovers: Vec::new(),
+  };
+  if to == from { return Ok(report); }
+  if to.starts_with(&from) || from.starts_with(&to) {
+    return Err(format!("{} and {} must not contain one another", to.display(), from.display()));
+  }
+  let content = migrate && !is_empty_dir(&from);
+  if content && !is_empty_dir(&to) {
+    return Err(format!("{} is not empty", to.display()));
+  }
+
+  if content {
+    refuse_external_node(&from)?;
+    report.stopped_node = proc.and_then(NodeProc::pid);
+    if let (Some(proc), Some(_)) = (proc, report.stopped_node) {
+      proc.stop(sink).await?;
+    }
+    report.bytes = tree_size(&from);
+    let mut old = vec![move_tree(&from, &to)?];
+    let stage = staging_root(&from);
+    if stage.exists() {
+      // best effort: a lost staging dir only means the install starts over
+      old.push(move_tree(&stage, &staging_root(&to)).unwrap_or(Some(stage)));
+    }
+    for p in old.into_iter().flatten() {
+      if let Err(reason) = remove_tree(&p) {
+        report.leftovers.push(Skip { path: p.display().to_string(), reason });
+      }
+    }
+    report.migrated = true;
+  } else {
+    fs::create_dir_all(&to).map_err(|e| format!("{}: {e}", to.display()))?;
+  }
+  save_choice(&to)?;
+  Ok(report)
+}
+
+/* ── COMMANDS ───────────────────────────────────────────────────────────── */
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn ark_home_info() -> Result<HomeInfo, String> {
+  Ok(info())
+}
+
+/// Move the Arknet home to `path` (content included unless `migrate` is false).
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn ark_home_set(
+  app: tauri::AppHandle,
+  proc: tauri::State<'_, NodeProc>,
+  ctl: tauri::State<'_, crate::install::InstallCtl>,
+  bridge: tauri::State<'_, crate::state::NodeBridge>,
+  path: String,
+  migrate: Option<bool>,
+) -> Result<HomeMove, String> {
+  ctl.begin()?;
+  let r = change(Path::new(&path), migrate.unwrap_or(true), Some(&*proc), Some(&app)).await;
+  ctl.finish();
+  bridge.retarget(crate::manifest::default_manifest_path());
+  r
+}
@@ -1,30 +1,33 @@
 // src-tauri/src/install.rs
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{
   env,
   ffi::OsStr,
   fs, io,
   path::{Path, PathBuf},
   process::Command,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+  time::{Instant, UNIX_EPOCH},
 };
-use tauri::{AppHandle, Emitter, Manager, Window};
-use tauri::path::BaseDirectory;
+use tokio::sync::Notify;
 // use std::ffi::OsString;
 
-use crate::settings::{ark_home, Settings};
-use crate::settings::save_settings as save_settings_cmd;
+use crate::cleanup::copy_tree;
+use crate::config::{self, Key};
+use crate::events::{self, EventSink};
+use crate::integrity::{self, FileIssue, IntegrityReport, TrustPolicy};
+use crate::pyenv;
+use crate::resources::Resources;
+use crate::subprocess::{self, Transcript};
+use crate::settings::{ark_home, load_settings, Settings};
+use crate::versions;
 
-#[derive(Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct InstallEvt {
-  step: u8,
-  total: u8,
-  label: String,
-  done: bool,
-  ok: bool,
-}
-
-const VERSION_ARG: &str = "--version";
+pub(crate) const VERSION_ARG: &str = "--version";
+#[cfg(windows)]
 const PROBE_ARG: &str = "--help";
 
 #[derive(Serialize, Clone)]
@@ -47,12 +50,15 @@ pub struct Preflight {
   pub missing_dlls: Vec<String>,
   pub dlls_ok: bool,
 
+  pub integrity_issues: Vec<FileIssue>,
+  pub integrity_notes: Vec<String>,
+  pub signature_ok: bool,
+  pub integrity_ok: bool,
+
   pub ok: bool,
 }
 
-fn emit(window: &Window, ev: InstallEvt) -> Result<(), String> {
-  window.emit("arknet://install_progress", ev).map_err(|e| e.to_string())
-}
+pub const EVT_INSTALL_PROGRESS: &str = "arknet://install_progress";
 
 fn is_writable(dir: &Path) -> bool {
   let test = dir.join(".arknet_write_test.tmp");
@@ -73,14 +79,14 @@ fn mark_executable(p: &Path) -> io::Result<()> {
 fn mark_executable(_p: &Path) -> io::Result<()> { Ok(()) }
 
 #[inline]
-fn exe(name: &str) -> String {
+pub(crate) fn exe(name: &str) -> String {
   #[cfg(windows)]
   { format!("{name}.exe") }
   #[cfg(not(windows))]
   { name.to_string() }
 }
 
-fn platform_dirs() -> &'static [&'static str] {
+pub(crate) fn platform_dirs() -> &'static [&'static str] {
   #[cfg(windows)]
   { &["windows"] }
   #[cfg(target_os = "macos")]
@@ -90,14 +96,12 @@ fn platform_dirs() -> &'static [&'static str] {
 }
 
 /// Resource/bin/<platform>
-fn resolve_resource_bin_dir(app: &AppHandle) -> Option<PathBuf> {
-  if let Ok(ovr) = std::env::var("ARKDESK_BIN_DIR") {
-    let p = PathBuf::from(ovr);
+pub(crate) fn resolve_resource_bin_dir(res: &Resources) -> Option<PathBuf> {
+  if let Some(p) = config::path(Key::BinDir) {
     if p.is_dir() { return Some(p); }
   }
   for &plat in platform_dirs() {
-    let rel = format!("bin/{plat}");
-    if let Ok(p) = app.path().resolve(&rel, BaseDirectory::Resource) {
+    if let Some(p) = res.resolve(&format!("bin/{plat}")) {
       if p.exists() { return Some(p); }
     }
   }
@@ -117,8 +121,8 @@ fn resolve_resource_bin_dir(app: &AppHandle) -> Option<PathBuf> {
 }
 
 /// Resource/wheels (flat or nested)
-fn resolve_resource_wheels_dir(app: &AppHandle) -> Option<PathBuf> {
-  if let Ok(p) = app.path().resolve("wheels", BaseDirectory::Resource) {
+fn resolve_resource_wheels_dir(res: &Resources) -> Option<PathBuf> {
+  if let Some(p) = res.resolve("wheels") {
     if p.exists() { return Some(p); }
   }
   #[cfg(debug_assertions)]
@@ -134,18 +138,18 @@ fn resolve_resource_wheels_dir(app: &AppHandle) -> Option<PathBuf> {
   None
 }
 
-fn required_bins() -> &'static [&'static str] {
+pub(crate) fn required_bins() -> &'static [&'static str] {
   &["arkd", "cli", "call", "cc", "tests"]
 }
 
 #[cfg(windows)]
-fn shared_exts() -> &'static [&'static str] { &[".dll"] }
+pub(crate) fn shared_exts() -> &'static [&'static str] { &[".dll"] }
 #[cfg(target_os = "macos")]
-fn shared_exts() -> &'static [&'static str] { &[".dylib"] }
+pub(crate) fn shared_exts() -> &'static [&'static str] { &[".dylib"] }
 #[cfg(all(unix, not(target_os = "macos")))]
-fn shared_exts() -> &'static [&'static str] { &[".so"] }
+pub(crate) fn shared_exts() -> &'static [&'static str] { &[".so"] }
 
-fn has_ext(p: &Path, exts: &[&str]) -> bool {
+pub(crate) fn has_ext(p: &Path, exts: &[&str]) -> bool {
   p.extension()
     .and_then(OsStr::to_str)
     .map(|e| exts.iter().any(|x| e.eq_ignore_ascii_case(x.trim_start_matches('.'))))
@@ -159,13 +163,9 @@ fn copy_file(src: &Path, dst: &Path) -> io::Result<()> {
   Ok(())
 }
 
-fn copy_bin_payload(src_bin: &Path, dest_bin: &Path) -> Result<(), String> {
-  fs::create_dir_all(dest_bin).map_err(|e| e.to_string())?;
-  for name in required_bins() {
-    let s = src_bin.join(exe(name));
-    let d = dest_bin.join(exe(name));
-    copy_file(&s, &d).map_err(|e| format!("copy {} failed: {}", s.display(), e))?;
-  }
+/// Files `copy_bin_payload` installs: required bins first, then shared libs and extra executables.
+pub(crate) fn bin_payload_files(src_bin: &Path) -> Result<Vec<PathBuf>, String> {
+  let mut files: Vec<PathBuf> = required_bins().iter().map(|n| src_bin.join(exe(n))).collect();
   let exts = shared_exts();
   for entry in fs::read_dir(src_bin).map_err(|e| e.to_string())? {
     let entry = entry.map_err(|e| e.to_string())?;
@@ -175,26 +175,219 @@ fn copy_bin_payload(src_bin: &Path, dest_bin: &Path) -> Result<(), String> {
       let is_required = required_bins().iter().any(|n| fname.eq_ignore_ascii_case(&exe(n)));
       if is_required { continue }
       if has_ext(&p, exts) || fname.ends_with(".exe") {
-        let d = dest_bin.join(fname);
-        copy_file(&p, &d).map_err(|e| format!("copy {} failed: {}", p.display(), e))?;
+        files.push(p);
       }
     }
   }
+  Ok(files)
+}
+
+fn copy_bin_payload(src_bin: &Path, dest_bin: &Path) -> Result<(), String> {
+  fs::create_dir_all(dest_bin).map_err(|e| e.to_string())?;
+  for s in bin_payload_files(src_bin)? {
+    let d = dest_bin.join(s.file_name().unwrap_or_default());
+    copy_file(&s, &d).map_err(|e| format!("copy {} failed: {}", s.display(), e))?;
+  }
   Ok(())
 }
 
+/// Wheels pip may pick from `--find-links`, plus the lock file if bundled.
+pub(crate) fn wheel_payload_files(res: &Resources, wheels: &Path) -> Vec<PathBuf> {
+  let mut files: Vec<PathBuf> = fs::read_dir(wheels)
+    .map(|rd| rd.flatten().map(|e| e.path()).filter(|p| p.is_file() && has_ext(p, &[".whl"])).collect())
+    .unwrap_or_default();
+  files.sort();
+  files.extend(res.resolve("bootstrap/requirements.lock.txt").filter(|p| p.is_file()));
+  files
+}
+
+// ---------- Versioned installs ----------
+
+/// Version string from `arkd --version` (e.g. "arkd v0.4.2 (abc)" -> "0.4.2"), with the raw
+/// first line. Falls back to `build-<sha256 prefix>` when arkd doesn't answer in time.
+fn detect_version(bin_dir: &Path) -> (String, Option<String>) {
+  let arkd = bin_dir.join(exe("arkd"));
+  let mut path = std::ffi::OsString::from(bin_dir.as_os_str());
+  if let Some(cur) = env::var_os("PATH") {
+    path.push(if cfg!(windows) { ";" } else { ":" });
+    path.push(cur);
+  }
+  let child = Command::new(&arkd)
+    .arg(VERSION_ARG)
+    .current_dir(bin_dir)
+    .env("PATH", path)
+    .stdin(std::process::Stdio::null())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn();
+
+  let reported = child.ok().and_then(|mut c| {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    loop {
+      match c.try_wait() {
+        Ok(Some(_)) => break,
+        Ok(None) if std::time::Instant::now() < deadline => std::thread::sleep(std::time::Duration::from_millis(50)),
+        _ => { let _ = c.kill(); let _ = c.wait(); return None; }
+      }
+    }
+    let out = c.wait_with_output().ok()?;
+    let text = if out.stdout.iter().any(|b| !b.is_ascii_whitespace()) { out.stdout } else { out.stderr };
+    String::from_utf8_lossy(&text).lines().map(str::trim).find(|l| !l.is_empty()).map(str::to_string)
+  });
+
+  let parsed = reported.as_deref().and_then(|line| {
+    line.split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+      .map(|t| t.strip_prefix('v').unwrap_or(t))
+      .find(|t| t.starts_with(|c: char| c.is_ascii_digit()) && t.contains('.'))
+      .map(str::to_string)
+  });
+  let version = parsed.unwrap_or_else(|| {
+    let h = integrity::sha256_file(&arkd).unwrap_or_default();
+    format!("build-{}", &h[..h.len().min(12)])
+  });
+  (version, reported)
+}
+
+/// Verify, stage and commit the bundled bins as a new version, then make it current.
+pub fn install_version(res: &Resources, src_bin: &Path, home: &Path) -> Result<String, String> {
+  install_version_named(res, src_bin, home, None)
+}
+
+/// As `install_version`, naming the version dir `name` (e.g. from a signed update
+/// bundle) instead of what `arkd --version` reports.
+pub fn install_version_named(res: &Resources, src_bin: &Path, home: &Path, name: Option<&str>) -> Result<String, String> {
+  // re-check right before copying: the bundle may have changed since preflight
+  require_verified(verify_files(res, &bin_payload_files(src_bin)?, Some(src_bin), None), "binaries")?;
+  stage_and_commit(src_bin, home, name)
+}
+
+/// Commit binaries the user built locally (source bootstrap) as a new current version.
+/// There is no signed manifest for these, so nothing is verified.
+pub fn install_local_build(bin_dir: &Path, home: &Path, name: Option<&str>) -> Result<String, String> {
+  stage_and_commit(bin_dir, home, name)
+}
+
+fn stage_and_commit(src_bin: &Path, home: &Path, name: Option<&str>) -> Result<String, String> {
+  let staging = versions::staging_dir(home)?;
+  let staged = copy_bin_payload(src_bin, &staging).map(|_| detect_version(&staging));
+  let (version, reported) = match staged {
+    Ok((detected, reported)) => (name.map(str::to_string).unwrap_or(detected), reported),
+    Err(e) => { let _ = fs::remove_dir_all(&staging); return Err(e); }
+  };
+  versions::commit(home, &staging, &version, reported, load_settings().keep_versions as usize)
+    .inspect_err(|_| { let _ = fs::remove_dir_all(&staging); })
+}
+
+// ---------- Payload integrity ----------
+
+/// Resources root holding `integrity.json`: the bundle dir, else the root that
+/// the resolved bin/wheels dirs sit under (dev fallbacks).
+fn integrity_root(res: &Resources, bin_dir: Option<&Path>, wheels_dir: Option<&Path>) -> Option<PathBuf> {
+  let cands = [
+    res.dir().cloned(),
+    bin_dir.and_then(|b| b.parent()?.parent()).map(Path::to_path_buf),
+    wheels_dir.and_then(Path::parent).map(Path::to_path_buf),
+  ];
+  cands.into_iter().flatten().find(|r| r.join(integrity::MANIFEST_FILE).is_file())
+    .or_else(|| res.dir().cloned())
+}
+
+fn verify_files(res: &Resources, files: &[PathBuf], bin_dir: Option<&Path>, wheels_dir: Option<&Path>) -> IntegrityReport {
+  let root = integrity_root(res, bin_dir, wheels_dir);
+  integrity::verify(root.as_deref(), files, &TrustPolicy::for_build())
+}
+
+/// Check the whole bundled payload (bins + wheels) against the signed manifest.
+pub fn verify_payload(res: &Resources) -> IntegrityReport {
+  let bin_dir = resolve_resource_bin_dir(res);
+  let wheels_dir = resolve_resource_wheels_dir(res);
+  let mut files = Vec::new();
+  if let Some(b) = &bin_dir {
+    files.extend(bin_payload_files(b).unwrap_or_default().into_iter().filter(|p| p.is_file()));
+  }
+  if let Some(w) = &wheels_dir {
+    files.extend(wheel_payload_files(res, w));
+  }
+  verify_files(res, &files, bin_dir.as_deref(), wheels_dir.as_deref())
+}
+
+fn require_verified(report: IntegrityReport, what: &str) -> Result<(), String> {
+  if report.ok { return Ok(()); }
+  Err(format!("Refusing to install {what}: integrity check failed: {}", report.describe()))
+}
+
+// ---------- Cancellation ----------
+
+pub const CANCELLED: &str = "install cancelled";
+
+/// Cancel handle shared between a running install and whoever may abort it.
+#[derive(Clone, Default)]
+pub struct Cancel(Arc<(AtomicBool, Notify)>);
+
+impl Cancel {
+  pub fn cancel(&self) {
+    self.0.0.store(true, Ordering::SeqCst);
+    self.0.1.notify_waiters();
+  }
+
+  pub fn is_cancelled(&self) -> bool { self.0.0.load(Ordering::SeqCst) }
+
+  /// Resolves once `cancel()` has been called.
+  pub async fn cancelled(&self) {
+    loop {
+      let notified = self.0.1.notified();
+      if self.is_cancelled() { return; }
+      notified.await;
+    }
+  }
+
+  pub(crate) fn check(&self) -> Result<(), String> {
+    if self.is_cancelled() { Err(CANCELLED.into()) } else { Ok(()) }
+  }
+}
+
+/// Managed state: at most one install at a time, cancellable from another command.
+#[derive(Default)]
+pub struct InstallCtl(Mutex<Option<Cancel>>);
+
+impl InstallCtl {
+  pub fn begin(&self) -> Result<Cancel, String> {
+    let mut cur = self.0.lock().unwrap();
+    if cur.is_some() { return Err("an install is already running".into()); }
+    let c = Cancel::default();
+    *cur = Some(c.clone());
+    Ok(c)
+  }
+
+  pub fn finish(&self) { self.0.lock().unwrap().take(); }
+
+  /// True if an install was running.
+  pub fn cancel(&self) -> bool {
+    self.0.lock().unwrap().as_ref().map(Cancel::cancel).is_some()
+  }
+}
+
+/// Run filesystem-heavy work (hashing, copying, renames) off the async workers.
+pub(crate) async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+  tokio::task::spawn_blocking(f).await.map_err(|e| e.to_string())?
+}
+
+fn or_cancelled(e: String, msg: &str) -> String {
+  if e == CANCELLED { e } else { format!("{msg}: {e}") }
+}
+
 // ---------- Python venv bootstrap ----------
 #[cfg(windows)]
-fn py_in_venv(venv: &Path) -> PathBuf { venv.join("Scripts").join("python.exe") }
+pub(crate) fn py_in_venv(venv: &Path) -> PathBuf { venv.join("Scripts").join("python.exe") }
 #[cfg(not(windows))]
-fn py_in_venv(venv: &Path) -> PathBuf { venv.join("bin").join("python") }
+pub(crate) fn py_in_venv(venv: &Path) -> PathBuf { venv.join("bin").join("python") }
 
 fn try_cmd_ok(cmd: &str, args: &[&str]) -> bool {
   Command::new(cmd).args(args).status().map(|s| s.success()).unwrap_or(false)
 }
 
 fn find_system_python() -> Option<(String, Vec<String>)> {
-  if let Ok(ovr) = env::var("ARKDESK_PY") {
+  if let Some(ovr) = config::get(Key::Python) {
     if try_cmd_ok(&ovr, &["-c","import sys;assert sys.version_info[:2]>=(3,9)"]) {
       return Some((ovr, vec![]));
     }
@@ -215,51 +408,402 @@ fn find_system_python() -> Option<(String, Vec<String>)> {
   None
 }
 
-fn ensure_pyenv(app: &AppHandle, window: &Window) -> Result<PathBuf, String> {
-  let home = ark_home();
-  let venv = home.join("pyenv");
-  let py = py_in_venv(&venv);
-  if py.exists() { return Ok(py); }
-
-  emit(window, InstallEvt{ step: 5, total: 10, label: "Creating Python venv".into(), done: false, ok: true })?;
-  let (sys_py, mut base_args) = find_system_python().ok_or_else(|| "Python 3.9+ not found on PATH".to_string())?;
-  base_args.push("-m".to_string());
-  base_args.push("venv".to_string());
-  base_args.push(venv.to_string_lossy().to_string());
-  let ok = Command::new(&sys_py).args(&base_args).status().map(|s| s.success()).unwrap_or(false);
-  if !ok { return Err("venv create failed".into()); }
-
-  emit(window, InstallEvt{ step: 6, total: 10, label: "Bootstrapping pip".into(), done: false, ok: true })?;
-  let ok = Command::new(&py).args(["-m","pip","install","-U","pip","setuptools","wheel"]).status().map(|s| s.success()).unwrap_or(false);
-  if !ok { return Err("pip bootstrap failed".into()); }
-
-  let wheels = resolve_resource_wheels_dir(app).ok_or_else(|| "resources/wheels missing".to_string())?;
-  let lock = app.path().resolve("bootstrap/requirements.lock.txt", BaseDirectory::Resource).ok();
-  let lock_present = lock.as_ref().map(|p| p.exists()).unwrap_or(false);
-
-  emit(window, InstallEvt{ step: 7, total: 10, label: "Installing ArkPy (offline)".into(), done: false, ok: true })?;
-  let status = if lock_present {
-    let lock_path = lock.unwrap();
-    Command::new(&py)
-      .args(["-m","pip","install","--no-index","--find-links"])
-      .arg(&wheels)
-      .args(["-r", lock_path.to_string_lossy().as_ref()])
-      .status()
-  } else {
-    Command::new(&py)
-      .args(["-m","pip","install","--no-index","--find-links"])
-      .arg(&wheels)
-      .arg("arknet-py")
-      .status()
-  };
-  if !status.map(|s| s.success()).unwrap_or(false) {
-    return Err("ArkPy wheel install failed".into());
+/// `sys_python -m venv <venv>`. The installer stages the venv and moves it into
+/// place afterwards; that is fine because we only ever run it as `python -m ...`.
+pub(crate) async fn create_venv(venv: &Path, sink: &dyn EventSink, cancel: &Cancel, log: &mut Transcript) -> Result<(), String> {
+  let (sys_py, base_args) = tokio::task::spawn_blocking(find_system_python).await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Python 3.9+ not found on PATH".to_string())?;
+  let mut cmd = tokio::process::Command::new(&sys_py);
+  cmd.args(&base_args).args(["-m", "venv"]).arg(venv);
+  subprocess::run(&mut cmd, sink, cancel, log).await.map_err(|e| or_cancelled(e, "venv create failed"))
+}
+
+pub(crate) async fn bootstrap_pip(py: &Path, sink: &dyn EventSink, cancel: &Cancel, log: &mut Transcript) -> Result<(), String> {
+  let mut cmd = tokio::process::Command::new(py);
+  cmd.args(["-m", "pip", "install", "-U", "pip", "setuptools", "wheel"]);
+  subprocess::run(&mut cmd, sink, cancel, log).await.map_err(|e| or_cancelled(e, "pip bootstrap failed"))
+}
+
+/// Offline ArkPy install (or upgrade) from the bundled wheels (and lock file, if bundled).
+pub(crate) async fn install_arkpy(res: &Resources, py: &Path, sink: &dyn EventSink, cancel: &Cancel, log: &mut Transcript) -> Result<(), String> {
+  let wheels = resolve_resource_wheels_dir(res).ok_or_else(|| "resources/wheels missing".to_string())?;
+  require_verified(verify_files(res, &wheel_payload_files(res, &wheels), None, Some(&wheels)), "wheels")?;
+  let lock = res.resolve("bootstrap/requirements.lock.txt").filter(|p| p.exists());
+
+  let mut cmd = tokio::process::Command::new(py);
+  cmd.args(["-m", "pip", "install", "--upgrade", "--no-index", "--find-links"]).arg(&wheels);
+  match lock {
+    Some(lock_path) => { cmd.arg("-r").arg(lock_path); }
+    None => { cmd.arg("arknet-py"); }
   }
-  Ok(py)
+  subprocess::run(&mut cmd, sink, cancel, log).await.map_err(|e| or_cancelled(e, "ArkPy wheel install failed"))
+}
+
+// ---------- Progress ----------
+
+/// Lines of subprocess output carried on a step's finish/fail event.
+const OUTPUT_TAIL: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StepState { Pending, Running, Done, Skipped, Failed }
+
+/// One declared install step as reported in the plan and the summary.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepRecord {
+  pub id: &'static str,
+  pub label: &'static str,
+  pub weight: u32,
+  pub state: StepState,
+  pub elapsed_ms: u64,
+  /// Skip reason or error.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub detail: Option<String>,
+}
+
+/// Payload of `EVT_INSTALL_PROGRESS`. `kind` is `plan` (once, all steps pending),
+/// then `start` and `finish`/`skip`/`fail` per step, then exactly one `summary`,
+/// which is the only event with `done: true` and is sent on success and failure alike.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallEvt {
+  pub kind: &'static str,
+  pub id: &'static str,
+  pub label: String,
+  /// 1-based index of `id` in the plan; 0 on plan/summary.
+  pub step: u8,
+  pub total: u8,
+  /// Weighted completion after this event, 0..=100.
+  pub pct: u8,
+  pub done: bool,
+  pub ok: bool,
+  pub elapsed_ms: u64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub output: Vec<String>,
+  /// Full subprocess output of the step (`logs/install.<step>-<ms>.log`), if it printed any.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub log: Option<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub steps: Vec<StepRecord>,
+}
+
+const PREPARE: (&str, &str, u32) = ("prepare", "Checking payload and preparing staging area", 1);
+const COMMIT: (&str, &str, u32) = ("commit", "Moving staged install into place", 1);
+
+/// Tracks the declared steps of one install run and emits their events.
+struct Progress<'a> {
+  sink: &'a dyn EventSink,
+  /// Where step transcripts are written (`logs/` under it).
+  home: PathBuf,
+  steps: Vec<StepRecord>,
+  current: Option<(usize, Instant)>,
+  /// Subprocess output of the running step (op id `install.<step>`).
+  transcript: Transcript,
+  t0: Instant,
+}
+
+impl<'a> Progress<'a> {
+  fn new(sink: &'a dyn EventSink, home: PathBuf) -> Self {
+    let declared = [PREPARE]
+      .into_iter()
+      .chain(Step::ALL.iter().map(|s| (s.id(), s.label(), s.weight())))
+      .chain([COMMIT]);
+    let steps = declared
+      .map(|(id, label, weight)| StepRecord { id, label, weight, state: StepState::Pending, elapsed_ms: 0, detail: None })
+      .collect();
+    let p = Self { sink, home, steps, current: None, transcript: Transcript::default(), t0: Instant::now() };
+    p.send(InstallEvt { kind: "plan", label: "Install".into(), steps: p.steps.clone(), ok: true, ..Default::default() });
+    p
+  }
+
+  fn index(&self, id: &str) -> usize {
+    self.steps.iter().position(|s| s.id == id).expect("declared install step")
+  }
+
+  fn pct(&self) -> u8 {
+    let total: u32 = self.steps.iter().map(|s| s.weight).sum();
+    let done: u32 = self.steps.iter()
+      .filter(|s| matches!(s.state, StepState::Done | StepState::Skipped))
+      .map(|s| s.weight)
+      .sum();
+    (done * 100 / total.max(1)) as u8
+  }
+
+  fn send(&self, mut ev: InstallEvt) {
+    ev.total = self.steps.len() as u8;
+    ev.pct = self.pct();
+    events::emit(self.sink, EVT_INSTALL_PROGRESS, &ev);
+  }
+
+  fn step_evt(&self, kind: &'static str, i: usize) -> InstallEvt {
+    let s = &self.steps[i];
+    InstallEvt {
+      kind,
+      id: s.id,
+      label: s.label.into(),
+      step: i as u8 + 1,
+      ok: s.state != StepState::Failed,
+      elapsed_ms: s.elapsed_ms,
+      ..Default::default()
+    }
+  }
+
+  fn start(&mut self, id: &str) {
+    let i = self.index(id);
+    self.steps[i].state = StepState::Running;
+    self.current = Some((i, Instant::now()));
+    self.transcript = Transcript::logged(format!("install.{id}"), &self.home);
+    self.send(self.step_evt("start", i));
+  }
+
+  fn finish(&mut self) {
+    let Some((i, t)) = self.current.take() else { return };
+    self.steps[i].state = StepState::Done;
+    self.steps[i].elapsed_ms = t.elapsed().as_millis() as u64;
+    let mut ev = self.step_evt("finish", i);
+    ev.output = self.transcript.tail(OUTPUT_TAIL);
+    ev.log = self.transcript.log_path();
+    self.send(ev);
+  }
+
+  fn skip(&mut self, id: &str, why: &str) {
+    let i = self.index(id);
+    self.steps[i].state = StepState::Skipped;
+    self.steps[i].detail = Some(why.into());
+    let mut ev = self.step_evt("skip", i);
+    ev.label = format!("{} ({why})", self.steps[i].label);
+    self.send(ev);
+  }
+
+  /// Mark the running step failed (if any) and send the terminal summary.
+  fn summary(mut self, out: &Result<Option<String>, String>) {
+    if let (Err(e), Some((i, t))) = (out, self.current.take()) {
+      self.steps[i].state = StepState::Failed;
+      self.steps[i].elapsed_ms = t.elapsed().as_millis() as u64;
+      self.steps[i].detail = Some(e.clone());
+      let mut ev = self.step_evt("fail", i);
+      ev.error = Some(e.clone());
+      ev.output = self.transcript.tail(OUTPUT_TAIL);
+      ev.log = self.transcript.log_path();
+      self.send(ev);
+    }
+    let label = match out {
+      Ok(Some(v)) => format!("Installed arkd {v}"),
+      Ok(None) => "Done".into(),
+      Err(e) if e == CANCELLED => "Install cancelled".into(),
+      Err(_) => "Install failed".into(),
+    };
+    self.send(InstallEvt {
+      kind: "summary",
+      label,
+      done: true,
+      ok: out.is_ok(),
+      elapsed_ms: self.t0.elapsed().as_millis() as u64,
+      error: out.as_ref().err().cloned(),
+      steps: self.steps.clone(),
+      ..Default::default()
+    });
+  }
+}
+
+// ---------- Staged install ----------
+
+const JOURNAL_FILE: &str = "journal.json";
+
+/// Resumable install steps, in order. Each one only writes inside the staging dir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Step {
+  Bins,
+  Config,
+  Venv,
+  Pip,
+  ArkPy,
+}
+
+impl Step {
+  const ALL: [Step; 5] = [Step::Bins, Step::Config, Step::Venv, Step::Pip, Step::ArkPy];
+
+  fn label(self) -> &'static str {
+    match self {
+      Step::Bins => "Copying binaries and DLLs",
+      Step::Config => "Writing config.json",
+      Step::Venv => "Creating Python venv",
+      Step::Pip => "Bootstrapping pip",
+      Step::ArkPy => "Installing ArkPy (offline)",
+    }
+  }
+
+  fn id(self) -> &'static str {
+    match self {
+      Step::Bins => "bins",
+      Step::Config => "config",
+      Step::Venv => "venv",
+      Step::Pip => "pip",
+      Step::ArkPy => "arkPy",
+    }
+  }
+
+  /// Share of the progress bar, roughly by typical duration.
+  fn weight(self) -> u32 {
+    match self {
+      Step::Bins => 3,
+      Step::Config => 1,
+      Step::Venv => 3,
+      Step::Pip => 4,
+      Step::ArkPy => 5,
+    }
+  }
+
+  fn is_python(self) -> bool { matches!(self, Step::Venv | Step::Pip | Step::ArkPy) }
+}
+
+/// `<staging>/journal.json`: which steps finished, for which payload.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Journal {
+  fingerprint: String,
+  #[serde(default)]
+  done: Vec<Step>,
+  #[serde(default)]
+  version: Option<String>,
+  #[serde(default)]
+  reported: Option<String>,
+}
+
+/// Sibling of the Arknet home (`~/.arknet.staging`), so the final moves are renames.
+pub fn staging_root(home: &Path) -> PathBuf {
+  let name = home.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "arknet".into());
+  home.with_file_name(format!("{name}.staging"))
+}
+
+/// Cheap identity of the bundled payload (names, sizes, mtimes): a journal written
+/// for a different payload is not resumed.
+fn payload_fingerprint(res: &Resources, src_bin: &Path, wheels: &Path) -> Result<String, String> {
+  let mut h = Sha256::new();
+  for p in bin_payload_files(src_bin)?.iter().chain(&wheel_payload_files(res, wheels)) {
+    let meta = fs::metadata(p).ok();
+    let mtime = meta.as_ref()
+      .and_then(|m| m.modified().ok())
+      .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+      .map(|d| d.as_nanos())
+      .unwrap_or(0);
+    h.update(format!("{}|{}|{}\n", p.display(), meta.map(|m| m.len()).unwrap_or(0), mtime));
+  }
+  Ok(hex::encode(h.finalize()))
+}
+
+/// The journal to resume from, or a fresh staging dir if there is none or it is stale.
+fn open_journal(stage: &Path, fingerprint: &str) -> Result<Journal, String> {
+  let prior: Option<Journal> = fs::read(stage.join(JOURNAL_FILE)).ok()
+    .and_then(|b| serde_json::from_slice(&b).ok());
+  if let Some(j) = prior.filter(|j| j.fingerprint == fingerprint) {
+    return Ok(j);
+  }
+  if stage.exists() {
+    fs::remove_dir_all(stage).map_err(|e| format!("clear {}: {e}", stage.display()))?;
+  }
+  fs::create_dir_all(stage).map_err(|e| format!("create {}: {e}", stage.display()))?;
+  let j = Journal { fingerprint: fingerprint.into(), ..Default::default() };
+  write_journal(stage, &j)?;
+  Ok(j)
+}
+
+fn write_journal(stage: &Path, j: &Journal) -> Result<(), String> {
+  let p = stage.join(JOURNAL_FILE);
+  let tmp = p.with_extension("json.tmp");
+  fs::write(&tmp, serde_json::to_vec_pretty(j).unwrap()).map_err(|e| e.to_string())?;
+  fs::rename(&tmp, &p).map_err(|e| e.to_string())
+}
+
+fn fresh_dir(p: &Path) -> Result<(), String> {
+  if p.exists() { fs::remove_dir_all(p).map_err(|e| format!("clear {}: {e}", p.display()))?; }
+  Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_step(
+  step: Step,
+  res: &Resources,
+  src_bin: &Path,
+  home: &Path,
+  stage: &Path,
+  journal: &mut Journal,
+  cancel: &Cancel,
+  progress: &mut Progress<'_>,
+) -> Result<(), String> {
+  let (sink, log) = (progress.sink, &mut progress.transcript);
+  let venv = stage.join("pyenv");
+  match step {
+    Step::Bins => {
+      let (res, src_bin, bin) = (res.clone(), src_bin.to_path_buf(), stage.join("bin"));
+      let (version, reported) = blocking(move || {
+        // re-check right before copying: the bundle may have changed since preflight
+        require_verified(verify_files(&res, &bin_payload_files(&src_bin)?, Some(&src_bin), None), "binaries")?;
+        fresh_dir(&bin)?;
+        copy_bin_payload(&src_bin, &bin)?;
+        Ok(detect_version(&bin))
+      }).await?;
+      journal.version = Some(version);
+      journal.reported = reported;
+    }
+    Step::Config => {
+      if !home.join("config.json").is_file() {
+        fs::write(stage.join("config.json"), serde_json::to_vec_pretty(&Settings::default()).unwrap())
+          .map_err(|e| e.to_string())?;
+      }
+    }
+    Step::Venv => {
+      fresh_dir(&venv)?;
+      create_venv(&venv, sink, cancel, log).await?;
+    }
+    Step::Pip => bootstrap_pip(&py_in_venv(&venv), sink, cancel, log).await?,
+    Step::ArkPy => install_arkpy(res, &py_in_venv(&venv), sink, cancel, log).await?,
+  }
+  Ok(())
+}
+
+/// Move whatever is staged into the home and drop the staging dir. Each part is
+/// skipped once moved, so an interrupted commit completes on the next run.
+/// Returns the installed version, if bins were part of this commit.
+fn commit_staged(home: &Path, stage: &Path, journal: &Journal) -> Result<Option<String>, String> {
+  for d in [versions::versions_dir(home), home.join("data"), home.join("logs")] {
+    fs::create_dir_all(&d).map_err(|e| format!("create {}: {e}", d.display()))?;
+  }
+
+  let mut installed = None;
+  let bin = stage.join("bin");
+  if bin.is_dir() {
+    let version = journal.version.as_deref().unwrap_or("unknown");
+    let keep = load_settings().keep_versions as usize;
+    installed = Some(versions::commit(home, &bin, version, journal.reported.clone(), keep)?);
+  }
+
+  let cfg = stage.join("config.json");
+  if cfg.is_file() && !home.join("config.json").exists() {
+    fs::rename(&cfg, home.join("config.json")).map_err(|e| format!("move config.json: {e}"))?;
+  }
+
+  let venv = stage.join("pyenv");
+  if venv.is_dir() {
+    let dest = pyenv::venv_dir(home);
+    let old = stage.join("pyenv.old");
+    if dest.exists() {
+      fs::rename(&dest, &old).map_err(|e| format!("replace {} failed (in use?): {e}", dest.display()))?;
+    }
+    fs::rename(&venv, &dest).map_err(|e| format!("move {} -> {} failed: {e}", venv.display(), dest.display()))?;
+  }
+
+  fs::remove_dir_all(stage).map_err(|e| format!("remove {}: {e}", stage.display()))?;
+  Ok(installed)
 }
 
 #[cfg(windows)]
-fn prepend_to_path(dir: &Path) {
+pub(crate) fn prepend_to_path(dir: &Path) {
   let cur = env::var_os("PATH").unwrap_or_default();
   let mut newp = dir.as_os_str().to_os_string();
   newp.push(";");
@@ -333,9 +877,19 @@ fn windows_loader_check(bin_dir_opt: &Option<PathBuf>) -> (Vec<String>, bool) {
   (notes, ok)
 }
 
+#[cfg(not(windows))]
+fn windows_loader_check(_bin_dir_opt: &Option<PathBuf>) -> (Vec<String>, bool) { (Vec::new(), true) }
+
 // ---------- Commands ----------
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn install_preflight(app: AppHandle) -> Result<Preflight, String> {
+pub async fn install_preflight(app: tauri::AppHandle) -> Result<Preflight, String> {
+  // hashes every bundled binary and wheel
+  let res = Resources::from_app(&app);
+  blocking(move || preflight(&res)).await
+}
+
+pub fn preflight(res: &Resources) -> Result<Preflight, String> {
   let home = ark_home();
   let parent: PathBuf = home.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
 
@@ -359,7 +913,7 @@ pub fn install_preflight(app: AppHandle) -> Result<Preflight, String> {
   }
 
   let mut missing_bins = Vec::new();
-  let bin_dir_opt = resolve_resource_bin_dir(&app);
+  let bin_dir_opt = resolve_resource_bin_dir(res);
   if let Some(bin_dir) = &bin_dir_opt {
     for &name in required_bins() {
       if !bin_dir.join(exe(name)).exists() {
@@ -372,7 +926,7 @@ pub fn install_preflight(app: AppHandle) -> Result<Preflight, String> {
   let bins_ok = missing_bins.is_empty();
 
   let mut missing_wheels = Vec::new();
-  if let Some(wd) = resolve_resource_wheels_dir(&app) {
+  if let Some(wd) = resolve_resource_wheels_dir(res) {
     let ok = fs::read_dir(&wd).map(|it| {
       it.filter_map(|e| e.ok())
         .any(|e| e.file_name().to_string_lossy().to_lowercase().starts_with("arknet_py-") ||
@@ -387,7 +941,9 @@ pub fn install_preflight(app: AppHandle) -> Result<Preflight, String> {
   // Windows DLL sanity: loader-only check; non-Windows always ok
   let (missing_dlls, dlls_ok) = windows_loader_check(&bin_dir_opt);
 
-  let ok = parent_exists && parent_writable && free_bytes >= need_bytes && bins_ok && wheels_ok && dlls_ok;
+  let integrity = verify_payload(res);
+
+  let ok = parent_exists && parent_writable && free_bytes >= need_bytes && bins_ok && wheels_ok && dlls_ok && integrity.ok;
 
   Ok(Preflight {
     home: home.to_string_lossy().to_string(),
@@ -403,14 +959,47 @@ pub fn install_preflight(app: AppHandle) -> Result<Preflight, String> {
     wheels_ok,
     missing_dlls,
     dlls_ok,
+    integrity_issues: integrity.issues,
+    integrity_notes: integrity.notes,
+    signature_ok: integrity.signature_ok,
+    integrity_ok: integrity.ok,
     ok,
   })
 }
 
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn install_arknet_progress(window: tauri::Window, ctl: tauri::State<'_, InstallCtl>) -> Result<(), String> {
+  use tauri::Manager;
+  let cancel = ctl.begin()?;
+  let out = install(&Resources::from_app(window.app_handle()), &window, &cancel).await;
+  ctl.finish();
+  out
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn install_arknet_progress(window: Window) -> Result<(), String> {
-  let app = window.app_handle();
-  let pf = install_preflight(app.clone())?;
+pub fn install_cancel(ctl: tauri::State<'_, InstallCtl>) -> Result<bool, String> {
+  Ok(ctl.cancel())
+}
+
+/// Install the bundled payload into the Arknet home, reporting progress to `sink`.
+///
+/// Everything is built in `staging_root(home)` first; finished steps are recorded in
+/// its journal so a failed or cancelled run resumes where it stopped. Apart from step
+/// logs in `logs/`, the home is only touched by the final commit. A `summary` event
+/// is emitted whatever the outcome.
+pub async fn install(res: &Resources, sink: &dyn EventSink, cancel: &Cancel) -> Result<(), String> {
+  let mut progress = Progress::new(sink, ark_home());
+  let out = run_install(res, &mut progress, cancel).await;
+  progress.summary(&out);
+  out.map(|_| ())
+}
+
+/// The install proper; returns the committed arkd version, if bins were committed.
+async fn run_install(res: &Resources, progress: &mut Progress<'_>, cancel: &Cancel) -> Result<Option<String>, String> {
+  progress.start(PREPARE.0);
+  let pf = { let res = res.clone(); blocking(move || preflight(&res)).await? };
   if !pf.bins_ok {
     return Err(format!("Missing binaries in app resources (bin/<platform>): {}", pf.missing_bins.join(", ")));
   }
@@ -420,100 +1009,72 @@ pub fn install_arknet_progress(window: Window) -> Result<(), String> {
   if !pf.dlls_ok {
     return Err(format!("Windows DLL check failed: {}", if pf.missing_dlls.is_empty() { "(unknown)".into() } else { pf.missing_dlls.join(", ") }));
   }
+  if !pf.integrity_ok {
+    return Err(format!("Bundled payload failed integrity check: {} issue(s)", pf.integrity_issues.len()));
+  }
   if !(pf.parent_exists && pf.parent_writable && pf.free_bytes >= pf.need_bytes) {
     return Err("Preflight failed: parent not writable and/or insufficient free space.".into());
   }
 
-  let total = 10u8;
   let home = ark_home();
-  let dest_bin = home.join("bin");
-
-  emit(&window, InstallEvt { step: 0, total, label: "Preparing directories".into(), done: false, ok: true })?;
-  fs::create_dir_all(&dest_bin).map_err(|e| e.to_string())?;
-  fs::create_dir_all(home.join("data")).map_err(|e| e.to_string())?;
-  fs::create_dir_all(home.join("logs")).map_err(|e| e.to_string())?;
-
-  emit(&window, InstallEvt { step: 1, total, label: "Locating bundled binaries".into(), done: false, ok: true })?;
-  let Some(src_bin) = resolve_resource_bin_dir(&window.app_handle()) else {
-    return Err("Bundled binaries not found.".into());
+  let stage = staging_root(&home);
+  let src_bin = resolve_resource_bin_dir(res).ok_or("Bundled binaries not found.")?;
+  let wheels = resolve_resource_wheels_dir(res).ok_or("resources/wheels missing")?;
+  let mut journal = {
+    let (res, src_bin, stage) = (res.clone(), src_bin.clone(), stage.clone());
+    blocking(move || open_journal(&stage, &payload_fingerprint(&res, &src_bin, &wheels)?)).await?
   };
-
-  emit(&window, InstallEvt { step: 2, total, label: "Copying binaries and DLLs".into(), done: false, ok: true })?;
-  copy_bin_payload(&src_bin, &dest_bin)?;
-
-  #[cfg(windows)]
-  {
-    prepend_to_path(&dest_bin);
-    emit(&window, InstallEvt { step: 3, total, label: "PATH updated for DLL resolution".into(), done: false, ok: true })?;
+  // a venv matching the payload lock is kept as is; one whose interpreter runs is
+  // copied into the stage to have ArkPy upgraded there; a missing or broken one is
+  // rebuilt (or its staged rebuild or upgrade resumed)
+  let current = { let (res, home) = (res.clone(), home.clone()); blocking(move || Ok(pyenv::verify(&res, &home))).await? };
+  let staged_venv = stage.join("pyenv");
+  let keep_pyenv = current.ok && !staged_venv.exists();
+  let copied = !current.ok && current.info.python_version.is_some() && !staged_venv.exists();
+  if copied {
+    let (live, staged) = (pyenv::venv_dir(&home), staged_venv.clone());
+    blocking(move || copy_tree(&live, &staged).map_err(|e| format!("copy {}: {e}", live.display()))).await?;
+    journal.done.retain(|s| !s.is_python());
+    journal.done.extend([Step::Venv, Step::Pip]);
+    write_journal(&stage, &journal)?;
   }
+  progress.finish();
 
-  emit(&window, InstallEvt { step: 4, total, label: "Writing config.json".into(), done: false, ok: true })?;
-  if !home.join("config.json").is_file() {
-    save_settings_cmd(Settings::default()).map_err(|e| e)?;
-  }
-
-  emit(&window, InstallEvt { step: 5, total, label: "Preparing Python runtime".into(), done: false, ok: true })?;
-  let _py = ensure_pyenv(&app, &window)?;
+  for &step in Step::ALL.iter() {
+    cancel.check()?;
+    if journal.done.contains(&step) {
+      progress.skip(step.id(), if copied && step.is_python() { "copied the existing venv" } else { "done in an earlier run" });
+      continue;
+    }
+    if keep_pyenv && step.is_python() {
+      progress.skip(step.id(), "venv matches the bundled lock");
+      continue;
+    }
 
-  emit(&window, InstallEvt { step: total, total, label: "Done".into(), done: true, ok: true })?;
-  Ok(())
-}
+    progress.start(step.id());
+    run_step(step, res, &src_bin, &home, &stage, &mut journal, cancel, progress).await?;
+    journal.done.push(step);
+    write_journal(&stage, &journal)?;
+    progress.finish();
+  }
 
-#[tauri::command]
-pub fn install_selftest(window: Window) -> Result<serde_json::Value, String> {
-  let app = window.app_handle();
-  let home = ark_home();
-  let bin_dir = home.join("bin");
-
-  // Ensure binaries exist in Ark home; fallback to bundled resources if needed.
-  let arkd = {
-    let p = bin_dir.join(exe("arkd"));
-    if !p.is_file() {
-      if let Some(src) = resolve_resource_bin_dir(&app) {
-        fs::create_dir_all(&bin_dir).map_err(|e| e.to_string())?;
-        copy_bin_payload(&src, &bin_dir)?;
-      }
-    }
-    bin_dir.join(exe("arkd"))
+  // point of no return: not cancellable, and safe to re-run if interrupted
+  cancel.check()?;
+  progress.start(COMMIT.0);
+  let version = {
+    let home = home.clone();
+    blocking(move || commit_staged(&home, &stage, &journal)).await?
   };
 
-  let py = ensure_pyenv(&app, &window)?;
-
   #[cfg(windows)]
-  prepend_to_path(&bin_dir);
-
-  // Loader probe: do not require a specific CLI flag to succeed.
-  let ark_out = Command::new(&arkd)
-    .arg(PROBE_ARG)
-    .output()
-    .map_err(|e| format!("arkd launch failed: {e}"))?;
-  let ark_loaded = true; // reaching here means the image loaded
-  let ark_exit_ok = ark_out.status.success();
-
-  let code = "import importlib.metadata as m, arknet_py; print(m.version('arknet-py'))";
-  let py_out = Command::new(&py)
-    .args(["-c", code])
-    .output()
-    .map_err(|e| format!("python import failed: {e}"))?;
-  let arkpy_ok = py_out.status.success();
-  let arkpy_ver = if arkpy_ok {
-    String::from_utf8_lossy(&py_out.stdout).trim().to_string()
-  } else { String::new() };
-
-  Ok(serde_json::json!({
-    "binPath": bin_dir.to_string_lossy(),
-    "arkdLoaded": ark_loaded,
-    "arkdExitOk": ark_exit_ok,
-    "arkdStdout": String::from_utf8_lossy(&ark_out.stdout),
-    "arkdStderr": String::from_utf8_lossy(&ark_out.stderr),
-    "python": py.to_string_lossy(),
-    "arkpyOk": arkpy_ok,
-    "arkpyVersion": arkpy_ver
-  }))
+  if let Some(v) = &version {
+    prepend_to_path(&versions::versions_dir(&home).join(v));
+  }
+  progress.finish();
+  Ok(version)
 }
 
-
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn reveal_ark_home() -> Result<(), String> {
   let p = ark_home();
   if !p.exists() { return Err("Arknet home does not exist".into()); }
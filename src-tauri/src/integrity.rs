@@ -0,0 +1,245 @@
+// src/integrity.rs
+//! Signed payload manifest for bundled binaries and wheels.
+//!
+//! `integrity.json` at the resources root maps each payload file (path relative
+//! to that root, `/`-separated) to its SHA-256; `integrity.json.sig` holds a hex
+//! ed25519 signature over the exact bytes of `integrity.json`. The trusted public
+//! key is embedded at build time via `ARKDESK_INTEGRITY_PUBKEY` (hex).
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fs, io,
+  path::{Path, PathBuf},
+};
+
+use crate::config::{self, Key};
+
+pub const MANIFEST_FILE: &str = "integrity.json";
+pub const SIG_FILE: &str = "integrity.json.sig";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+  pub version: u32,
+  pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Problem {
+  /// Hash differs from the manifest.
+  Modified,
+  /// Listed in the manifest but not on disk.
+  Missing,
+  /// On disk and about to be used, but not listed.
+  Unlisted,
+  /// Manifest or signature unreadable, malformed or not trusted.
+  Untrusted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIssue {
+  pub path: String,
+  pub problem: Problem,
+  pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+  pub root: Option<String>,
+  pub signature_ok: bool,
+  pub checked: usize,
+  pub issues: Vec<FileIssue>,
+  pub notes: Vec<String>,
+  pub ok: bool,
+}
+
+impl IntegrityReport {
+  /// One-line summary of the issues, for errors.
+  pub fn describe(&self) -> String {
+    self.issues.iter()
+      .map(|i| format!("{} ({})", i.path, i.detail))
+      .collect::<Vec<_>>()
+      .join(", ")
+  }
+}
+
+/// Which key to trust and whether an unsigned payload is acceptable.
+#[derive(Debug, Clone)]
+pub struct TrustPolicy {
+  pub key: Option<VerifyingKey>,
+  pub allow_unsigned: bool,
+}
+
+impl TrustPolicy {
+  /// Release builds require the embedded key and a valid signature. Debug builds
+  /// also take `Key::IntegrityPubkey` from the environment or CLI and tolerate a
+  /// missing manifest or key (hashes are still checked when a manifest exists).
+  pub fn for_build() -> Self {
+    let key = config::get(Key::IntegrityPubkey).and_then(|k| parse_pubkey(&k));
+    Self { key, allow_unsigned: cfg!(debug_assertions) }
+  }
+}
+
+pub fn parse_pubkey(hex_key: &str) -> Option<VerifyingKey> {
+  let bytes: [u8; 32] = hex::decode(hex_key.trim()).ok()?.try_into().ok()?;
+  VerifyingKey::from_bytes(&bytes).ok()
+}
+
+pub fn sha256_file(p: &Path) -> io::Result<String> {
+  let mut f = fs::File::open(p)?;
+  let mut h = Sha256::new();
+  io::copy(&mut f, &mut h)?;
+  Ok(hex::encode(h.finalize()))
+}
+
+/// `p` relative to `root`, `/`-separated; None if outside it.
+fn rel_key(root: &Path, p: &Path) -> Option<String> {
+  let rel = p.strip_prefix(root).ok()?;
+  let parts: Vec<String> = rel.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+  Some(parts.join("/"))
+}
+
+fn untrusted(path: &str, detail: impl Into<String>) -> FileIssue {
+  FileIssue { path: path.into(), problem: Problem::Untrusted, detail: detail.into() }
+}
+
+/// Read the manifest under `root` and check its signature against `policy`.
+fn load_manifest(root: &Path, policy: &TrustPolicy, report: &mut IntegrityReport) -> Option<IntegrityManifest> {
+  let bytes = match fs::read(root.join(MANIFEST_FILE)) {
+    Ok(b) => b,
+    Err(e) => {
+      if policy.allow_unsigned {
+        report.notes.push(format!("{MANIFEST_FILE} not found; payload not verified (dev build)"));
+      } else {
+        report.issues.push(untrusted(MANIFEST_FILE, format!("unreadable: {e}")));
+      }
+      return None;
+    }
+  };
+
+  match &policy.key {
+    Some(key) => {
+      let sig = fs::read_to_string(root.join(SIG_FILE)).ok()
+        .and_then(|s| hex::decode(s.trim()).ok())
+        .and_then(|b| Signature::from_slice(&b).ok());
+      match sig {
+        Some(sig) if key.verify(&bytes, &sig).is_ok() => report.signature_ok = true,
+        Some(_) => report.issues.push(untrusted(SIG_FILE, "signature does not match the trusted key")),
+        None => report.issues.push(untrusted(SIG_FILE, "signature missing or malformed")),
+      }
+    }
+    None if policy.allow_unsigned => report.notes.push("no trusted key built in; signature not checked (dev build)".into()),
+    None => report.issues.push(untrusted(SIG_FILE, "no trusted key built in")),
+  }
+
+  match serde_json::from_slice::<IntegrityManifest>(&bytes) {
+    Ok(m) if m.version == FORMAT_VERSION => Some(m),
+    Ok(m) => { report.issues.push(untrusted(MANIFEST_FILE, format!("unsupported version {}", m.version))); None }
+    Err(e) => { report.issues.push(untrusted(MANIFEST_FILE, format!("malformed: {e}"))); None }
+  }
+}
+
+/// Check `payload` (files about to be installed) against the manifest at `root`.
+/// Listed files that sit in the same directories as the payload but are gone
+/// are reported as missing.
+pub fn verify(root: Option<&Path>, payload: &[PathBuf], policy: &TrustPolicy) -> IntegrityReport {
+  let mut report = IntegrityReport {
+    root: root.map(|r| r.to_string_lossy().to_string()),
+    signature_ok: false,
+    checked: 0,
+    issues: Vec::new(),
+    notes: Vec::new(),
+    ok: false,
+  };
+
+  let manifest = root.and_then(|r| load_manifest(r, policy, &mut report));
+  if root.is_none() {
+    if policy.allow_unsigned {
+      report.notes.push("no resources root; payload not verified (dev build)".into());
+    } else {
+      report.issues.push(untrusted(MANIFEST_FILE, "resources root not found"));
+    }
+  }
+
+  if let (Some(root), Some(m)) = (root, manifest) {
+    let mut dirs = BTreeSet::new();
+    for p in payload {
+      let Some(key) = rel_key(root, p) else {
+        report.issues.push(FileIssue {
+          path: p.to_string_lossy().to_string(),
+          problem: Problem::Unlisted,
+          detail: "outside the signed resources root".into(),
+        });
+        continue;
+      };
+      if let Some((dir, _)) = key.rsplit_once('/') { dirs.insert(dir.to_string()); }
+      report.checked += 1;
+
+      let Some(expected) = m.files.get(&key) else {
+        report.issues.push(FileIssue { path: key, problem: Problem::Unlisted, detail: "not in manifest".into() });
+        continue;
+      };
+      match sha256_file(p) {
+        Ok(actual) if actual.eq_ignore_ascii_case(expected) => {}
+        Ok(actual) => report.issues.push(FileIssue {
+          path: key,
+          problem: Problem::Modified,
+          detail: format!("sha256 {actual}, expected {expected}"),
+        }),
+        Err(e) => report.issues.push(FileIssue { path: key, problem: Problem::Missing, detail: e.to_string() }),
+      }
+    }
+
+    for key in m.files.keys() {
+      let in_dir = key.rsplit_once('/').is_some_and(|(d, _)| dirs.contains(d));
+      if in_dir && !root.join(key).is_file() {
+        report.issues.push(FileIssue { path: key.clone(), problem: Problem::Missing, detail: "listed but not bundled".into() });
+      }
+    }
+  }
+
+  report.ok = report.issues.is_empty();
+  report
+}
+
+/// Hash every file under `subdirs` of `root` (entries may also name single files),
+/// write the manifest and its signature. Used at staging time (`arkdesk-cli integrity sign`).
+pub fn sign(root: &Path, subdirs: &[&str], key: &SigningKey) -> io::Result<IntegrityManifest> {
+  fn walk(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+      let p = entry?.path();
+      if p.is_dir() { walk(&p, out)?; } else if p.is_file() { out.push(p); }
+    }
+    Ok(())
+  }
+
+  let mut files = Vec::new();
+  for sub in subdirs {
+    let d = root.join(sub);
+    if d.is_dir() { walk(&d, &mut files)?; } else if d.is_file() { files.push(d); }
+  }
+
+  let mut m = IntegrityManifest { version: FORMAT_VERSION, files: BTreeMap::new() };
+  for p in files {
+    if let Some(k) = rel_key(root, &p) { m.files.insert(k, sha256_file(&p)?); }
+  }
+
+  let bytes = serde_json::to_vec_pretty(&m).map_err(io::Error::other)?;
+  fs::write(root.join(MANIFEST_FILE), &bytes)?;
+  fs::write(root.join(SIG_FILE), hex::encode(key.sign(&bytes).to_bytes()))?;
+  Ok(m)
+}
+
+/// Signing key from a file holding a hex 32-byte seed (e.g. `openssl rand -hex 32`).
+pub fn read_signing_key(p: &Path) -> Result<SigningKey, String> {
+  let s = fs::read_to_string(p).map_err(|e| format!("{}: {e}", p.display()))?;
+  let seed: [u8; 32] = hex::decode(s.trim()).ok()
+    .and_then(|b| b.try_into().ok())
+    .ok_or_else(|| format!("{}: expected 64 hex chars", p.display()))?;
+  Ok(SigningKey::from_bytes(&seed))
+}
@@ -1,4 +1,42 @@
 // src-tauri/src/lib.rs
-//! ArkDesk library crate stub.
-//! Runtime, commands, and polling live in `src/main.rs` now.
-//! Intentionally empty to avoid duplicate Tauri builders.
+//! ArkDesk backend: node supervision, install, settings, RPC and admin logic.
+//! Everything outside `desktop` is Tauri-free and shared with `arkdesk-cli`;
+//! the desktop app (`src/main.rs`) only calls `run()`.
+pub mod settings;
+pub mod config;       // layered defaults / config.json / env / CLI overrides
+pub mod home;         // choosing and moving the Arknet home
+pub mod status;
+pub mod validate;
+pub mod cleanup;
+pub mod install;
+pub mod miner;
+
+pub mod manifest;
+pub mod admin_client;
+pub mod state;
+pub mod node_control; // process supervisor
+pub mod types;        // centralized payloads
+pub mod rpc;          // RPC client
+pub mod pollers;      // admin/RPC pollers
+pub mod profiles;     // arkd launch profiles
+pub mod sysprobe;     // native process/socket inspection
+pub mod events;       // event sink (Tauri events or terminal)
+pub mod resources;    // bundled payload lookup
+pub mod integrity;    // signed payload manifest
+pub mod versions;     // side-by-side arkd installs
+pub mod bundle;       // offline update bundles
+pub mod selftest;     // installed-node diagnostics
+pub mod subprocess;   // streamed helper processes
+pub mod runner;       // dev runner and build-from-source bootstrap
+pub mod pyenv;        // managed ArkPy venv
+pub mod reconfig;     // applying saved settings to the running node
+pub mod endpoints;    // saved RPC endpoints
+pub mod presets;      // settings export/import bundles
+pub mod snapshots;    // data/ snapshots and restore
+pub mod disk;         // disk usage and low-disk watchdog
+
+#[cfg(feature = "desktop")]
+mod desktop;          // Tauri commands, pollers and builder
+
+#[cfg(feature = "desktop")]
+pub use desktop::run;
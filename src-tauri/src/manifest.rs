@@ -1,5 +1,8 @@
 use serde::Deserialize;
-use std::{fs, io, path::Path};
+use std::{fs, io, path::{Path, PathBuf}};
+
+use crate::config::{self, Key};
+use crate::settings::ark_home;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Manifest {
@@ -32,3 +35,29 @@ pub fn read_manifest(path: &Path) -> io::Result<Manifest> {
     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
   Ok(m)
 }
+
+/// Where arkd writes node.json: the `run_dir` setting (`ARK_RUN_DIR`), else
+/// `default_run_dir()`.
+pub fn default_manifest_path() -> PathBuf {
+  config::path(Key::RunDir).unwrap_or_else(default_run_dir).join("node.json")
+}
+
+/// The platform run dir if it exists, then the Arknet home's, then a
+/// project-local fallback.
+pub fn default_run_dir() -> PathBuf {
+  // 1) Windows roaming profile (APPDATA\Arknet\var\devnet\run)
+  #[cfg(target_os = "windows")]
+  {
+    if let Ok(appdata) = std::env::var("APPDATA") {
+      let p = Path::new(&appdata).join("Arknet").join("var").join("devnet").join("run");
+      if p.exists() { return p; }
+    }
+  }
+
+  // 2) <ark home>/var/devnet/run
+  let p = ark_home().join("var").join("devnet").join("run");
+  if p.exists() { return p; }
+
+  // 3) project-local fallback
+  PathBuf::from("./var/devnet/run")
+}
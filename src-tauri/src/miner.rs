@@ -1,10 +1,11 @@
 // src-tauri/src/miner.rs
 use serde::Serialize;
-use serde_json;
-use std::{fs, path::PathBuf, process::Command};
-use tauri::{AppHandle, Manager};
+use std::{path::PathBuf, process::Command};
 
-use crate::settings::{ark_home, Settings};
+use crate::install::py_in_venv;
+use crate::pyenv;
+use crate::resources::Resources;
+use crate::settings::{ark_home, try_load_settings};
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")] // <-- ensure camelCase everywhere in this struct
@@ -27,6 +28,7 @@ pub struct HostProbe {
   pub bundled_c_lib_exists: bool,
   pub using_bundled: bool,
 
+  /// Python/pip/ArkPy fields describe the managed venv, not the system interpreter.
   pub python_ok: bool,
   pub python_version: Option<String>,
   pub pip_ok: bool,
@@ -53,52 +55,10 @@ fn run_ok_out(cmd: &str, args: &[&str]) -> Option<String> {
   Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
 }
 
-fn detect_python() -> (bool, Option<String>, bool) {
-  if let Some(exe) = run_ok_out("py", &["-3", "-c", "import sys; print(sys.executable)"]) {
-    let pip_ok = run_ok_out(&exe, &["-m", "pip", "--version"]).is_some();
-    let ver = run_ok_out(&exe, &["-c", "import sys; print(sys.version.split()[0])"]);
-    return (true, ver, pip_ok);
-  }
-  for name in ["python3", "python"] {
-    if let Some(exe_path) = run_ok_out(name, &["-c", "import sys; print(sys.executable)"]) {
-      let pip_ok = run_ok_out(&exe_path, &["-m", "pip", "--version"]).is_some();
-      let ver = run_ok_out(&exe_path, &["-c", "import sys; print(sys.version.split()[0])"]);
-      return (true, ver, pip_ok);
-    }
-  }
-  (false, None, false)
-}
-
-fn python_executable() -> Option<String> {
-  if let Some(exe) = run_ok_out("py", &["-3", "-c", "import sys; print(sys.executable)"]) { return Some(exe); }
-  for name in ["python3", "python"] {
-    if let Some(exe) = run_ok_out(name, &["-c", "import sys; print(sys.executable)"]) { return Some(exe); }
-  }
-  None
-}
-
-fn detect_arkpy(python: Option<&str>) -> (bool, Option<String>) {
-  let py = match python { Some(p) => p, None => return (false, None) };
-  let ok = run_ok_out(py, &["-c", "import importlib.util as u; print(1 if u.find_spec('arkpy') else 0)"])
-    .map(|s| s == "1").unwrap_or(false);
-  let ver = run_ok_out(py, &["-c", r#"import sys
-try:
-  import importlib.metadata as md
-  print(md.version('arkpy'))
-except Exception:
-  try:
-    import pkg_resources as pr
-    print(pr.get_distribution('arkpy').version)
-  except Exception:
-    print('')
-"#]).and_then(|s| if s.is_empty(){None}else{Some(s)});
-  (ok, ver)
-}
-
 // ---------- GPU DETECTION ----------
 
 fn gi(name: String, vram_bytes: u64, driver: Option<String>) -> GpuInfo {
-  GpuInfo { name, vram_mb: (vram_bytes / (1024*1024)) as u64, vram_bytes, driver }
+  GpuInfo { name, vram_mb: vram_bytes / (1024*1024), vram_bytes, driver }
 }
 
 fn parse_nvidia_smi() -> Option<Vec<GpuInfo>> {
@@ -158,6 +118,7 @@ fn parse_windows_gpus() -> Option<Vec<GpuInfo>> {
 }
 
 #[cfg(not(target_os = "windows"))]
+#[allow(dead_code)]
 fn parse_windows_gpus() -> Option<Vec<GpuInfo>> { None }
 
 #[cfg(target_os = "macos")]
@@ -183,6 +144,7 @@ fn parse_macos_gpus() -> Option<Vec<GpuInfo>> {
 }
 
 #[cfg(not(target_os = "macos"))]
+#[allow(dead_code)]
 fn parse_macos_gpus() -> Option<Vec<GpuInfo>> { None }
 
 #[cfg(all(target_os = "linux", not(target_os = "android")))]
@@ -208,10 +170,11 @@ fn enrich_vram(mut base: Vec<GpuInfo>) -> Vec<GpuInfo> {
 #[cfg(target_os = "macos")]
 fn enrich_vram(base: Vec<GpuInfo>) -> Vec<GpuInfo> { base } // mac path already provides VRAM
 #[cfg(all(unix, not(target_os = "macos")))]
+#[allow(dead_code)]
 fn enrich_vram(base: Vec<GpuInfo>) -> Vec<GpuInfo> { base }
 
 fn sort_gpus(mut gpus: Vec<GpuInfo>) -> Vec<GpuInfo> {
-  gpus.sort_by(|a, b| b.vram_bytes.cmp(&a.vram_bytes));
+  gpus.sort_by_key(|g| std::cmp::Reverse(g.vram_bytes));
   gpus
 }
 
@@ -222,9 +185,9 @@ const LIB_NAME: &str = "libarknet.dylib";
 #[cfg(all(unix, not(target_os = "macos")))]
 const LIB_NAME: &str = "libarknet.so";
 
-fn find_bundled_lib(app: &AppHandle) -> Option<PathBuf> {
+fn find_bundled_lib(resources: &Resources) -> Option<PathBuf> {
   let mut candidates: Vec<PathBuf> = Vec::new();
-  if let Ok(res) = app.path().resource_dir() {
+  if let Some(res) = resources.dir() {
     candidates.push(res.join(LIB_NAME));
     candidates.push(res.join("arknet").join(LIB_NAME));
     candidates.push(res.join("arknet").join("lib").join(LIB_NAME));
@@ -243,59 +206,64 @@ fn find_bundled_lib(app: &AppHandle) -> Option<PathBuf> {
   candidates.into_iter().find(|p| p.exists())
 }
 
-fn load_settings_quiet() -> Option<Settings> {
-  let p = ark_home().join("config.json");
-  if !p.is_file() { return None; }
-  let s = fs::read_to_string(p).ok()?;
-  serde_json::from_str::<Settings>(&s).ok()
-}
-
 // ---------- Commands ----------
 
-#[tauri::command]
-pub fn probe_bundled_clib(app: AppHandle) -> Result<BundledCLib, String> {
-  let path = find_bundled_lib(&app);
-  Ok(BundledCLib {
+pub fn bundled_clib(resources: &Resources) -> BundledCLib {
+  let path = find_bundled_lib(resources);
+  BundledCLib {
     path: path.as_ref().map(|p| p.to_string_lossy().into_owned()),
     exists: path.as_ref().map(|p| p.is_file()).unwrap_or(false),
-  })
+  }
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
+pub fn probe_bundled_clib(app: tauri::AppHandle) -> Result<BundledCLib, String> {
+  Ok(bundled_clib(&Resources::from_app(&app)))
+}
+
+/// The managed venv's interpreter, if installed.
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn locate_python() -> Result<Option<String>, String> {
-  Ok(python_executable())
+  let py = py_in_venv(&pyenv::venv_dir(&ark_home()));
+  Ok(py.is_file().then(|| py.to_string_lossy().into_owned()))
 }
 
+/// Install ArkPy into the managed venv from the bundled wheels, (re)building
+/// the venv first if it is missing or broken.
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn install_arkpy() -> Result<(), String> {
-  let Some(py) = python_executable() else { return Err("Python not found on PATH".into()); };
-  let ok = Command::new(&py).args(["-m","pip","install","--upgrade","--user","arkpy"])
-    .status().map_err(|e| e.to_string())?;
-  if !ok.success() { return Err("pip failed installing arkpy".into()); }
-  Ok(())
+pub async fn install_arkpy(app: tauri::AppHandle, ctl: tauri::State<'_, crate::install::InstallCtl>) -> Result<(), String> {
+  let cancel = ctl.begin()?;
+  let out = pyenv::ensure(&Resources::from_app(&app), &ark_home(), &app, &cancel).await;
+  ctl.finish();
+  out.map(|_| ())
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn host_probe(app: AppHandle) -> Result<HostProbe, String> {
+pub fn host_probe(app: tauri::AppHandle) -> Result<HostProbe, String> {
+  Ok(probe_host(&Resources::from_app(&app)))
+}
+
+pub fn probe_host(resources: &Resources) -> HostProbe {
   let os = std::env::consts::OS.to_string();
   let arch = std::env::consts::ARCH.to_string();
 
-  let bundled = find_bundled_lib(&app);
+  let bundled = find_bundled_lib(resources);
   let (bundled_path, bundled_exists) = (
     bundled.as_ref().map(|p| p.to_string_lossy().into_owned()),
     bundled.as_ref().map(|p| p.is_file()).unwrap_or(false),
   );
 
-  let current = load_settings_quiet();
+  let current = try_load_settings().ok();
   let using_bundled = if let (Some(cur), Some(bp)) = (current.as_ref(), bundled_path.as_ref()) {
     std::path::Path::new(&cur.c_lib_path).eq(std::path::Path::new(bp))
   } else { false };
 
-  let (python_ok, python_version, pip_ok) = detect_python();
-  let py_exe = python_executable();
-  let (ark_py_ok, ark_py_version) = detect_arkpy(py_exe.as_deref());
-
-  let mut warnings = vec![];
+  let env = pyenv::inspect(&ark_home());
+  #[cfg_attr(all(unix, not(target_os = "macos")), allow(unused_mut))]
+  let mut warnings: Vec<String> = env.error.iter().map(|e| format!("ArkPy venv: {e}")).collect();
   let mut gpus: Vec<GpuInfo> = vec![];
   let mut cuda_ok = false;
 
@@ -331,15 +299,18 @@ pub fn host_probe(app: AppHandle) -> Result<HostProbe, String> {
 
   let gpus = sort_gpus(gpus);
 
-  Ok(HostProbe {
+  HostProbe {
     os, arch,
     bundled_c_lib_path: bundled_path,
     bundled_c_lib_exists: bundled_exists,
     using_bundled,
-    python_ok, python_version, pip_ok,
-    ark_py_ok, ark_py_version,
+    python_ok: env.python_version.is_some(),
+    python_version: env.python_version,
+    pip_ok: env.pip_version.is_some(),
+    ark_py_ok: env.ok,
+    ark_py_version: env.arkpy_version,
     cuda_ok,
     gpus,
     warnings,
-  })
+  }
 }
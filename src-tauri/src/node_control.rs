@@ -10,18 +10,29 @@ use std::{
   sync::Arc,
   time::{SystemTime, UNIX_EPOCH},
 };
-use tauri::{AppHandle, Emitter, Manager, State};
-use tauri::path::BaseDirectory;
+#[cfg(feature = "desktop")]
+use tauri::{AppHandle, State};
 use tokio::{
   io::{AsyncBufReadExt, BufReader},
   process::{Child, Command},
   sync::mpsc,
   time::{timeout, Duration, sleep},
 };
-use crate::settings::ark_home;
-
-const EVT_LOG: &str = "node://log";
-const EVT_STATUS: &str = "node://status";
+use crate::config::{self, Key};
+use crate::disk;
+use crate::events::{self, EventSink, Sink};
+use crate::manifest::default_manifest_path;
+use crate::profiles::{self, LaunchSpec};
+use crate::resources::Resources;
+use crate::state::NodeBridge;
+use crate::subprocess;
+use crate::sysprobe;
+use crate::versions;
+use crate::settings::{ark_home, try_load_settings, write_settings, Settings};
+
+pub const EVT_LOG: &str = "node://log";
+pub const EVT_STATUS: &str = "node://status";
+pub const EVT_PORTS: &str = "node://ports";
 const LOG_TAIL_MAX: usize = 2000;
 
 #[derive(Default)]
@@ -31,6 +42,9 @@ pub struct NodeProc { inner: Arc<Mutex<Inner>> }
 struct Inner {
   child: Option<Child>,
   tail: Vec<String>,
+  last_launch: Option<LaunchSpec>,
+  /// `auto_ports` of the last start, reused by `restart`.
+  last_auto_ports: bool,
   #[cfg(unix)]
   pgid: Option<i32>,
 }
@@ -39,7 +53,16 @@ struct Inner {
 struct LogEvt { ts_ms: u64, stream: &'static str, line: String }
 
 #[derive(Serialize)]
-struct StatusEvt { kind: &'static str, msg: String, pid: Option<u32>, exe: Option<String> }
+struct StatusEvt {
+  kind: &'static str,
+  msg: String,
+  pid: Option<u32>,
+  exe: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  profile: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  cmdline: Option<String>,
+}
 
 /* ───────────────── utilities ───────────────── */
 
@@ -51,11 +74,11 @@ fn now_ms() -> u64 {
     .as_millis() as u64
 }
 
-fn emit_status(app: &AppHandle, kind: &'static str, msg: String, pid: Option<u32>, exe: Option<String>) {
-  let _ = app.emit(EVT_STATUS, &StatusEvt { kind, msg, pid, exe });
+fn emit_status(sink: &dyn EventSink, kind: &'static str, msg: String, pid: Option<u32>, exe: Option<String>) {
+  events::emit(sink, EVT_STATUS, &StatusEvt { kind, msg, pid, exe, profile: None, cmdline: None });
 }
 
-fn push_and_emit(proc_arc: &Arc<Mutex<Inner>>, app: &AppHandle, stream: &'static str, line: String) {
+fn push_and_emit(proc_arc: &Arc<Mutex<Inner>>, sink: &dyn EventSink, stream: &'static str, line: String) {
   let ts = now_ms();
   let decorated = format!("[{ts}][{stream}] {line}");
   {
@@ -66,7 +89,7 @@ fn push_and_emit(proc_arc: &Arc<Mutex<Inner>>, app: &AppHandle, stream: &'static
       g.tail.drain(0..excess);
     }
   }
-  let _ = app.emit(EVT_LOG, &LogEvt { ts_ms: ts, stream, line });
+  events::emit(sink, EVT_LOG, &LogEvt { ts_ms: ts, stream, line });
 }
 
 /* ───────────────── platform helpers ───────────────── */
@@ -95,24 +118,22 @@ fn platform_dirs() -> &'static [&'static str] {
 
 /* ───────────────── arkd resolution ───────────────── */
 
-fn resolve_arkd_path(app: Option<&AppHandle>) -> Option<PathBuf> {
+pub fn resolve_arkd_path(res: Option<&Resources>) -> Option<PathBuf> {
   let arkd = exe("arkd");
 
-  if let Ok(p) = env::var("ARK_ARKD") {
-    let p = PathBuf::from(p);
+  if let Some(p) = config::path(Key::Arkd) {
     if is_exe(&p) { return Some(p); }
   }
-  if let Ok(home) = env::var("ARK_HOME") {
-    let p = PathBuf::from(home).join("bin").join(&arkd);
+  // active versions/<ver>/ first, then the legacy flat bin/
+  let home = ark_home();
+  for dir in versions::active_dir(&home).into_iter().chain([home.join("bin")]) {
+    let p = dir.join(&arkd);
     if is_exe(&p) { return Some(p); }
   }
-  let p = ark_home().join("bin").join(&arkd);
-  if is_exe(&p) { return Some(p); }
 
-  if let Some(app) = app {
+  if let Some(res) = res {
     for &plat in platform_dirs() {
-      let rel = format!("bin/{plat}");
-      if let Ok(dir) = app.path().resolve(&rel, BaseDirectory::Resource) {
+      if let Some(dir) = res.resolve(&format!("bin/{plat}")) {
         let p = dir.join(&arkd);
         if is_exe(&p) { return Some(p); }
       }
@@ -155,40 +176,60 @@ fn resolve_arkd_path(app: Option<&AppHandle>) -> Option<PathBuf> {
 
 /* ───────────────── file tailer (logs dir) ───────────────── */
 
-fn spawn_file_tailer(proc_arc: Arc<Mutex<Inner>>, app: AppHandle, logs_dir: PathBuf) {
-  tauri::async_runtime::spawn(async move {
-    let mut offsets: HashMap<PathBuf, u64> = HashMap::new();
-    loop {
-      if proc_arc.lock().child.is_none() { break; }
-
-      if let Ok(rd) = fs::read_dir(&logs_dir) {
-        for entry in rd.flatten() {
-          let path = entry.path();
-          if !path.is_file() { continue; }
-          let fname = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
-          if !(fname.ends_with(".log") || fname.eq_ignore_ascii_case("arkd.out") || fname.eq_ignore_ascii_case("arkd.log")) { continue; }
-
-          if let Ok(meta) = fs::metadata(&path) {
-            let len = meta.len();
-            let start = offsets.get(&path).copied().unwrap_or_else(|| len.saturating_sub(64 * 1024));
-            if len >= start {
-              if let Ok(mut f) = fs::File::open(&path) {
-                let _ = f.seek(SeekFrom::Start(start));
-                let mut buf = Vec::with_capacity((len - start).min(1_000_000) as usize);
-                if f.read_to_end(&mut buf).is_ok() {
-                  offsets.insert(path.clone(), len);
-                  let text = String::from_utf8_lossy(&buf);
-                  for line in text.split(|c| c == '\n' || c == '\r').filter(|l| !l.is_empty()) {
-                    push_and_emit(&proc_arc, &app, "file", format!("{fname}: {line}"));
-                  }
-                }
+/// Incremental reader over the arkd log files (`*.log`, `arkd.out`) in a directory.
+pub struct LogTailer {
+  dir: PathBuf,
+  offsets: HashMap<PathBuf, u64>,
+}
+
+impl LogTailer {
+  pub fn new(dir: PathBuf) -> Self { Self { dir, offsets: HashMap::new() } }
+
+  /// `(file name, line)` pairs appended since the last poll; a file seen for the
+  /// first time starts at its last 64 KiB.
+  pub fn poll(&mut self) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let Ok(rd) = fs::read_dir(&self.dir) else { return out };
+    for entry in rd.flatten() {
+      let path = entry.path();
+      if !path.is_file() { continue; }
+      let fname = path.file_name().and_then(|s| s.to_str()).unwrap_or("").to_string();
+      if !(fname.ends_with(".log") || fname.eq_ignore_ascii_case("arkd.out") || fname.eq_ignore_ascii_case("arkd.log")) { continue; }
+      if subprocess::is_transcript_log(&fname) { continue; }
+
+      if let Ok(meta) = fs::metadata(&path) {
+        let len = meta.len();
+        let start = self.offsets.get(&path).copied().unwrap_or_else(|| len.saturating_sub(64 * 1024));
+        if len >= start {
+          if let Ok(mut f) = fs::File::open(&path) {
+            let _ = f.seek(SeekFrom::Start(start));
+            let mut buf = Vec::with_capacity((len - start).min(1_000_000) as usize);
+            if f.read_to_end(&mut buf).is_ok() {
+              self.offsets.insert(path.clone(), len);
+              let text = String::from_utf8_lossy(&buf);
+              for line in text.split(['\n', '\r']).filter(|l| !l.is_empty()) {
+                out.push((fname.clone(), line.to_string()));
               }
-            } else {
-              offsets.insert(path.clone(), len);
             }
           }
+        } else {
+          self.offsets.insert(path.clone(), len);
         }
       }
+    }
+    out
+  }
+}
+
+fn spawn_file_tailer(proc_arc: Arc<Mutex<Inner>>, sink: Sink, logs_dir: PathBuf) {
+  tokio::spawn(async move {
+    let mut tailer = LogTailer::new(logs_dir);
+    loop {
+      if proc_arc.lock().child.is_none() { break; }
+
+      for (fname, line) in tailer.poll() {
+        push_and_emit(&proc_arc, &*sink, "file", format!("{fname}: {line}"));
+      }
 
       sleep(Duration::from_millis(700)).await;
     }
@@ -197,63 +238,161 @@ fn spawn_file_tailer(proc_arc: Arc<Mutex<Inner>>, app: AppHandle, logs_dir: Path
 
 /* ───────────────── start/stop impl ───────────────── */
 
-async fn start_impl(app: AppHandle, proc: &NodeProc, args: Option<Vec<String>>) -> Result<(), String> {
-  if proc.inner.lock().child.is_some() { return Ok(()); }
-
-  emit_status(&app, "starting", "starting arkd".into(), None, None);
-  push_and_emit(&proc.inner, &app, "sys", "starting arkd".to_string());
-
-  let exe_path = resolve_arkd_path(Some(&app)).ok_or_else(|| {
-    let msg = "arkd not found. Run installer or set ARK_ARKD/ARK_HOME, or ensure it’s on PATH.".to_string();
-    emit_status(&app, "error", msg.clone(), None, None);
-    push_and_emit(&proc.inner, &app, "sys", msg.clone());
-    msg
-  })?;
-
+/// arkd command in its own process group/session; stdout/stderr are left to the caller.
+fn arkd_command(exe_path: &Path, args: &[String], launch: &LaunchSpec) -> Command {
   #[cfg(windows)]
-  let mut child = {
+  {
     use std::process::Command as StdCommand;
     use std::os::windows::process::CommandExt;
     const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
 
-    let mut scmd = StdCommand::new(&exe_path);
-    if let Some(a) = args.as_ref() { scmd.args(a); }
-    scmd.current_dir(ark_home())
+    let mut scmd = StdCommand::new(exe_path);
+    scmd.args(args)
+        .envs(&launch.env)
+        .current_dir(&launch.work_dir)
         .stdin(Stdio::null())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
         .creation_flags(CREATE_NEW_PROCESS_GROUP);
-
-    let mut cmd = Command::from(scmd);
-    cmd.spawn().map_err(|e| format!("spawn arkd failed: {e}"))?
-  };
+    Command::from(scmd)
+  }
 
   #[cfg(not(windows))]
-  let mut child = {
-    let mut cmd = Command::new(&exe_path);
-    if let Some(a) = args.as_ref() { cmd.args(a); }
-    cmd.current_dir(ark_home())
-       .stdin(Stdio::null())
-       .stdout(Stdio::piped())
-       .stderr(Stdio::piped());
+  {
+    let mut cmd = Command::new(exe_path);
+    cmd.args(args)
+       .envs(&launch.env)
+       .current_dir(&launch.work_dir)
+       .stdin(Stdio::null());
 
     #[cfg(unix)]
-    {
-      use std::os::unix::process::CommandExt;
-      unsafe {
-        cmd.pre_exec(|| {
-          if libc::setsid() == -1 { return Err(std::io::Error::last_os_error()); }
-          Ok(())
-        });
-      }
+    unsafe {
+      cmd.pre_exec(|| {
+        if libc::setsid() == -1 { return Err(std::io::Error::last_os_error()); }
+        Ok(())
+      });
     }
-    cmd.spawn().map_err(|e| format!("spawn arkd failed: {e}"))?
-  };
+    cmd
+  }
+}
 
-  let pid = child.id();
+/// Unsupervised arkd with piped output, for short throwaway runs (self-test).
+pub(crate) fn spawn_scratch(exe_path: &Path, launch: &LaunchSpec) -> std::io::Result<Child> {
+  arkd_command(exe_path, &launch.args, launch)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+}
+
+/// Terminate a child started by `spawn_scratch` and its process group: SIGTERM, then
+/// SIGKILL after `grace` (a tree kill on Windows). True if it exited before the kill.
+pub(crate) async fn terminate_child(child: &mut Child, grace: Duration) -> bool {
+  let Some(pid) = child.id() else { return true };
+  #[cfg(unix)]
+  {
+    let _ = kill_pgroup(pid as i32, libc::SIGTERM);
+    if timeout(grace, child.wait()).await.is_ok() { return true; }
+    let _ = kill_pgroup(pid as i32, libc::SIGKILL);
+  }
+  #[cfg(windows)]
+  {
+    if timeout(grace, child.wait()).await.is_ok() { return true; }
+    kill_tree_windows(pid).await;
+  }
+  let _ = timeout(Duration::from_secs(2), child.wait()).await;
+  false
+}
+
+/// Resolve arkd and run the port preflight; returns the binary and the effective args.
+fn prepare_launch(
+  proc: &NodeProc,
+  sink: &dyn EventSink,
+  res: Option<&Resources>,
+  launch: &LaunchSpec,
+  auto_ports: bool,
+) -> Result<(PathBuf, Vec<String>), String> {
+  emit_status(sink, "starting", "starting arkd".into(), None, None);
+  push_and_emit(&proc.inner, sink, "sys", "starting arkd".to_string());
+
+  if let Err(msg) = disk::start_guard(&ark_home()) {
+    emit_status(sink, "error", msg.clone(), None, None);
+    push_and_emit(&proc.inner, sink, "sys", msg.clone());
+    return Err(msg);
+  }
+
+  let exe_path = resolve_arkd_path(res).ok_or_else(|| {
+    let msg = "arkd not found. Run installer or set ARK_ARKD/ARK_HOME, or ensure it’s on PATH.".to_string();
+    emit_status(sink, "error", msg.clone(), None, None);
+    push_and_emit(&proc.inner, sink, "sys", msg.clone());
+    msg
+  })?;
+
+  let mut args = launch.args.clone();
+  let settings = config::settings();
+  let (p2p, rpc) = launch_ports(&args, &settings);
+  let plan = plan_ports(p2p, rpc, auto_ports);
+  if !plan.conflicts.is_empty() {
+    events::emit(sink, EVT_PORTS, &plan);
+    let fail = |msg: String| {
+      emit_status(sink, "error", msg.clone(), None, None);
+      push_and_emit(&proc.inner, sink, "sys", msg.clone());
+      msg
+    };
+    if !plan.reassigned {
+      return Err(fail(describe_conflicts(&plan.conflicts)));
+    }
+
+    // ports the profile or ad-hoc args chose stay out of config.json; a broken
+    // config.json is not overwritten with defaults
+    let from_settings = [(P2P_PORT_ARG, p2p, plan.p2p_port), (RPC_PORT_ARG, rpc, plan.rpc_port)]
+      .map(|(flag, old, new)| (flag_port(&args, flag).is_none() && old != new).then_some(new));
+    if from_settings.iter().any(Option::is_some) {
+      let mut saved = try_load_settings().map_err(|e| fail(format!("cannot reassign ports: {e}")))?;
+      if let Some(p) = from_settings[0] { saved.p2p_port = p; }
+      if let Some(p) = from_settings[1] { saved.rpc_port = p; }
+      write_settings(&saved).map_err(fail)?;
+    }
+    for (flag, old, new) in [(P2P_PORT_ARG, p2p, plan.p2p_port), (RPC_PORT_ARG, rpc, plan.rpc_port)] {
+      if old != new { set_flag(&mut args, flag, new); }
+    }
+    push_and_emit(&proc.inner, sink, "sys", format!(
+      "ports reassigned: p2p {p2p} -> {}, rpc {rpc} -> {}", plan.p2p_port, plan.rpc_port,
+    ));
+  }
+  // first run: ark_home (the default cwd) may not exist yet
+  let _ = fs::create_dir_all(&launch.work_dir);
+  Ok((exe_path, args))
+}
+
+fn announce_started(proc: &NodeProc, sink: &dyn EventSink, exe_path: &Path, args: Vec<String>, launch: &LaunchSpec, pid: Option<u32>) {
   let exe_s = exe_path.to_string_lossy().to_string();
-  emit_status(&app, "started", format!("arkd spawned pid={:?}", pid), pid, Some(exe_s.clone()));
-  push_and_emit(&proc.inner, &app, "sys", format!("exec={} args={}", exe_s, args.unwrap_or_default().join(" ")));
+  let effective = LaunchSpec { args, ..launch.clone() };
+  let cmdline = effective.command_line(&exe_s);
+  events::emit(sink, EVT_STATUS, &StatusEvt {
+    kind: "started",
+    msg: format!("arkd spawned pid={:?}", pid),
+    pid,
+    exe: Some(exe_s),
+    profile: launch.profile.clone(),
+    cmdline: Some(cmdline.clone()),
+  });
+  push_and_emit(&proc.inner, sink, "sys", format!("exec={cmdline}"));
+  if let Some(Err(e)) = launch.profile.as_deref().map(profiles::mark_used) {
+    push_and_emit(&proc.inner, sink, "sys", format!("could not record last-used profile: {e}"));
+  }
+}
+
+async fn start_impl(sink: Sink, res: Option<&Resources>, proc: &NodeProc, launch: LaunchSpec, auto_ports: bool) -> Result<(), String> {
+  if proc.inner.lock().child.is_some() { return Ok(()); }
+
+  let (exe_path, args) = prepare_launch(proc, &*sink, res, &launch, auto_ports)?;
+  let mut child = arkd_command(&exe_path, &args, &launch)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("spawn arkd failed: {e}"))?;
+
+  let pid = child.id();
+  announce_started(proc, &*sink, &exe_path, args, &launch, pid);
 
   let stdout = child.stdout.take();
   let stderr = child.stderr.take();
@@ -263,38 +402,68 @@ async fn start_impl(app: AppHandle, proc: &NodeProc, args: Option<Vec<String>>)
     let mut g = proc_arc.lock();
     #[cfg(unix)] { g.pgid = pid.map(|p| p as i32); }
     g.child = Some(child);
+    g.last_launch = Some(launch);
+    g.last_auto_ports = auto_ports;
   }
 
   let (tx, mut rx) = mpsc::unbounded_channel::<(&'static str, String)>();
   if let Some(out) = stdout {
     let txo = tx.clone();
-    tauri::async_runtime::spawn(async move {
+    tokio::spawn(async move {
       let mut lines = BufReader::new(out).lines();
       while let Ok(Some(line)) = lines.next_line().await { let _ = txo.send(("stdout", line)); }
     });
   }
   if let Some(err) = stderr {
-    tauri::async_runtime::spawn(async move {
+    tokio::spawn(async move {
       let mut lines = BufReader::new(err).lines();
       while let Ok(Some(line)) = lines.next_line().await { let _ = tx.send(("stderr", line)); }
     });
   }
 
-  let app_clone = app.clone();
-  tauri::async_runtime::spawn(async move {
+  let sink_clone = sink.clone();
+  tokio::spawn(async move {
     while let Some((stream, line)) = rx.recv().await {
-      push_and_emit(&proc_arc, &app_clone, stream, line);
+      push_and_emit(&proc_arc, &*sink_clone, stream, line);
     }
   });
 
   let logs_dir = ark_home().join("logs");
   if logs_dir.is_dir() {
-    spawn_file_tailer(proc.inner.clone(), app.clone(), logs_dir);
+    spawn_file_tailer(proc.inner.clone(), sink, logs_dir);
   }
 
   Ok(())
 }
 
+/// Spawn arkd detached from this process, with stdout/stderr appended to `log_file`.
+async fn start_detached_impl(
+  sink: &dyn EventSink,
+  res: Option<&Resources>,
+  proc: &NodeProc,
+  launch: LaunchSpec,
+  auto_ports: bool,
+  log_file: &Path,
+) -> Result<Option<u32>, String> {
+  let (exe_path, args) = prepare_launch(proc, sink, res, &launch, auto_ports)?;
+  if let Some(parent) = log_file.parent() { fs::create_dir_all(parent).map_err(|e| e.to_string())?; }
+  let out = fs::OpenOptions::new().create(true).append(true).open(log_file)
+    .map_err(|e| format!("{}: {e}", log_file.display()))?;
+  let err = out.try_clone().map_err(|e| e.to_string())?;
+
+  let child = arkd_command(&exe_path, &args, &launch)
+    .stdout(Stdio::from(out))
+    .stderr(Stdio::from(err))
+    .spawn()
+    .map_err(|e| format!("spawn arkd failed: {e}"))?;
+
+  let pid = child.id();
+  announce_started(proc, sink, &exe_path, args, &launch, pid);
+  // Dropping the handle leaves the process running; it is no longer supervised here.
+  drop(child);
+  Ok(pid)
+}
+
 #[cfg(unix)]
 fn kill_pgroup(pgid: i32, sig: i32) -> std::io::Result<()> {
   let r = unsafe { libc::kill(-pgid, sig) };
@@ -315,7 +484,7 @@ async fn kill_tree_windows(pid: u32) {
     .await;
 }
 
-async fn stop_impl(proc: &NodeProc, app: Option<&AppHandle>) -> Result<(), String> {
+async fn stop_impl(proc: &NodeProc, sink: Option<&dyn EventSink>) -> Result<(), String> {
   #[cfg(unix)]
   let (mut child, pid, pgid_opt) = {
     let mut g = proc.inner.lock();
@@ -369,164 +538,249 @@ async fn stop_impl(proc: &NodeProc, app: Option<&AppHandle>) -> Result<(), Strin
   #[cfg(windows)]
   let pid_for_evt = pid_opt;
 
-  if let Some(app) = app {
-    emit_status(app, "stopped", format!("arkd stopped pid={pid_for_evt:?}"), pid_for_evt, None);
-    push_and_emit(&proc.inner, app, "sys", format!("stopped pid={pid_for_evt:?}"));
+  if let Some(sink) = sink {
+    emit_status(sink, "stopped", format!("arkd stopped pid={pid_for_evt:?}"), pid_for_evt, None);
+    push_and_emit(&proc.inner, sink, "sys", format!("stopped pid={pid_for_evt:?}"));
   }
   Ok(())
 }
 
-/* ───────────────── external probe (port / process name) ───────────────── */
-
-fn rpc_ports_to_probe() -> Vec<u16> {
-  if let Ok(v) = env::var("ARK_RPC_PORT") {
-    if let Ok(p) = v.parse::<u16>() { return vec![p]; }
+async fn wait_gone(pid: u32, within: Duration) -> bool {
+  let deadline = tokio::time::Instant::now() + within;
+  while sysprobe::pid_alive(pid) {
+    if tokio::time::Instant::now() >= deadline { return false; }
+    sleep(Duration::from_millis(200)).await;
   }
-  vec![8645] // default
+  true
 }
 
-async fn run_cmd_with_timeout(mut cmd: Command, ms: u64) -> Option<String> {
-  match timeout(Duration::from_millis(ms), cmd.output()).await.ok()? {
-    Ok(out) if out.status.success() => {
-      Some(String::from_utf8_lossy(&out.stdout).to_string())
-    }
-    _ => None,
+#[cfg(unix)]
+async fn terminate_pid(pid: u32) {
+  unsafe { libc::kill(pid as i32, libc::SIGTERM); }
+  if !wait_gone(pid, Duration::from_secs(3)).await {
+    unsafe { libc::kill(pid as i32, libc::SIGKILL); }
+    wait_gone(pid, Duration::from_secs(2)).await;
   }
 }
 
 #[cfg(windows)]
-async fn pid_listening_on(port: u16) -> Option<u32> {
-  let mut cmd = Command::new("cmd");
-  cmd.args(["/C", "netstat -ano -p tcp"]);
-  let out = run_cmd_with_timeout(cmd, 1200).await?;
-  for line in out.lines() {
-    if line.contains(&format!(":{port}")) && line.to_ascii_lowercase().contains("listen") {
-      if let Some(pid_str) = line.split_whitespace().last() {
-        if let Ok(pid) = pid_str.parse::<u32>() { return Some(pid); }
-      }
+async fn terminate_pid(pid: u32) {
+  kill_tree_windows(pid).await;
+  wait_gone(pid, Duration::from_secs(2)).await;
+}
+
+/// Stop an arkd this process does not supervise: admin shutdown first, then signal the pid.
+/// Refuses whatever else holds the rpc port, or an arkd that isn't from `home`.
+pub async fn stop_external(bridge: &NodeBridge, home: &Path) -> Result<ProbeInfo, String> {
+  let probed = probe_external_node();
+  if !probed.running { return Ok(probed); }
+  if !probed.is_arkd_in(home) {
+    return Err(format!(
+      "pid {} ({}) is not an arkd from {}; not stopping it",
+      probed.pid.map(|p| p.to_string()).unwrap_or_else(|| "?".into()),
+      probed.exe.as_deref().unwrap_or("unknown executable"),
+      home.display(),
+    ));
+  }
+
+  if let Ok(admin) = bridge.admin() {
+    let _ = admin.shutdown().await;
+  }
+  if let Some(pid) = probed.pid {
+    if !wait_gone(pid, Duration::from_secs(5)).await {
+      terminate_pid(pid).await;
+    }
+    if sysprobe::pid_alive(pid) {
+      return Err(format!("arkd pid={pid} did not exit"));
     }
   }
-  None
+  Ok(probed)
 }
 
-#[cfg(all(unix, not(target_os = "macos")))]
-async fn pid_listening_on(port: u16) -> Option<u32> {
-  // Prefer ss; fallback to lsof.
-  let mut cmd = Command::new("sh");
-  cmd.args(["-lc", &format!("command -v ss >/dev/null 2>&1 && ss -ltnp 'sport = :{port}' || true")]);
-  if let Some(out) = run_cmd_with_timeout(cmd, 1200).await {
-    for line in out.lines() {
-      if let Some(p) = line.find("pid=") {
-        let rest = &line[p + 4..];
-        let id: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
-        if let Ok(pid) = id.parse::<u32>() { return Some(pid); }
-      }
-    }
+/// What `stop_node_for_home` shut down.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StoppedNodes {
+  /// Pid of the supervised node; `NodeProc::restart` brings it back.
+  pub supervised: Option<u32>,
+  /// Pid of an arkd ArkDesk doesn't supervise.
+  pub external: Option<u32>,
+}
+
+impl StoppedNodes {
+  pub fn pid(&self) -> Option<u32> { self.supervised.or(self.external) }
+}
+
+/// Pid of an arkd running from `home`'s binaries that this process doesn't supervise.
+pub fn external_node_in(home: &Path) -> Option<u32> {
+  let probe = probe_external_node();
+  if probe.running && probe.is_arkd_in(home) { probe.pid } else { None }
+}
+
+/// Refuse to touch `home` while an unsupervised arkd runs from it.
+pub fn refuse_external_node(home: &Path) -> Result<(), String> {
+  match external_node_in(home) {
+    Some(pid) => Err(format!("arkd (pid {pid}) is still running from {}; stop it first", home.display())),
+    None => Ok(()),
   }
-  let mut cmd2 = Command::new("sh");
-  cmd2.args(["-lc", &format!("lsof -nP -iTCP:{port} -sTCP:LISTEN -t 2>/dev/null")]);
-  if let Some(out) = run_cmd_with_timeout(cmd2, 1200).await {
-    for line in out.lines() {
-      if let Ok(pid) = line.trim().parse::<u32>() { return Some(pid); }
-    }
+}
+
+/// Stop every arkd that uses `home` before touching its files: the supervised
+/// node, then one running from the home's binaries (admin shutdown via the
+/// home's node.json, then a signal). Errors if either is still running.
+pub async fn stop_node_for_home(proc: Option<&NodeProc>, home: &Path, sink: Option<&dyn EventSink>) -> Result<StoppedNodes, String> {
+  let supervised = proc.and_then(NodeProc::pid);
+  if let (Some(proc), Some(_)) = (proc, supervised) {
+    proc.stop(sink).await?;
   }
-  None
+  let external = match external_node_in(home) {
+    Some(_) => stop_external(&NodeBridge::new(default_manifest_path()), home).await?.pid,
+    None => None,
+  };
+  Ok(StoppedNodes { supervised, external })
 }
 
-#[cfg(target_os = "macos")]
-async fn pid_listening_on(port: u16) -> Option<u32> {
-  let mut cmd = Command::new("sh");
-  cmd.args(["-lc", &format!("lsof -nP -iTCP:{port} -sTCP:LISTEN -t 2>/dev/null")]);
-  let out = run_cmd_with_timeout(cmd, 1200).await?;
-  for line in out.lines() {
-    if let Ok(pid) = line.trim().parse::<u32>() { return Some(pid); }
+/* ───────────────── port preflight ───────────────── */
+
+pub(crate) const P2P_PORT_ARG: &str = "--p2p-port";
+pub(crate) const RPC_PORT_ARG: &str = "--rpc-port";
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortConflict {
+  port: u16,
+  purpose: &'static str, // "p2p" | "rpc"
+  pid: Option<u32>,
+  exe: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortPlan {
+  p2p_port: u16,
+  rpc_port: u16,
+  reassigned: bool,
+  conflicts: Vec<PortConflict>,
+}
+
+/// True if nothing is listening on `port`, either on the wildcard or on loopback.
+fn port_is_free(port: u16) -> bool {
+  use std::net::{Ipv4Addr, TcpListener};
+  // Bind one at a time: holding the wildcard listener would make the loopback bind fail.
+  if TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).is_err() { return false; }
+  TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+/// Ask the OS for a free port that isn't in `avoid`.
+pub(crate) fn ephemeral_port(avoid: &[u16]) -> Option<u16> {
+  use std::net::{Ipv4Addr, TcpListener};
+  for _ in 0..16 {
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).ok()?.local_addr().ok()?.port();
+    if !avoid.contains(&port) && port_is_free(port) { return Some(port); }
   }
   None
 }
 
-#[cfg(windows)]
-async fn exe_for_pid(pid: u32) -> Option<String> {
-  let mut cmd = Command::new("powershell");
-  cmd.args([
-    "-NoProfile","-NonInteractive","-Command",
-    &format!("(Get-Process -Id {}).Path", pid),
-  ]);
-  let out = run_cmd_with_timeout(cmd, 800).await?;
-  let p = out.trim();
-  if p.is_empty() { None } else { Some(p.to_string()) }
-}
-
-#[cfg(all(unix, not(target_os = "macos")))]
-async fn exe_for_pid(pid: u32) -> Option<String> {
-  let link = format!("/proc/{pid}/exe");
-  match fs::read_link(&link) {
-    Ok(p) => Some(p.to_string_lossy().to_string()),
-    Err(_) => {
-      let mut cmd = Command::new("sh");
-      cmd.args(["-lc", &format!("ps -p {} -o comm=", pid)]);
-      run_cmd_with_timeout(cmd, 600).await.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
-    }
-  }
+fn port_conflict(port: u16, purpose: &'static str) -> Option<PortConflict> {
+  if port_is_free(port) { return None; }
+  let pid = pid_listening_on(port);
+  let exe = match pid { Some(p) => exe_for_pid(p), None => None };
+  Some(PortConflict { port, purpose, pid, exe })
 }
 
-#[cfg(target_os = "macos")]
-async fn exe_for_pid(pid: u32) -> Option<String> {
-  let mut cmd = Command::new("sh");
-  cmd.args(["-lc", &format!("ps -p {} -o comm=", pid)]);
-  run_cmd_with_timeout(cmd, 600).await.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+/// Value of `flag <port>` in arkd's args, if given.
+fn flag_port(args: &[String], flag: &str) -> Option<u16> {
+  let i = args.iter().position(|a| a == flag)?;
+  args.get(i + 1)?.parse().ok()
 }
 
-async fn find_arkd_by_name() -> Option<u32> {
-  #[cfg(windows)]
-  {
-    let mut cmd = Command::new("cmd");
-    cmd.args(["/C", "tasklist /FI \"IMAGENAME eq arkd.exe\" /FO CSV /NH"]);
-    if let Some(out) = run_cmd_with_timeout(cmd, 800).await {
-      for line in out.lines() {
-        let l = line.trim_matches('"');
-        if l.to_ascii_lowercase().starts_with("arkd.exe") {
-          let parts: Vec<&str> = l.split("\",\"").collect();
-          if parts.len() > 1 {
-            if let Ok(pid) = parts[1].replace(',', "").parse::<u32>() {
-              return Some(pid);
-            }
-          }
-        }
-      }
-    }
-    None
+/// The p2p and rpc ports arkd will bind: from its args, else from settings.
+fn launch_ports(args: &[String], s: &Settings) -> (u16, u16) {
+  (flag_port(args, P2P_PORT_ARG).unwrap_or(s.p2p_port), flag_port(args, RPC_PORT_ARG).unwrap_or(s.rpc_port))
+}
+
+/// Probe the ports arkd will bind; with `auto`, replace every conflicting port with a free one.
+fn plan_ports(p2p_port: u16, rpc_port: u16, auto: bool) -> PortPlan {
+  let mut conflicts = Vec::new();
+  if let Some(c) = port_conflict(p2p_port, "p2p") { conflicts.push(c); }
+  if rpc_port == p2p_port {
+    conflicts.push(PortConflict { port: rpc_port, purpose: "rpc", pid: None, exe: None });
+  } else if let Some(c) = port_conflict(rpc_port, "rpc") {
+    conflicts.push(c);
   }
-  #[cfg(any(target_os = "macos", all(unix, not(target_os = "macos"))))]
-  {
-    let mut cmd = Command::new("sh");
-    cmd.args(["-lc", "pgrep -x arkd || pgrep -f '/arkd(\\s|$)' || true"]);
-    if let Some(out) = run_cmd_with_timeout(cmd, 600).await {
-      for line in out.lines() {
-        if let Ok(pid) = line.trim().parse::<u32>() { return Some(pid); }
-      }
-    }
-    None
+
+  let mut plan = PortPlan { p2p_port, rpc_port, reassigned: false, conflicts };
+  if !auto || plan.conflicts.is_empty() { return plan; }
+
+  let (mut p2p, mut rpc) = (plan.p2p_port, plan.rpc_port);
+  for c in &plan.conflicts {
+    let Some(port) = ephemeral_port(&[p2p, rpc]) else { return plan; };
+    if c.purpose == "p2p" { p2p = port } else { rpc = port }
+  }
+  plan.p2p_port = p2p;
+  plan.rpc_port = rpc;
+  plan.reassigned = true;
+  plan
+}
+
+fn describe_conflicts(conflicts: &[PortConflict]) -> String {
+  let parts: Vec<String> = conflicts.iter().map(|c| {
+    let owner = match (c.pid, c.exe.as_deref()) {
+      (Some(pid), Some(exe)) => format!("pid {pid} ({exe})"),
+      (Some(pid), None) => format!("pid {pid}"),
+      (None, _) if c.purpose == "rpc" => "the p2p port".to_string(),
+      (None, _) => "an unknown process".to_string(),
+    };
+    format!("{} port {} is in use by {}", c.purpose, c.port, owner)
+  }).collect();
+  format!("port conflict: {}. Stop the other process, change ports in settings, or start with automatic ports.", parts.join("; "))
+}
+
+/// Replace `flag <value>` in `args`, or append it.
+fn set_flag(args: &mut Vec<String>, flag: &str, value: u16) {
+  match args.iter().position(|a| a == flag) {
+    Some(i) if i + 1 < args.len() => args[i + 1] = value.to_string(),
+    Some(_) => args.push(value.to_string()),
+    None => { args.push(flag.to_string()); args.push(value.to_string()); }
   }
 }
 
+/* ───────────────── external probe (port / process name) ───────────────── */
+
+fn rpc_ports_to_probe() -> Vec<u16> {
+  vec![config::settings().rpc_port]
+}
+
+fn pid_listening_on(port: u16) -> Option<u32> { sysprobe::pid_listening_on(port) }
+
+fn exe_for_pid(pid: u32) -> Option<String> { sysprobe::exe_for_pid(pid) }
+
+fn find_arkd_by_name() -> Option<u32> { sysprobe::find_pid_by_name("arkd") }
+
 #[derive(Serialize, Debug, Clone)]
 pub struct ProbeInfo {
-  running: bool,
-  pid: Option<u32>,
-  exe: Option<String>,
-  port: Option<u16>,
-  source: &'static str, // "internal" | "port" | "name" | "none"
+  pub running: bool,
+  pub pid: Option<u32>,
+  pub exe: Option<String>,
+  pub port: Option<u16>,
+  pub source: &'static str, // "internal" | "port" | "name" | "none"
 }
 
-async fn probe_external_node() -> ProbeInfo {
+impl ProbeInfo {
+  /// The probed process is an `arkd` executable under `home`.
+  pub fn is_arkd_in(&self, home: &Path) -> bool {
+    self.exe.as_deref().map(Path::new).is_some_and(|e| e.starts_with(home) && e.file_stem().is_some_and(|s| s == "arkd"))
+  }
+}
+
+pub fn probe_external_node() -> ProbeInfo {
   for p in rpc_ports_to_probe() {
-    if let Some(pid) = pid_listening_on(p).await {
-      let exe = exe_for_pid(pid).await;
+    if let Some(pid) = pid_listening_on(p) {
+      let exe = exe_for_pid(pid);
       return ProbeInfo { running: true, pid: Some(pid), exe, port: Some(p), source: "port" };
     }
   }
-  if let Some(pid) = find_arkd_by_name().await {
-    let exe = exe_for_pid(pid).await;
+  if let Some(pid) = find_arkd_by_name() {
+    let exe = exe_for_pid(pid);
     return ProbeInfo { running: true, pid: Some(pid), exe, port: None, source: "name" };
   }
   ProbeInfo { running: false, pid: None, exe: None, port: None, source: "none" }
@@ -548,78 +802,164 @@ fn internal_child_status(proc: &NodeProc) -> Option<u32> {
   pid
 }
 
+/* ───────────────── supervisor API ───────────────── */
+
+/// Launch spec for a start request: a named profile (plus extra args), ad-hoc args,
+/// or, with neither, the last-used profile.
+pub fn launch_for(profile: Option<String>, args: Option<Vec<String>>) -> Result<LaunchSpec, String> {
+  Ok(match (profile, args) {
+    (Some(name), extra) => {
+      let mut l = profiles::resolve(&name)?;
+      l.args.extend(extra.unwrap_or_default());
+      l
+    }
+    (None, Some(a)) => LaunchSpec::adhoc(a),
+    (None, None) => profiles::last_used().unwrap_or_else(|| LaunchSpec::adhoc(Vec::new())),
+  })
+}
+
+impl NodeProc {
+  /// Spawn and supervise arkd; output is streamed to `sink` and kept in the log tail.
+  pub async fn start(&self, sink: Sink, res: Option<&Resources>, launch: LaunchSpec, auto_ports: bool) -> Result<(), String> {
+    start_impl(sink, res, self, launch, auto_ports).await
+  }
+
+  /// Spawn arkd without supervising it (output goes to `log_file`); returns its pid.
+  pub async fn start_detached(
+    &self,
+    sink: &dyn EventSink,
+    res: Option<&Resources>,
+    launch: LaunchSpec,
+    auto_ports: bool,
+    log_file: &Path,
+  ) -> Result<Option<u32>, String> {
+    start_detached_impl(sink, res, self, launch, auto_ports, log_file).await
+  }
+
+  pub async fn stop(&self, sink: Option<&dyn EventSink>) -> Result<(), String> {
+    stop_impl(self, sink).await
+  }
+
+  /// Restart with the previous launch and port policy (or the last-used profile).
+  pub async fn restart(&self, sink: Sink, res: Option<&Resources>) -> Result<(), String> {
+    let (last, auto_ports) = {
+      let g = self.inner.lock();
+      (g.last_launch.clone(), g.last_auto_ports)
+    };
+    let launch = last.or_else(profiles::last_used).unwrap_or_else(|| LaunchSpec::adhoc(Vec::new()));
+    stop_impl(self, Some(&*sink)).await?;
+    start_impl(sink, res, self, launch, auto_ports).await
+  }
+
+  /// Pid of the supervised child, if it is still alive.
+  pub fn pid(&self) -> Option<u32> { internal_child_status(self) }
+
+  pub fn log_tail(&self, n: usize) -> Vec<String> {
+    let g = self.inner.lock();
+    let start = g.tail.len().saturating_sub(n);
+    g.tail[start..].to_vec()
+  }
+
+  pub fn clear_log(&self) { self.inner.lock().tail.clear(); }
+
+  /// Supervised child first, then anything listening on the RPC port or named arkd.
+  pub fn probe(&self) -> ProbeInfo {
+    if let Some(pid) = internal_child_status(self) {
+      let exe = exe_for_pid(pid);
+      return ProbeInfo { running: true, pid: Some(pid), exe, port: None, source: "internal" };
+    }
+    probe_external_node()
+  }
+}
+
+/// Check the configured p2p/rpc ports without starting anything.
+pub fn port_check(auto_ports: bool) -> PortPlan {
+  // the ports a plain start would use: last-used profile over settings
+  let args = launch_for(None, None).map(|l| l.args).unwrap_or_default();
+  let (p2p, rpc) = launch_ports(&args, &config::settings());
+  plan_ports(p2p, rpc, auto_ports)
+}
+
 /* ───────────────── tauri commands ───────────────── */
 
+#[cfg(feature = "desktop")]
+fn app_sink(app: &AppHandle) -> Sink { Arc::new(app.clone()) }
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn node_is_running(proc: State<'_, NodeProc>) -> Result<bool, String> {
-  if internal_child_status(&proc).is_some() {
-    return Ok(true);
-  }
-  let probed = probe_external_node().await;
-  Ok(probed.running)
+  Ok(proc.probe().running)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn node_pid(proc: State<'_, NodeProc>) -> Result<Option<u32>, String> {
-  if let Some(pid) = internal_child_status(&proc) {
-    return Ok(Some(pid));
-  }
-  let probed = probe_external_node().await;
-  Ok(probed.pid)
+  Ok(proc.probe().pid)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn node_exec_path(app: AppHandle) -> Option<String> {
-  resolve_arkd_path(Some(&app))
+  resolve_arkd_path(Some(&Resources::from_app(&app)))
     .and_then(|p| p.canonicalize().ok())
     .map(|p| p.to_string_lossy().to_string())
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn node_log_tail(proc: State<'_, NodeProc>, n: Option<usize>) -> Vec<String> {
-  let g = proc.inner.lock();
-  let len = g.tail.len();
-  let start = len.saturating_sub(n.unwrap_or(200));
-  g.tail[start..].to_vec()
+  proc.log_tail(n.unwrap_or(200))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn node_log_clear(proc: State<'_, NodeProc>) {
-  proc.inner.lock().tail.clear();
+  proc.clear_log();
 }
 
 #[derive(Serialize)]
 pub struct StartOk { started: bool }
 
+/// Start arkd from a named profile (plus optional extra args), from ad-hoc args,
+/// or, with neither, from the last-used profile.
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub async fn node_start(app: AppHandle, proc: State<'_, NodeProc>, args: Option<Vec<String>>) -> Result<StartOk, String> {
-  start_impl(app.clone(), &proc, args).await?;
+pub async fn node_start(
+  app: AppHandle,
+  proc: State<'_, NodeProc>,
+  args: Option<Vec<String>>,
+  profile: Option<String>,
+  auto_ports: Option<bool>,
+) -> Result<StartOk, String> {
+  let launch = launch_for(profile, args)?;
+  let res = Resources::from_app(&app);
+  proc.start(app_sink(&app), Some(&res), launch, auto_ports.unwrap_or(false)).await?;
   Ok(StartOk { started: true })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn node_stop(app: AppHandle, proc: State<'_, NodeProc>) -> Result<(), String> {
-  stop_impl(&proc, Some(&app)).await
+  proc.stop(Some(&app)).await
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn node_restart(app: AppHandle, proc: State<'_, NodeProc>) -> Result<(), String> {
-  stop_impl(&proc, Some(&app)).await?;
-  start_impl(app, &proc, None).await
+  let res = Resources::from_app(&app);
+  proc.restart(app_sink(&app), Some(&res)).await
+}
+
+/// Check the configured p2p/rpc ports without starting anything.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn node_port_check(auto_ports: Option<bool>) -> Result<PortPlan, String> {
+  Ok(port_check(auto_ports.unwrap_or(false)))
 }
 
 /* Extra: expose a rich probe for the UI */
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn node_probe(proc: State<'_, NodeProc>) -> Result<ProbeInfo, String> {
-  if let Some(pid) = internal_child_status(&proc) {
-    let exe = exe_for_pid(pid).await;
-    return Ok(ProbeInfo {
-      running: true,
-      pid: Some(pid),
-      exe,
-      port: None,
-      source: "internal",
-    });
-  }
-  Ok(probe_external_node().await)
+  Ok(proc.probe())
 }
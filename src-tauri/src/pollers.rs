@@ -0,0 +1,134 @@
+// src/pollers.rs
+//! Background admin/RPC pollers. Each loop is a thin `tick()` + sleep so a single
+//! round can be driven directly (tests, CLI) against any `EventSink`.
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+use crate::events::{self, EventSink, Sink};
+use crate::state::NodeBridge;
+use crate::types::{ChainTip, MempoolInfo, Stale};
+
+/* ---- events ---- */
+pub const EVT_HEALTH:  &str = "node://health";
+pub const EVT_STATUS:  &str = "node://status";
+pub const EVT_TIP:     &str = "node://tip";
+pub const EVT_MEMPOOL: &str = "node://mempool";
+pub const EVT_CAPS:    &str = "node://caps";
+pub const EVT_STALE:   &str = "node://stale";
+
+pub const POLL_EVERY:  Duration = Duration::from_millis(1000);
+pub const STALE_EVERY: Duration = Duration::from_millis(2000);
+
+fn now_ms() -> u64 {
+  use std::time::{SystemTime, UNIX_EPOCH};
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_millis() as u64
+}
+
+/// Last-success timestamps (ms since epoch, 0 = never).
+#[derive(Default)]
+pub struct Stamps {
+  pub admin_ok_ms:  AtomicU64,
+  pub status_ok_ms: AtomicU64,
+  pub tip_ok_ms:    AtomicU64,
+  pub mem_ok_ms:    AtomicU64,
+}
+
+impl Stamps {
+  pub fn stale(&self) -> Stale {
+    let now = now_ms();
+    let age = |t: u64| if t == 0 { None } else { Some(now.saturating_sub(t)) };
+    Stale {
+      now_ms: now,
+      admin_age_ms:   age(self.admin_ok_ms.load(Ordering::Relaxed)),
+      status_age_ms:  age(self.status_ok_ms.load(Ordering::Relaxed)),
+      tip_age_ms:     age(self.tip_ok_ms.load(Ordering::Relaxed)),
+      mempool_age_ms: age(self.mem_ok_ms.load(Ordering::Relaxed)),
+    }
+  }
+}
+
+/* ---- admin: healthz + status ---- */
+
+pub async fn status_tick(sink: &dyn EventSink, bridge: &NodeBridge, stamps: &Stamps) {
+  bridge.maybe_refresh();
+
+  if let Ok(admin) = bridge.admin() {
+    if let Ok(h) = admin.healthz().await {
+      stamps.admin_ok_ms.store(now_ms(), Ordering::Relaxed);
+      events::emit(sink, EVT_HEALTH, &h);
+    }
+    if let Ok(s) = admin.status().await {
+      stamps.status_ok_ms.store(now_ms(), Ordering::Relaxed);
+      events::emit(sink, EVT_STATUS, &s);
+    }
+  }
+}
+
+pub async fn status_poller(sink: Sink, bridge: NodeBridge, stamps: Arc<Stamps>) {
+  loop {
+    status_tick(&*sink, &bridge, &stamps).await;
+    sleep(POLL_EVERY).await;
+  }
+}
+
+/* ---- rpc: caps (once), tip (on change), mempool ---- */
+
+#[derive(Default)]
+pub struct RpcPoller {
+  last_tip_key: Option<(u64, Option<String>)>,
+  caps_sent: bool,
+}
+
+impl RpcPoller {
+  pub async fn tick(&mut self, sink: &dyn EventSink, bridge: &NodeBridge, stamps: &Stamps) {
+    bridge.maybe_refresh();
+
+    let Ok(rpc) = bridge.rpc() else { return };
+
+    // one-time capability snapshot
+    if !self.caps_sent {
+      if let Ok(list) = rpc.call::<Vec<String>, _>("rpc.list", json!({})).await {
+        events::emit(sink, EVT_CAPS, &list);
+        self.caps_sent = true;
+      }
+    }
+
+    // tip (only on change)
+    if let Ok(tip) = rpc.call::<ChainTip, _>("chain.tip", json!({})).await {
+      let key = (tip.height, tip.block_id.clone());
+      if self.last_tip_key.as_ref() != Some(&key) {
+        stamps.tip_ok_ms.store(now_ms(), Ordering::Relaxed);
+        events::emit(sink, EVT_TIP, &tip);
+        self.last_tip_key = Some(key);
+      }
+    }
+
+    // mempool (steady cadence)
+    if let Ok(mp) = rpc.call::<MempoolInfo, _>("mempool.info", json!({})).await {
+      stamps.mem_ok_ms.store(now_ms(), Ordering::Relaxed);
+      events::emit(sink, EVT_MEMPOOL, &mp);
+    }
+  }
+}
+
+pub async fn rpc_poller(sink: Sink, bridge: NodeBridge, stamps: Arc<Stamps>) {
+  let mut poller = RpcPoller::default();
+  loop {
+    poller.tick(&*sink, &bridge, &stamps).await;
+    sleep(POLL_EVERY).await;
+  }
+}
+
+/* ---- staleness ---- */
+
+pub async fn stale_emitter(sink: Sink, stamps: Arc<Stamps>) {
+  loop {
+    events::emit(&*sink, EVT_STALE, &stamps.stale());
+    sleep(STALE_EVERY).await;
+  }
+}
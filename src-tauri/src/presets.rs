@@ -0,0 +1,155 @@
+// src/presets.rs
+//! One-file settings bundles: `config.json`, launch profiles (the arkd args)
+//! and saved endpoints, exported on one machine and imported on another.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+  fs,
+  path::Path,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::endpoints::{self, EndpointStore};
+use crate::profiles::{self, ProfileStore};
+use crate::reconfig::{self, FieldChange};
+use crate::settings::{self, ark_home, load_settings, write_settings};
+use crate::validate::{self, Diagnostic};
+
+pub const BUNDLE_FORMAT: &str = "arkdesk-settings";
+pub const BUNDLE_VERSION: u32 = 1;
+/// Stands in for endpoint header values left out of an export.
+pub const REDACTED: &str = "<redacted>";
+
+/* ── TYPES ──────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+  pub format: String,
+  pub version: u32,
+  #[serde(default)]
+  pub exported_at: u64,
+  /// `config.json` as written, migrated on import like the file itself.
+  pub settings: Value,
+  #[serde(default)]
+  pub launch_profiles: ProfileStore,
+  #[serde(default)]
+  pub endpoints: EndpointStore,
+  /// Header values were replaced by `REDACTED`.
+  #[serde(default)]
+  pub redacted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+  /// What changed in `config.json`, and what it takes to apply.
+  pub changes: Vec<FieldChange>,
+  /// Problems with the imported settings on this machine (saved regardless).
+  pub diagnostics: Vec<Diagnostic>,
+  pub profiles: Vec<String>,
+  pub endpoints: Vec<String>,
+  /// `endpoint: header` left out: redacted in the bundle and not set here.
+  pub missing_secrets: Vec<String>,
+}
+
+/* ── EXPORT ─────────────────────────────────────────────────────────────── */
+
+/// Current settings, launch profiles and endpoints; header values are redacted
+/// unless `include_secrets`.
+pub fn collect(include_secrets: bool) -> SettingsBundle {
+  let mut endpoints = endpoints::load();
+  if !include_secrets {
+    for ep in endpoints.items.values_mut() {
+      ep.headers.values_mut().for_each(|v| *v = REDACTED.to_string());
+    }
+  }
+  SettingsBundle {
+    format: BUNDLE_FORMAT.into(),
+    version: BUNDLE_VERSION,
+    exported_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+    settings: serde_json::to_value(load_settings()).unwrap(),
+    launch_profiles: profiles::load(),
+    endpoints,
+    redacted: !include_secrets,
+  }
+}
+
+pub fn export(path: &Path, include_secrets: bool) -> Result<SettingsBundle, String> {
+  let bundle = collect(include_secrets);
+  if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+    fs::create_dir_all(dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
+  }
+  let data = serde_json::to_vec_pretty(&bundle).unwrap();
+  fs::write(path, data).map_err(|e| format!("write {}: {e}", path.display()))?;
+  Ok(bundle)
+}
+
+/* ── IMPORT ─────────────────────────────────────────────────────────────── */
+
+pub fn read(path: &Path) -> Result<SettingsBundle, String> {
+  let bytes = fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+  let bundle: SettingsBundle = serde_json::from_slice(&bytes).map_err(|e| format!("{}: {e}", path.display()))?;
+  if bundle.format != BUNDLE_FORMAT {
+    return Err(format!("{}: not an ArkDesk settings bundle", path.display()));
+  }
+  if bundle.version > BUNDLE_VERSION {
+    return Err(format!("{}: bundle version {} is newer than this ArkDesk supports", path.display(), bundle.version));
+  }
+  Ok(bundle)
+}
+
+/// Apply `bundle` over the local state: settings are replaced, profiles and
+/// endpoints are merged by name (the bundle wins). Redacted header values keep
+/// the local value, or are dropped and reported. Does not restart anything.
+pub fn apply(bundle: SettingsBundle) -> Result<ImportReport, String> {
+  let new = settings::migrate(bundle.settings).map_err(|e| format!("settings: {e}"))?;
+  let changes = reconfig::diff(&load_settings(), &new);
+  let diagnostics = validate::check(&new, &ark_home());
+
+  let mut local_eps = endpoints::load();
+  let mut missing_secrets = Vec::new();
+  let mut endpoint_ids: Vec<String> = bundle.endpoints.items.keys().cloned().collect();
+  endpoint_ids.sort();
+  for (id, mut ep) in bundle.endpoints.items {
+    let local = local_eps.items.get(&id).map(|e| e.headers.clone()).unwrap_or_default();
+    ep.headers.retain(|k, v| {
+      if v != REDACTED { return true; }
+      match local.get(k) {
+        Some(mine) => { v.clone_from(mine); true }
+        None => { missing_secrets.push(format!("{id}: {k}")); false }
+      }
+    });
+    endpoints::upsert(&mut local_eps, ep);
+  }
+  if local_eps.active_id.is_none() { local_eps.active_id = bundle.endpoints.active_id; }
+  missing_secrets.sort();
+
+  let mut local_profiles = profiles::load();
+  let profile_names: Vec<String> = bundle.launch_profiles.items.keys().cloned().collect();
+  local_profiles.items.extend(bundle.launch_profiles.items);
+  if local_profiles.last_used.is_none() { local_profiles.last_used = bundle.launch_profiles.last_used; }
+
+  write_settings(&new)?;
+  profiles::save(&local_profiles).map_err(|e| format!("save launch profiles: {e}"))?;
+  endpoints::save(&local_eps).map_err(|e| format!("save endpoints: {e}"))?;
+  Ok(ImportReport { changes, diagnostics, profiles: profile_names, endpoints: endpoint_ids, missing_secrets })
+}
+
+pub fn import(path: &Path) -> Result<ImportReport, String> {
+  apply(read(path)?)
+}
+
+/* ── COMMANDS ───────────────────────────────────────────────────────────── */
+
+/// Write a settings bundle to `path`; endpoint headers are redacted unless `include_secrets`.
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn settings_export(path: String, include_secrets: Option<bool>) -> Result<SettingsBundle, String> {
+  export(Path::new(&path), include_secrets.unwrap_or(false))
+}
+
+/// Import a settings bundle; restart-worthy changes are reported, not applied.
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn settings_import(path: String) -> Result<ImportReport, String> {
+  import(Path::new(&path))
+}
@@ -0,0 +1,190 @@
+// src/profiles.rs
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::BTreeMap,
+  fs,
+  io::{self, Write},
+  path::PathBuf,
+};
+
+use crate::settings::{ark_home, NodeRole};
+
+pub(crate) const DATA_DIR_ARG: &str = "--data-dir";
+const LOG_LEVEL_ARG: &str = "--log-level";
+const ROLE_ARG: &str = "--role";
+
+/// Named arkd launch configuration, stored next to config.json.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProfile {
+  pub name: String,
+  #[serde(default)]
+  pub args: Vec<String>,
+  #[serde(default)]
+  pub env: BTreeMap<String, String>,
+  #[serde(default)]
+  pub work_dir: Option<String>,
+  #[serde(default)]
+  pub data_dir: Option<String>,
+  #[serde(default)]
+  pub log_level: Option<String>,
+  #[serde(default)]
+  pub role: Option<NodeRole>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStore {
+  #[serde(default)]
+  pub last_used: Option<String>,
+  #[serde(default)]
+  pub items: BTreeMap<String, LaunchProfile>,
+}
+
+/// Fully resolved launch: what actually gets passed to arkd.
+#[derive(Debug, Clone)]
+pub struct LaunchSpec {
+  pub profile: Option<String>,
+  pub args: Vec<String>,
+  pub env: BTreeMap<String, String>,
+  pub work_dir: PathBuf,
+}
+
+impl LaunchSpec {
+  /// Ad-hoc launch without a profile (legacy `node_start(args)`).
+  pub fn adhoc(args: Vec<String>) -> Self {
+    Self { profile: None, args, env: BTreeMap::new(), work_dir: ark_home() }
+  }
+
+  pub fn from_profile(p: &LaunchProfile) -> Self {
+    let mut args = p.args.clone();
+    if let Some(d) = p.data_dir.as_deref().filter(|s| !s.is_empty()) {
+      args.push(DATA_DIR_ARG.into());
+      args.push(d.into());
+    }
+    if let Some(l) = p.log_level.as_deref().filter(|s| !s.is_empty()) {
+      args.push(LOG_LEVEL_ARG.into());
+      args.push(l.into());
+    }
+    if let Some(r) = &p.role {
+      args.push(ROLE_ARG.into());
+      args.push(r.as_str().into());
+    }
+    let work_dir = p.work_dir.as_deref()
+      .filter(|s| !s.is_empty())
+      .map(PathBuf::from)
+      .unwrap_or_else(ark_home);
+    Self { profile: Some(p.name.clone()), args, env: p.env.clone(), work_dir }
+  }
+
+  /// Shell-ish rendering for logs and status events (env first, then exe and args).
+  pub fn command_line(&self, exe: &str) -> String {
+    let mut parts: Vec<String> = self.env.iter().map(|(k, v)| format!("{k}={}", quote(v))).collect();
+    parts.push(quote(exe));
+    parts.extend(self.args.iter().map(|a| quote(a)));
+    parts.join(" ")
+  }
+}
+
+fn quote(s: &str) -> String {
+  if !s.is_empty() && !s.chars().any(|c| c.is_whitespace() || c == '"' || c == '\'') {
+    return s.to_string();
+  }
+  format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+/* ── STORE IO ────────────────────────────────────────────────────────────── */
+
+fn profiles_path() -> PathBuf { ark_home().join("profiles.json") }
+
+pub fn load() -> ProfileStore {
+  let Ok(bytes) = fs::read(profiles_path()) else { return ProfileStore::default() };
+  serde_json::from_slice(&bytes).unwrap_or_default()
+}
+
+pub(crate) fn save(store: &ProfileStore) -> io::Result<()> {
+  let p = profiles_path();
+  if let Some(parent) = p.parent() { fs::create_dir_all(parent)?; }
+  let tmp = p.with_extension("json.tmp");
+  let mut f = fs::File::create(&tmp)?;
+  f.write_all(&serde_json::to_vec_pretty(store).unwrap())?;
+  f.flush()?;
+  fs::rename(tmp, p)?;
+  Ok(())
+}
+
+/// Remember `name` as the profile to reuse on restart. Ad-hoc starts don't
+/// call this, so the user's pick survives them.
+pub fn mark_used(name: &str) -> io::Result<()> {
+  let mut store = load();
+  if store.last_used.as_deref() == Some(name) { return Ok(()); }
+  store.last_used = Some(name.to_string());
+  save(&store)
+}
+
+/// Resolve a named profile into a launch spec.
+pub fn resolve(name: &str) -> Result<LaunchSpec, String> {
+  load().items.get(name)
+    .map(LaunchSpec::from_profile)
+    .ok_or_else(|| format!("launch profile not found: {name}"))
+}
+
+/// The last-used profile, if it still exists.
+pub fn last_used() -> Option<LaunchSpec> {
+  let store = load();
+  store.last_used.as_deref()
+    .and_then(|n| store.items.get(n))
+    .map(LaunchSpec::from_profile)
+}
+
+fn check_name(name: &str) -> Result<(), String> {
+  if name.trim().is_empty() { return Err("profile name must not be empty".into()); }
+  Ok(())
+}
+
+/* ── COMMANDS ────────────────────────────────────────────────────────────── */
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn launch_profiles_list() -> Result<ProfileStore, String> { Ok(load()) }
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn launch_profile_create(profile: LaunchProfile) -> Result<(), String> {
+  check_name(&profile.name)?;
+  let mut store = load();
+  if store.items.contains_key(&profile.name) {
+    return Err(format!("launch profile already exists: {}", profile.name));
+  }
+  store.items.insert(profile.name.clone(), profile);
+  save(&store).map_err(|e| e.to_string())
+}
+
+/// Update `name` in place; `profile.name` may differ to rename it.
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn launch_profile_update(name: String, profile: LaunchProfile) -> Result<(), String> {
+  check_name(&profile.name)?;
+  let mut store = load();
+  if !store.items.contains_key(&name) {
+    return Err(format!("launch profile not found: {name}"));
+  }
+  if profile.name != name && store.items.contains_key(&profile.name) {
+    return Err(format!("launch profile already exists: {}", profile.name));
+  }
+  store.items.remove(&name);
+  if store.last_used.as_deref() == Some(name.as_str()) {
+    store.last_used = Some(profile.name.clone());
+  }
+  store.items.insert(profile.name.clone(), profile);
+  save(&store).map_err(|e| e.to_string())
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn launch_profile_delete(name: String) -> Result<(), String> {
+  let mut store = load();
+  if store.items.remove(&name).is_none() {
+    return Err(format!("launch profile not found: {name}"));
+  }
+  if store.last_used.as_deref() == Some(name.as_str()) {
+    store.last_used = None;
+  }
+  save(&store).map_err(|e| e.to_string())
+}
@@ -0,0 +1,245 @@
+// src/pyenv.rs
+//! The managed Python environment ArkPy runs in (`<home>/pyenv`): inspect it,
+//! verify it against the bundled `requirements.lock.txt`, upgrade it from the
+//! bundled wheels, or rebuild it from scratch.
+use serde::Serialize;
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use crate::events::EventSink;
+use crate::install::{self, py_in_venv, Cancel};
+use crate::resources::Resources;
+use crate::subprocess::Transcript;
+
+/// Distribution name of the ArkPy wheel.
+pub const ARKPY_DIST: &str = "arknet-py";
+const LOCK_FILE: &str = "bootstrap/requirements.lock.txt";
+
+/// Prints interpreter version and every installed distribution as JSON.
+const INSPECT_PROBE: &str = "\
+import json, sys, importlib.metadata as m
+pk = sorted({(d.metadata['Name'], d.version) for d in m.distributions() if d.metadata['Name']})
+print(json.dumps({'python': sys.version.split()[0], 'packages': pk}))";
+
+/* ── TYPES ──────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Package {
+  pub name: String,
+  pub version: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PyEnvInfo {
+  pub path: String,
+  pub python: String,
+  pub exists: bool,
+  /// Interpreter runs and `arknet-py` is installed.
+  pub ok: bool,
+  pub python_version: Option<String>,
+  pub pip_version: Option<String>,
+  pub arkpy_version: Option<String>,
+  pub packages: Vec<Package>,
+  pub error: Option<String>,
+}
+
+/// A locked requirement that is missing (`have: None`) or at another version.
+/// `want: None` means any version (no lock file bundled).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Mismatch {
+  pub name: String,
+  pub want: Option<String>,
+  pub have: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PyEnvVerify {
+  pub ok: bool,
+  pub lock: Option<String>,
+  pub mismatches: Vec<Mismatch>,
+  pub info: PyEnvInfo,
+}
+
+/* ── INSPECT / VERIFY ───────────────────────────────────────────────────── */
+
+pub fn venv_dir(home: &Path) -> PathBuf { home.join("pyenv") }
+
+/// Lowercase with `_`/`.` folded to `-`, as pip compares names.
+fn normalize(name: &str) -> String {
+  name.trim().to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+/// Interpreter and package versions of the managed venv. Never fails: problems
+/// are reported in `error` with `ok: false`.
+pub fn inspect(home: &Path) -> PyEnvInfo {
+  let venv = venv_dir(home);
+  let py = py_in_venv(&venv);
+  let mut info = PyEnvInfo {
+    path: venv.to_string_lossy().into_owned(),
+    python: py.to_string_lossy().into_owned(),
+    exists: py.is_file(),
+    ..Default::default()
+  };
+  if !info.exists {
+    info.error = Some("managed Python environment not installed".into());
+    return info;
+  }
+
+  #[derive(serde::Deserialize)]
+  struct Probe { python: String, packages: Vec<(String, String)> }
+
+  let probe = Command::new(&py).args(["-c", INSPECT_PROBE]).output()
+    .map_err(|e| format!("{}: {e}", py.display()))
+    .and_then(|o| if o.status.success() {
+      serde_json::from_slice::<Probe>(&o.stdout).map_err(|e| format!("unexpected probe output: {e}"))
+    } else {
+      Err(format!("{}: {}", o.status, String::from_utf8_lossy(&o.stderr).trim()))
+    });
+  match probe {
+    Ok(p) => {
+      info.python_version = Some(p.python);
+      info.packages = p.packages.into_iter().map(|(name, version)| Package { name, version }).collect();
+      let version_of = |dist: &str| info.packages.iter().find(|p| normalize(&p.name) == dist).map(|p| p.version.clone());
+      info.pip_version = version_of("pip");
+      info.arkpy_version = version_of(ARKPY_DIST);
+      info.ok = info.arkpy_version.is_some();
+      if !info.ok { info.error = Some(format!("{ARKPY_DIST} not installed")); }
+    }
+    Err(e) => info.error = Some(format!("interpreter broken: {e}")),
+  }
+  info
+}
+
+/// `name==version` pins of a pip requirements file; options, hashes, markers
+/// and extras are ignored.
+pub fn parse_lock(text: &str) -> Vec<(String, String)> {
+  text.lines()
+    .filter_map(|l| {
+      let l = l.split('#').next()?.split(';').next()?.trim();
+      if l.starts_with('-') { return None; }
+      let (name, ver) = l.split_whitespace().next()?.split_once("==")?;
+      let name = name.split('[').next()?;
+      Some((normalize(name), ver.trim_end_matches('\\').to_string()))
+    })
+    .collect()
+}
+
+/// Compare the venv against the bundled lock file (or just require `arknet-py`
+/// when none is bundled).
+pub fn verify(res: &Resources, home: &Path) -> PyEnvVerify {
+  let info = inspect(home);
+  let lock = res.resolve(LOCK_FILE).filter(|p| p.is_file());
+  let wanted: Vec<(String, Option<String>)> = match lock.as_ref().and_then(|p| fs::read_to_string(p).ok()) {
+    Some(text) => parse_lock(&text).into_iter().map(|(n, v)| (n, Some(v))).collect(),
+    None => vec![(ARKPY_DIST.to_string(), None)],
+  };
+
+  let mismatches: Vec<Mismatch> = wanted.into_iter()
+    .filter_map(|(name, want)| {
+      let have = info.packages.iter().find(|p| normalize(&p.name) == name).map(|p| p.version.clone());
+      let matches = match (&want, &have) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(w), Some(h)) => w == h,
+      };
+      (!matches).then_some(Mismatch { name, want, have })
+    })
+    .collect();
+
+  PyEnvVerify {
+    ok: info.ok && mismatches.is_empty(),
+    lock: lock.map(|p| p.to_string_lossy().into_owned()),
+    mismatches,
+    info,
+  }
+}
+
+pub(crate) async fn inspect_async(home: &Path) -> Result<PyEnvInfo, String> {
+  let home = home.to_path_buf();
+  tokio::task::spawn_blocking(move || inspect(&home)).await.map_err(|e| e.to_string())
+}
+
+/* ── REPAIR / UPGRADE ───────────────────────────────────────────────────── */
+
+/// Build a fresh venv next to the current one and swap it in only once ArkPy
+/// installed cleanly, so a failed repair leaves the old env untouched.
+pub async fn repair(res: &Resources, home: &Path, sink: &dyn EventSink, cancel: &Cancel) -> Result<PyEnvInfo, String> {
+  let venv = venv_dir(home);
+  let fresh = home.join("pyenv.new");
+  let old = home.join("pyenv.old");
+  for d in [&fresh, &old] {
+    if d.exists() { fs::remove_dir_all(d).map_err(|e| format!("clear {}: {e}", d.display()))?; }
+  }
+
+  let mut log = Transcript::logged("pyenv.repair", home);
+  let built = async {
+    install::create_venv(&fresh, sink, cancel, &mut log).await?;
+    let py = py_in_venv(&fresh);
+    install::bootstrap_pip(&py, sink, cancel, &mut log).await?;
+    install::install_arkpy(res, &py, sink, cancel, &mut log).await
+  }.await;
+  if let Err(e) = built {
+    let _ = fs::remove_dir_all(&fresh);
+    return Err(e);
+  }
+
+  if venv.exists() {
+    fs::rename(&venv, &old).map_err(|e| format!("replace {} failed (in use?): {e}", venv.display()))?;
+  }
+  fs::rename(&fresh, &venv).map_err(|e| format!("move {} -> {} failed: {e}", fresh.display(), venv.display()))?;
+  let _ = fs::remove_dir_all(&old);
+  inspect_async(home).await
+}
+
+/// Reinstall ArkPy and its locked dependencies from the bundled wheels.
+pub async fn upgrade(res: &Resources, home: &Path, sink: &dyn EventSink, cancel: &Cancel) -> Result<PyEnvInfo, String> {
+  let py = py_in_venv(&venv_dir(home));
+  if !py.is_file() { return Err("managed Python environment not installed; repair it first".into()); }
+  install::install_arkpy(res, &py, sink, cancel, &mut Transcript::logged("pyenv.upgrade", home)).await?;
+  inspect_async(home).await
+}
+
+/// Upgrade a working venv in place, rebuild anything else.
+pub async fn ensure(res: &Resources, home: &Path, sink: &dyn EventSink, cancel: &Cancel) -> Result<PyEnvInfo, String> {
+  if inspect_async(home).await?.python_version.is_some() {
+    upgrade(res, home, sink, cancel).await
+  } else {
+    repair(res, home, sink, cancel).await
+  }
+}
+
+/* ── COMMANDS ───────────────────────────────────────────────────────────── */
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn pyenv_inspect() -> Result<PyEnvInfo, String> {
+  Ok(inspect(&crate::settings::ark_home()))
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn pyenv_verify(app: tauri::AppHandle) -> Result<PyEnvVerify, String> {
+  Ok(verify(&Resources::from_app(&app), &crate::settings::ark_home()))
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn pyenv_repair(app: tauri::AppHandle, ctl: tauri::State<'_, install::InstallCtl>) -> Result<PyEnvInfo, String> {
+  let cancel = ctl.begin()?;
+  let out = repair(&Resources::from_app(&app), &crate::settings::ark_home(), &app, &cancel).await;
+  ctl.finish();
+  out
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn pyenv_upgrade(app: tauri::AppHandle, ctl: tauri::State<'_, install::InstallCtl>) -> Result<PyEnvInfo, String> {
+  let cancel = ctl.begin()?;
+  let out = upgrade(&Resources::from_app(&app), &crate::settings::ark_home(), &app, &cancel).await;
+  ctl.finish();
+  out
+}
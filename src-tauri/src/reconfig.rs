@@ -0,0 +1,179 @@
+// src/reconfig.rs
+//! What saving settings takes to apply: each changed field is classified as
+//! live, node-restart or app-restart, and `apply` performs the least of those
+//! (a node restart only when the caller confirmed it).
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::events::Sink;
+use crate::node_control::NodeProc;
+use crate::resources::Resources;
+use crate::settings::{load_settings, save_settings, Settings};
+
+/* ── CLASSIFICATION ─────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Effect {
+  /// Read from `config.json` on every use (status probes, installer, selftest).
+  Live,
+  /// Passed to arkd on its command line.
+  NodeRestart,
+  /// Not known to this build: only a fresh start is sure to pick it up.
+  AppRestart,
+}
+
+/// How a change to each `config.json` field reaches its consumers.
+const EFFECTS: &[(&str, Effect)] = &[
+  ("p2pPort", Effect::NodeRestart),
+  ("rpcPort", Effect::NodeRestart),
+  ("role", Effect::NodeRestart),
+  ("cLibPath", Effect::Live),
+  ("arkPyPath", Effect::Live),
+  ("keepVersions", Effect::Live),
+  ("diskWarnMib", Effect::Live),
+  ("diskStopMib", Effect::Live),
+];
+
+fn effect_of(field: &str) -> Effect {
+  EFFECTS.iter().find(|(f, _)| *f == field).map(|(_, e)| *e).unwrap_or(Effect::AppRestart)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+  pub field: String,
+  pub from: Value,
+  pub to: Value,
+  pub effect: Effect,
+}
+
+/// Changed fields by name (`schemaVersion` is not a setting).
+pub fn diff(old: &Settings, new: &Settings) -> Vec<FieldChange> {
+  let (Value::Object(old), Value::Object(new)) = (serde_json::to_value(old).unwrap(), serde_json::to_value(new).unwrap()) else {
+    return Vec::new();
+  };
+  new.into_iter()
+    .filter(|(k, v)| k != "schemaVersion" && old.get(k) != Some(v))
+    .map(|(field, to)| FieldChange {
+      effect: effect_of(&field),
+      from: old.get(&field).cloned().unwrap_or(Value::Null),
+      field,
+      to,
+    })
+    .collect()
+}
+
+/* ── PLAN ───────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Action { None, RestartNode, RestartApp }
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyPlan {
+  pub changes: Vec<FieldChange>,
+  /// An arkd answers on the configured RPC port or runs under ArkDesk.
+  pub node_running: bool,
+  /// That arkd is ArkDesk's child, so it can be restarted from here.
+  pub node_managed: bool,
+  pub action: Action,
+  /// `action` disrupts something and should be confirmed first.
+  pub needs_confirmation: bool,
+}
+
+fn fields(changes: &[FieldChange], effect: Effect) -> String {
+  changes.iter().filter(|c| c.effect == effect).map(|c| c.field.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// The least disruptive action that gets every change in effect.
+pub fn plan(old: &Settings, new: &Settings, node_running: bool, node_managed: bool) -> ApplyPlan {
+  let changes = diff(old, new);
+  let action = match changes.iter().map(|c| c.effect).max() {
+    Some(Effect::AppRestart) => Action::RestartApp,
+    Some(Effect::NodeRestart) if node_managed => Action::RestartNode,
+    _ => Action::None,
+  };
+  ApplyPlan { needs_confirmation: action != Action::None, changes, node_running, node_managed, action }
+}
+
+/* ── APPLY ──────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyReport {
+  pub plan: ApplyPlan,
+  /// What was actually done; `RestartApp` is left to the caller.
+  pub performed: Action,
+  pub message: String,
+}
+
+/// Current plan for saving `new` over the saved settings.
+pub fn preview(new: &Settings, proc: &NodeProc) -> ApplyPlan {
+  plan(&load_settings(), new, proc.probe().running, proc.pid().is_some())
+}
+
+/// Save `new` (validated unless `force`), then restart the supervised node if
+/// a launch flag changed and `confirmed`. Nothing else needs doing: every other
+/// consumer re-reads `config.json`.
+pub async fn apply(
+  new: Settings,
+  force: bool,
+  confirmed: bool,
+  proc: &NodeProc,
+  sink: Sink,
+  res: Option<&Resources>,
+) -> Result<ApplyReport, String> {
+  let plan = preview(&new, proc);
+  save_settings(new, Some(force))?;
+
+  let restart = fields(&plan.changes, Effect::NodeRestart);
+  let (performed, message) = match plan.action {
+    _ if plan.changes.is_empty() => (Action::None, "saved; nothing changed".to_string()),
+    Action::RestartApp => (Action::None, format!("saved; restart ArkDesk to apply {}", fields(&plan.changes, Effect::AppRestart))),
+    Action::RestartNode if confirmed => {
+      proc.restart(sink, res).await.map_err(|e| format!("saved, but restarting arkd failed: {e}"))?;
+      (Action::RestartNode, format!("saved; arkd restarted to apply {restart}"))
+    }
+    Action::RestartNode => (Action::None, format!("saved; restart the node to apply {restart}")),
+    Action::None if !restart.is_empty() && plan.node_running => {
+      (Action::None, format!("saved; arkd was not started by ArkDesk, restart it yourself to apply {restart}"))
+    }
+    Action::None if !restart.is_empty() => (Action::None, format!("saved; {restart} apply at next node start")),
+    Action::None => (Action::None, "saved; applied".to_string()),
+  };
+  Ok(ApplyReport { plan, performed, message })
+}
+
+/* ── COMMANDS ───────────────────────────────────────────────────────────── */
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn settings_preview(proc: tauri::State<'_, NodeProc>, settings: Settings) -> Result<ApplyPlan, String> {
+  Ok(preview(&settings, &proc))
+}
+
+/// Save and apply; with `confirm`, restarts the node or (for app-restart
+/// changes) ArkDesk itself once the report is delivered.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn settings_apply(
+  app: tauri::AppHandle,
+  proc: tauri::State<'_, NodeProc>,
+  settings: Settings,
+  force: Option<bool>,
+  confirm: Option<bool>,
+) -> Result<ApplyReport, String> {
+  let confirm = confirm.unwrap_or(false);
+  let res = Resources::from_app(&app);
+  let mut report = apply(settings, force.unwrap_or(false), confirm, &proc, std::sync::Arc::new(app.clone()), Some(&res)).await?;
+  if confirm && report.plan.action == Action::RestartApp {
+    report.performed = Action::RestartApp;
+    tauri::async_runtime::spawn(async move {
+      tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+      app.restart();
+    });
+  }
+  Ok(report)
+}
@@ -0,0 +1,39 @@
+// src/resources.rs
+//! Location of bundled payloads (`bin/<platform>`, `wheels`, `bootstrap`).
+use std::path::PathBuf;
+
+use crate::config::{self, Key};
+
+#[derive(Debug, Clone, Default)]
+pub struct Resources {
+  dir: Option<PathBuf>,
+}
+
+impl Resources {
+  pub fn new(dir: Option<PathBuf>) -> Self { Self { dir } }
+
+  /// The app bundle's resource directory.
+  #[cfg(feature = "desktop")]
+  pub fn from_app<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Self {
+    use tauri::Manager;
+    Self { dir: app.path().resource_dir().ok() }
+  }
+
+  /// Headless lookup: `Key::Resources`, else `resources/` next to the executable.
+  pub fn discover() -> Self {
+    if let Some(p) = config::path(Key::Resources) {
+      if p.is_dir() { return Self::new(Some(p)); }
+    }
+    let dir = std::env::current_exe().ok()
+      .and_then(|exe| exe.parent().map(|d| d.join("resources")))
+      .filter(|d| d.is_dir());
+    Self::new(dir)
+  }
+
+  pub fn dir(&self) -> Option<&PathBuf> { self.dir.as_ref() }
+
+  /// `rel` under the resource directory (not checked for existence).
+  pub fn resolve(&self, rel: &str) -> Option<PathBuf> {
+    self.dir.as_ref().map(|d| d.join(rel))
+  }
+}
@@ -1,17 +1,41 @@
+// src/runner.rs
+//! Dev runner (`spawn_node`) and the build-from-source bootstrap: fetch the Arknet
+//! sources at a pinned ref, build them, and install the products as an arkd version.
+#[cfg(feature = "desktop")]
 use anyhow::{anyhow, Result};
-use std::{path::{Path, PathBuf}, process::Stdio};
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::BTreeMap,
+  fs,
+  path::{Path, PathBuf},
+  time::UNIX_EPOCH,
+};
+#[cfg(feature = "desktop")]
+use std::process::Stdio;
+#[cfg(feature = "desktop")]
 use tauri::{AppHandle, Emitter, Manager};
+#[cfg(feature = "desktop")]
 use tokio::{
   io::{AsyncBufReadExt, BufReader},
-  process::{Child, Command},
+  process::Child,
 };
+use tokio::process::Command;
+
+use crate::cleanup::APP_IDENTIFIER;
+use crate::events::EventSink;
+use crate::install::{self, Cancel};
+use crate::settings::{ark_home, load_settings};
+use crate::subprocess::{self, Transcript};
+use crate::versions::{self, VersionList};
 
+#[cfg(feature = "desktop")]
 #[derive(Clone)]
 pub struct ChildHandle {
   id: String,
   inner: std::sync::Arc<tokio::sync::Mutex<Child>>,
 }
 
+#[cfg(feature = "desktop")]
 impl ChildHandle {
   pub fn status(&self) -> String {
     format!("running: {}", self.id)
@@ -24,6 +48,7 @@ impl ChildHandle {
   }
 }
 
+#[cfg(feature = "desktop")]
 fn default_run_path(app: &AppHandle) -> PathBuf {
   // <app_local_data>/arknet/build/bin/<os>/run
   let base = app
@@ -45,6 +70,7 @@ fn default_run_path(app: &AppHandle) -> PathBuf {
     .join(if cfg!(target_os = "windows") { "run.exe" } else { "run" })
 }
 
+#[cfg(feature = "desktop")]
 pub async fn spawn_node(app: AppHandle, id: &str, bin_override: Option<String>) -> Result<ChildHandle> {
   let path = bin_override.map(PathBuf::from).unwrap_or_else(|| default_run_path(&app));
   if !path.exists() {
@@ -53,14 +79,7 @@ pub async fn spawn_node(app: AppHandle, id: &str, bin_override: Option<String>)
 
   let mut cmd = Command::new(&path);
   cmd
-    .env(
-      "ARK_HOME",
-      path
-        .parent()
-        .and_then(|p| p.parent())
-        .unwrap_or(Path::new("."))
-        .to_path_buf(),
-    )
+    .env("ARK_HOME", ark_home())
     .stdout(Stdio::piped())
     .stderr(Stdio::piped());
 
@@ -102,52 +121,258 @@ pub async fn spawn_node(app: AppHandle, id: &str, bin_override: Option<String>)
   })
 }
 
+
 /* -------- Bootstrap: fetch & build Arknet source -------- */
 
-pub async fn bootstrap_fetch(app: &AppHandle, url: Option<String>, dest_dir: Option<String>) -> Result<()> {
-  let url = url.unwrap_or_else(|| "https://github.com/arknet-labs/arknet.git".to_string());
-  let dest = dest_dir
-    .map(PathBuf::from)
-    .unwrap_or_else(|| app.path().app_local_data_dir().unwrap().join("arknet"));
+// git/make output streams as `ark:proc:log` with these ids; failures carry its tail.
+const OP_FETCH: &str = "bootstrap.fetch";
+const OP_BUILD: &str = "bootstrap.build";
+
+pub const DEFAULT_REPO: &str = "https://github.com/arknet-labs/arknet.git";
+/// Ref checked out when none is given; pin a tag for reproducible builds.
+pub const DEFAULT_REF: &str = "main";
+/// Written into `.git/` so it never dirties the work tree.
+const PIN_FILE: &str = "arkdesk-bootstrap.json";
+/// Build products are looked for this deep below the checkout.
+const SEARCH_DEPTH: usize = 6;
 
-  if dest.exists() {
-    return Ok(());
+/// What the last fetch checked out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchReport {
+  pub src_dir: String,
+  pub url: String,
+  pub git_ref: String,
+  pub commit: String,
+  /// An existing checkout was moved to the ref rather than cloned.
+  pub updated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReport {
+  pub src_dir: String,
+  pub pin: Option<FetchReport>,
+  /// Binary name -> path it was taken from.
+  pub products: BTreeMap<String, String>,
+  /// Installed version name, now current.
+  pub version: String,
+  pub versions: VersionList,
+}
+
+/// `<local data>/io.arknet.arkdesk/arknet`, where Tauri's `app_local_data_dir` points.
+pub fn default_src_dir() -> PathBuf {
+  dirs::data_local_dir()
+    .unwrap_or_else(ark_home)
+    .join(APP_IDENTIFIER)
+    .join("arknet")
+}
+
+fn git(dir: &Path) -> Command {
+  let mut cmd = Command::new("git");
+  cmd.arg("-C").arg(dir).env("GIT_TERMINAL_PROMPT", "0");
+  cmd
+}
+
+fn read_pin(src: &Path) -> Option<FetchReport> {
+  serde_json::from_slice(&fs::read(src.join(".git").join(PIN_FILE)).ok()?).ok()
+}
+
+/// Check out `git_ref` of `url` into `dest`: clone if there is no checkout yet,
+/// otherwise fetch into the existing one. Local changes to tracked files are discarded.
+pub async fn fetch(url: &str, git_ref: &str, dest: &Path, sink: &dyn EventSink, cancel: &Cancel) -> Result<FetchReport, String> {
+  let mut log = Transcript::logged(OP_FETCH, &ark_home());
+  let updated = dest.join(".git").is_dir();
+  if !updated {
+    if dest.exists() && fs::read_dir(dest).map(|mut d| d.next().is_some()).unwrap_or(true) {
+      return Err(format!("{} exists and is not a git checkout", dest.display()));
+    }
+    fs::create_dir_all(dest).map_err(|e| format!("create {}: {e}", dest.display()))?;
+    subprocess::run(git(dest).args(["init", "-q"]), sink, cancel, &mut log).await
+      .map_err(|e| format!("git init failed: {e}"))?;
   }
-  tokio::fs::create_dir_all(dest.parent().unwrap()).await.ok();
-
-  let status = Command::new("git")
-    .arg("clone")
-    .arg("--depth=1")
-    .arg(&url)
-    .arg(&dest)
-    .status()
-    .await?;
-
-  if !status.success() {
-    return Err(anyhow!("git clone failed ({})", status));
+
+  // fetching the ref directly works the same for branches, tags and commits
+  subprocess::run(git(dest).args(["fetch", "--depth=1", "--progress", url, git_ref]), sink, cancel, &mut log).await
+    .map_err(|e| format!("git fetch {git_ref} failed: {e}"))?;
+  subprocess::run(git(dest).args(["checkout", "-q", "--force", "--detach", "FETCH_HEAD"]), sink, cancel, &mut log).await
+    .map_err(|e| format!("git checkout failed: {e}"))?;
+  subprocess::run(git(dest).args(["rev-parse", "HEAD"]), sink, cancel, &mut log).await
+    .map_err(|e| format!("git rev-parse failed: {e}"))?;
+  let commit = log.lines.iter().rev().find(|l| l.stream == "stdout").map(|l| l.line.trim().to_string()).unwrap_or_default();
+
+  let report = FetchReport {
+    src_dir: dest.to_string_lossy().into_owned(),
+    url: url.to_string(),
+    git_ref: git_ref.to_string(),
+    commit,
+    updated,
+  };
+  fs::write(dest.join(".git").join(PIN_FILE), serde_json::to_vec_pretty(&report).unwrap()).map_err(|e| e.to_string())?;
+  Ok(report)
+}
+
+fn is_exe(p: &Path) -> bool {
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    p.metadata().map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0).unwrap_or(false)
   }
-  Ok(())
+  #[cfg(not(unix))]
+  { p.is_file() }
 }
 
-pub async fn bootstrap_build(app: &AppHandle, src_dir: Option<String>) -> Result<()> {
-  let src = src_dir
-    .map(PathBuf::from)
-    .unwrap_or_else(|| app.path().app_local_data_dir().unwrap().join("arknet"));
+fn mtime(p: &Path) -> u128 {
+  p.metadata().and_then(|m| m.modified()).ok()
+    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+    .map(|d| d.as_nanos())
+    .unwrap_or(0)
+}
 
-  if !src.exists() {
-    return Err(anyhow!("source dir not found: {}", src.display()));
+/// Executables named like a required binary, anywhere below `dir` (skipping `.git`).
+fn find_products(dir: &Path, depth: usize, names: &[String], acc: &mut Vec<PathBuf>) {
+  let Ok(rd) = fs::read_dir(dir) else { return };
+  for e in rd.flatten() {
+    let p = e.path();
+    let name = e.file_name().to_string_lossy().into_owned();
+    if p.is_dir() {
+      if depth > 0 && !name.starts_with('.') { find_products(&p, depth - 1, names, acc); }
+    } else if names.contains(&name) && is_exe(&p) {
+      acc.push(p);
+    }
   }
+}
 
-  // make; or ./build.sh
-  let mut status = Command::new("make").current_dir(&src).status().await?;
-  if !status.success() {
-    let build_sh = src.join("build.sh");
-    if build_sh.exists() {
-      status = Command::new("sh").arg(build_sh).current_dir(&src).status().await?;
+/// Pick the build output dir: the one holding the most required binaries, newest arkd
+/// first. Any binary missing there is taken from wherever its newest copy is.
+fn locate_products(src: &Path) -> Result<BTreeMap<String, PathBuf>, String> {
+  let names: Vec<String> = install::required_bins().iter().map(|n| install::exe(n)).collect();
+  let mut found = Vec::new();
+  find_products(src, SEARCH_DEPTH, &names, &mut found);
+
+  let arkd = install::exe("arkd");
+  let score = |dir: &Path| found.iter().filter(|p| p.parent() == Some(dir)).count();
+  let out_dir = found.iter()
+    .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy() == arkd))
+    .max_by_key(|p| (score(p.parent().unwrap()), mtime(p)))
+    .and_then(|p| p.parent())
+    .ok_or_else(|| format!("build finished but no {arkd} was found under {}", src.display()))?;
+
+  let mut products = BTreeMap::new();
+  let mut missing = Vec::new();
+  for n in &names {
+    let pick = Some(out_dir.join(n)).filter(|p| is_exe(p)).or_else(|| {
+      found.iter().filter(|p| p.file_name().is_some_and(|f| f.to_string_lossy() == *n)).max_by_key(|p| mtime(p)).cloned()
+    });
+    match pick {
+      Some(p) => { products.insert(n.clone(), p); }
+      None => missing.push(n.clone()),
     }
   }
-  if !status.success() {
-    return Err(anyhow!("build failed ({})", status));
+  if !missing.is_empty() {
+    return Err(format!("build finished but {} not found under {}", missing.join(", "), src.display()));
   }
-  Ok(())
+  // shared libraries built next to arkd travel with it
+  for e in fs::read_dir(out_dir).map_err(|e| e.to_string())?.flatten() {
+    let p = e.path();
+    if p.is_file() && install::has_ext(&p, install::shared_exts()) {
+      products.insert(e.file_name().to_string_lossy().into_owned(), p);
+    }
+  }
+  Ok(products)
+}
+
+/// `src-<ref>-<short commit>`; without a pin the version `arkd --version` reports is used.
+fn version_name(pin: Option<&FetchReport>) -> Option<String> {
+  let pin = pin.filter(|p| !p.commit.is_empty())?;
+  let short = &pin.commit[..pin.commit.len().min(10)];
+  Some(versions::sanitize(&format!("src-{}-{short}", pin.git_ref.replace('/', "-"))))
+}
+
+/// Build the checkout in `src` (`make`, else `build.sh`), then install what it produced
+/// into `home` as a new current version.
+pub async fn build(src: &Path, home: &Path, sink: &dyn EventSink, cancel: &Cancel) -> Result<BuildReport, String> {
+  if !src.is_dir() {
+    return Err(format!("source dir not found: {} (fetch it first)", src.display()));
+  }
+
+  let mut log = Transcript::logged(OP_BUILD, home);
+  let build_sh = src.join("build.sh");
+  let mut out = if src.join("Makefile").is_file() || !build_sh.is_file() {
+    subprocess::run(Command::new("make").current_dir(src), sink, cancel, &mut log).await
+  } else {
+    Err("no Makefile".into())
+  };
+  if out.as_ref().is_err_and(|e| e != install::CANCELLED) && build_sh.is_file() {
+    out = subprocess::run(Command::new("sh").arg(&build_sh).current_dir(src), sink, cancel, &mut log).await;
+  }
+  out.map_err(|e| if e == install::CANCELLED { e } else { format!("build failed: {e}") })?;
+  cancel.check()?;
+
+  let products = locate_products(src)?;
+  // gather into one dir so the install sees a normal bin/<platform> layout
+  let gathered = versions::versions_dir(home).join(format!(".build-{}", std::process::id()));
+  let _ = fs::remove_dir_all(&gathered);
+  fs::create_dir_all(&gathered).map_err(|e| format!("create {}: {e}", gathered.display()))?;
+  let installed = {
+    let (products, src, home, gathered) = (products.clone(), src.to_path_buf(), home.to_path_buf(), gathered.clone());
+    install::blocking(move || {
+      for (name, p) in &products {
+        fs::copy(p, gathered.join(name)).map_err(|e| format!("copy {} failed: {e}", p.display()))?;
+      }
+      let pin = read_pin(&src);
+      install::install_local_build(&gathered, &home, version_name(pin.as_ref()).as_deref()).map(|v| (v, pin))
+    }).await
+  };
+  let _ = fs::remove_dir_all(&gathered);
+  let (version, pin) = installed?;
+
+  Ok(BuildReport {
+    src_dir: src.to_string_lossy().into_owned(),
+    pin,
+    products: products.into_iter().map(|(n, p)| (n, p.to_string_lossy().into_owned())).collect(),
+    version,
+    versions: versions::list(home, load_settings().keep_versions as usize),
+  })
+}
+
+/* -------- commands -------- */
+
+// The bridge passes snake_case argument names (`dest_dir`, `src_dir`). Bootstrap runs
+// count as installs: one at a time, cancelled by `install_cancel`.
+
+#[cfg(feature = "desktop")]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn ark_bootstrap_fetch(
+  app: AppHandle,
+  ctl: tauri::State<'_, install::InstallCtl>,
+  url: Option<String>,
+  dest_dir: Option<String>,
+  git_ref: Option<String>,
+) -> Result<FetchReport, String> {
+  let cancel = ctl.begin()?;
+  let dest = dest_dir.map(PathBuf::from).unwrap_or_else(default_src_dir);
+  let out = fetch(
+    url.as_deref().unwrap_or(DEFAULT_REPO),
+    git_ref.as_deref().unwrap_or(DEFAULT_REF),
+    &dest,
+    &app,
+    &cancel,
+  ).await;
+  ctl.finish();
+  out
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn ark_bootstrap_build(
+  app: AppHandle,
+  ctl: tauri::State<'_, install::InstallCtl>,
+  src_dir: Option<String>,
+) -> Result<BuildReport, String> {
+  let cancel = ctl.begin()?;
+  let src = src_dir.map(PathBuf::from).unwrap_or_else(default_src_dir);
+  let out = build(&src, &ark_home(), &app, &cancel).await;
+  ctl.finish();
+  out
 }
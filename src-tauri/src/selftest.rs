@@ -0,0 +1,413 @@
+// src/selftest.rs
+//! End-to-end check of the installed node: start arkd on a throwaway data dir and
+//! ephemeral ports, talk to it over admin and RPC, run the bundled `tests` binary,
+//! check that ArkPy loads the C library, then shut it down and clean up.
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::json;
+use std::{
+  collections::BTreeMap,
+  env, fs,
+  path::{Path, PathBuf},
+  process::{Output, Stdio},
+  sync::Arc,
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncRead, BufReader},
+  process::{Child, Command},
+  time::{sleep, timeout},
+};
+
+use crate::bundle::SUPPORTED_ABI_REVS;
+use crate::events::{self, EventSink};
+use crate::install::{exe, py_in_venv, required_bins, VERSION_ARG};
+use crate::miner::bundled_clib;
+use crate::node_control::{ephemeral_port, spawn_scratch, terminate_child, P2P_PORT_ARG, RPC_PORT_ARG};
+use crate::profiles::{LaunchSpec, DATA_DIR_ARG};
+use crate::pyenv;
+use crate::resources::Resources;
+use crate::settings::{ark_home, load_settings};
+use crate::state::NodeBridge;
+use crate::versions;
+
+pub const EVT_SELFTEST_CHECK: &str = "arknet://selftest_check";
+pub const EVT_SELFTEST_DONE: &str = "arknet://selftest_done";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(20);
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+const API_TIMEOUT: Duration = Duration::from_secs(10);
+const TESTS_TIMEOUT: Duration = Duration::from_secs(180);
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+const KILL_GRACE: Duration = Duration::from_secs(3);
+const LOG_MAX: usize = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckState { Running, Pass, Fail, Skip }
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+  pub id: &'static str,
+  pub label: &'static str,
+  pub state: CheckState,
+  pub detail: String,
+  pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelftestReport {
+  /// No check failed.
+  pub ok: bool,
+  /// Some checks were skipped, so `ok` covers less than the whole node.
+  pub partial: bool,
+  pub checks: Vec<CheckResult>,
+  pub bin_path: Option<String>,
+  pub python: Option<String>,
+  pub arkpy_version: Option<String>,
+  /// arkd and `tests` output, most recent last.
+  pub log: Vec<String>,
+  pub elapsed_ms: u64,
+}
+
+/// Check ids and labels, in run order.
+pub const CHECKS: &[(&str, &str)] = &[
+  ("binaries", "Installed binaries"),
+  ("loader", "arkd loads"),
+  ("start", "Start arkd on scratch data"),
+  ("healthz", "Admin /v1/healthz"),
+  ("rpc", "RPC chain.tip"),
+  ("tests", "Bundled tests"),
+  ("arkpy", "ArkPy import"),
+  ("clib", "ArkPy loads the C library"),
+  ("shutdown", "Shutdown and cleanup"),
+];
+
+fn label(id: &str) -> &'static str {
+  CHECKS.iter().find(|(i, _)| *i == id).map(|(_, l)| *l).unwrap_or("")
+}
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn push_log(log: &Log, line: String) {
+  let mut g = log.lock();
+  g.push(line);
+  let n = g.len();
+  if n > LOG_MAX { g.drain(..n - LOG_MAX); }
+}
+
+struct Run<'a> {
+  sink: &'a dyn EventSink,
+  checks: Vec<CheckResult>,
+}
+
+impl Run<'_> {
+  fn begin(&self, id: &'static str) -> Instant {
+    let running = CheckResult { id, label: label(id), state: CheckState::Running, detail: String::new(), elapsed_ms: 0 };
+    events::emit(self.sink, EVT_SELFTEST_CHECK, &running);
+    Instant::now()
+  }
+
+  fn finish(&mut self, id: &'static str, started: Instant, out: Result<String, String>) -> bool {
+    let (state, detail) = match out {
+      Ok(d) => (CheckState::Pass, d),
+      Err(d) => (CheckState::Fail, d),
+    };
+    self.record(CheckResult { id, label: label(id), state, detail, elapsed_ms: started.elapsed().as_millis() as u64 });
+    state == CheckState::Pass
+  }
+
+  fn skip(&mut self, id: &'static str, why: &str) {
+    self.record(CheckResult { id, label: label(id), state: CheckState::Skip, detail: why.to_string(), elapsed_ms: 0 });
+  }
+
+  fn record(&mut self, r: CheckResult) {
+    events::emit(self.sink, EVT_SELFTEST_CHECK, &r);
+    self.checks.push(r);
+  }
+}
+
+/// Output of a short-lived subprocess, killed after `limit`.
+async fn output(cmd: &mut Command, limit: Duration) -> Result<Output, String> {
+  cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true);
+  let child = cmd.spawn().map_err(|e| format!("spawn failed: {e}"))?;
+  match timeout(limit, child.wait_with_output()).await {
+    Ok(r) => r.map_err(|e| e.to_string()),
+    Err(_) => Err(format!("timed out after {}s", limit.as_secs())),
+  }
+}
+
+fn last_line(bytes: &[u8]) -> String {
+  String::from_utf8_lossy(bytes).lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim().to_string()
+}
+
+/// Last non-empty line of stderr (else stdout), for failure details.
+fn failure(o: &Output) -> String {
+  let line = if o.stderr.is_empty() { last_line(&o.stdout) } else { last_line(&o.stderr) };
+  if line.is_empty() { format!("exited with {}", o.status) } else { format!("exited with {}: {line}", o.status) }
+}
+
+fn pipe_to_log(r: impl AsyncRead + Unpin + Send + 'static, log: Log, tag: &'static str) {
+  tokio::spawn(async move {
+    let mut lines = BufReader::new(r).lines();
+    while let Ok(Some(line)) = lines.next_line().await { push_log(&log, format!("[{tag}] {line}")); }
+  });
+}
+
+/// Installed arkd dir: the active version, else a legacy `bin/`.
+fn installed_bin_dir(home: &Path) -> Option<PathBuf> {
+  versions::active_dir(home)
+    .into_iter()
+    .chain([home.join("bin")])
+    .find(|d| d.join(exe("arkd")).is_file())
+}
+
+fn scratch_dir() -> Result<PathBuf, String> {
+  let ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+  let dir = env::temp_dir().join(format!("arkdesk-selftest-{}-{ms}", std::process::id()));
+  for sub in ["data", "run"] {
+    fs::create_dir_all(dir.join(sub)).map_err(|e| format!("{}: {e}", dir.display()))?;
+  }
+  Ok(dir)
+}
+
+/// arkd running on scratch data, with clients for the node.json it writes.
+struct Scratch {
+  dir: PathBuf,
+  child: Child,
+  bridge: NodeBridge,
+}
+
+async fn start_node(arkd: &Path, dir: PathBuf, log: &Log) -> Result<(Scratch, String), String> {
+  let p2p = ephemeral_port(&[]).ok_or("no free port")?;
+  let rpc = ephemeral_port(&[p2p]).ok_or("no free port")?;
+  let run_dir = dir.join("run");
+  let launch = LaunchSpec {
+    profile: None,
+    args: vec![
+      DATA_DIR_ARG.into(), dir.join("data").to_string_lossy().into_owned(),
+      P2P_PORT_ARG.into(), p2p.to_string(),
+      RPC_PORT_ARG.into(), rpc.to_string(),
+    ],
+    env: BTreeMap::from([
+      ("ARK_RUN_DIR".to_string(), run_dir.to_string_lossy().into_owned()),
+      ("ARK_HOME".to_string(), dir.to_string_lossy().into_owned()),
+    ]),
+    work_dir: dir.clone(),
+  };
+  let mut child = spawn_scratch(arkd, &launch).map_err(|e| format!("spawn failed: {e}"))?;
+  if let Some(o) = child.stdout.take() { pipe_to_log(o, log.clone(), "arkd:stdout"); }
+  if let Some(e) = child.stderr.take() { pipe_to_log(e, log.clone(), "arkd:stderr"); }
+  let pid = child.id().unwrap_or_default();
+  let mut scratch = Scratch { dir, child, bridge: NodeBridge::new(run_dir.join("node.json")) };
+
+  let deadline = Instant::now() + START_TIMEOUT;
+  loop {
+    if scratch.bridge.admin().is_ok() {
+      return Ok((scratch, format!("pid {pid}, p2p {p2p}, rpc {rpc}")));
+    }
+    if let Ok(Some(st)) = scratch.child.try_wait() {
+      let _ = fs::remove_dir_all(&scratch.dir);
+      return Err(format!("arkd exited during startup ({st})"));
+    }
+    if Instant::now() > deadline {
+      terminate_child(&mut scratch.child, KILL_GRACE).await;
+      let _ = fs::remove_dir_all(&scratch.dir);
+      return Err(format!("no node.json after {}s", START_TIMEOUT.as_secs()));
+    }
+    sleep(Duration::from_millis(200)).await;
+  }
+}
+
+/// Retry `f` until it succeeds or `API_TIMEOUT` passes; the admin listener may
+/// come up slightly after node.json is written.
+async fn retry<T, F, Fut>(mut f: F) -> Result<T, String>
+where
+  F: FnMut() -> Fut,
+  Fut: std::future::Future<Output = Result<T, String>>,
+{
+  let deadline = Instant::now() + API_TIMEOUT;
+  loop {
+    match f().await {
+      Ok(v) => return Ok(v),
+      Err(e) if Instant::now() > deadline => return Err(e),
+      Err(_) => sleep(Duration::from_millis(300)).await,
+    }
+  }
+}
+
+async fn check_healthz(bridge: &NodeBridge) -> Result<String, String> {
+  let admin = bridge.admin().map_err(str::to_string)?;
+  let h = retry(|| async { admin.healthz().await.map_err(|e| e.to_string()) }).await?;
+  if !h.ok { return Err("healthz reported ok=false".into()); }
+  if !SUPPORTED_ABI_REVS.contains(&h.abi_rev) {
+    return Err(format!("abiRev {} is not supported by this ArkDesk", h.abi_rev));
+  }
+  Ok(format!("arkd {}, abiRev {}", h.version, h.abi_rev))
+}
+
+async fn check_rpc(bridge: &NodeBridge) -> Result<String, String> {
+  let rpc = bridge.rpc().map_err(str::to_string)?;
+  let r = retry(|| async { rpc.call_value("chain.tip", json!({})).await.map_err(|e| e.to_string()) }).await?;
+  let tip = r.result.ok_or("chain.tip returned no result")?;
+  let mut s = tip.to_string();
+  if s.len() > 160 { s.truncate(160); s.push('…'); }
+  Ok(s)
+}
+
+async fn check_tests(bin: &Path, scratch: &Path, log: &Log) -> Result<String, String> {
+  let o = output(Command::new(bin).current_dir(scratch).env("ARK_HOME", scratch), TESTS_TIMEOUT).await?;
+  for l in String::from_utf8_lossy(&o.stdout).lines().chain(String::from_utf8_lossy(&o.stderr).lines()) {
+    push_log(log, format!("[tests] {l}"));
+  }
+  if !o.status.success() { return Err(failure(&o)); }
+  Ok(last_line(&o.stdout))
+}
+
+/// Admin shutdown, then SIGTERM, then SIGKILL; only the last counts as a failure.
+async fn stop_node(mut s: Scratch) -> Result<String, String> {
+  let asked = match s.bridge.admin() {
+    Ok(a) => a.shutdown().await.unwrap_or(false),
+    Err(_) => false,
+  };
+  let mut how = if asked && timeout(SHUTDOWN_GRACE, s.child.wait()).await.is_ok() {
+    Ok("clean shutdown via admin API".to_string())
+  } else if terminate_child(&mut s.child, KILL_GRACE).await {
+    Ok("stopped with SIGTERM (admin shutdown did not finish)".to_string())
+  } else {
+    Err("arkd had to be killed".to_string())
+  };
+  if let Err(e) = fs::remove_dir_all(&s.dir) {
+    how = Err(format!("scratch dir {} not removed: {e}", s.dir.display()));
+  }
+  how
+}
+
+const ARKPY_PROBE: &str = "import importlib.metadata as m, arknet_py; print(m.version('arknet-py'))";
+const CLIB_PROBE: &str = "import ctypes, sys; ctypes.CDLL(sys.argv[1]); print('loaded', sys.argv[1])";
+
+/// Run every check against the installed node, emitting each result as it lands.
+pub async fn run(res: &Resources, sink: &dyn EventSink) -> SelftestReport {
+  let t0 = Instant::now();
+  let home = ark_home();
+  let log: Log = Arc::default();
+  let mut r = Run { sink, checks: Vec::new() };
+
+  let t = r.begin("binaries");
+  let bin_dir = installed_bin_dir(&home);
+  let bins_ok = r.finish("binaries", t, bin_dir.as_ref()
+    .map(|d| d.display().to_string())
+    .ok_or_else(|| "no installed arkd; run install first".to_string()));
+
+  let mut loaded = false;
+  if let (true, Some(dir)) = (bins_ok, &bin_dir) {
+    #[cfg(windows)]
+    crate::install::prepend_to_path(dir);
+    let t = r.begin("loader");
+    let out = output(Command::new(dir.join(exe("arkd"))).arg(VERSION_ARG), PROBE_TIMEOUT).await
+      .and_then(|o| if o.status.success() { Ok(last_line(&o.stdout)) } else { Err(failure(&o)) });
+    loaded = r.finish("loader", t, out);
+  } else {
+    r.skip("loader", "arkd not installed");
+  }
+
+  let mut node = None;
+  if let (true, Some(dir)) = (loaded, &bin_dir) {
+    let t = r.begin("start");
+    let started = match scratch_dir() {
+      Ok(scratch) => start_node(&dir.join(exe("arkd")), scratch, &log).await,
+      Err(e) => Err(e),
+    };
+    match started {
+      Ok((s, detail)) => { r.finish("start", t, Ok(detail)); node = Some(s); }
+      Err(e) => { r.finish("start", t, Err(e)); }
+    }
+  } else {
+    r.skip("start", "arkd does not load");
+  }
+
+  match &node {
+    Some(s) => {
+      let t = r.begin("healthz");
+      let out = check_healthz(&s.bridge).await;
+      r.finish("healthz", t, out);
+      let t = r.begin("rpc");
+      let out = check_rpc(&s.bridge).await;
+      r.finish("rpc", t, out);
+
+      let tests = bin_dir.as_ref().map(|d| d.join(exe("tests"))).filter(|p| p.is_file());
+      match tests {
+        Some(bin) => {
+          let t = r.begin("tests");
+          let out = check_tests(&bin, &s.dir, &log).await;
+          r.finish("tests", t, out);
+        }
+        // part of every install: missing means a broken one, not nothing to run
+        None if required_bins().contains(&"tests") => {
+          let t = r.begin("tests");
+          r.finish("tests", t, Err("tests binary missing from the installed version".into()));
+        }
+        None => r.skip("tests", "no tests binary installed"),
+      }
+    }
+    None => {
+      for id in ["healthz", "rpc", "tests"] { r.skip(id, "arkd not started"); }
+    }
+  }
+
+  let py = py_in_venv(&pyenv::venv_dir(&home));
+  let t = r.begin("arkpy");
+  let arkpy = if py.is_file() {
+    output(Command::new(&py).args(["-c", ARKPY_PROBE]), PROBE_TIMEOUT).await
+      .and_then(|o| if o.status.success() { Ok(last_line(&o.stdout)) } else { Err(failure(&o)) })
+  } else {
+    Err("ArkPy venv missing; run install first".into())
+  };
+  let arkpy_version = arkpy.as_ref().ok().cloned();
+  let arkpy_ok = r.finish("arkpy", t, arkpy);
+
+  let lib = Some(load_settings().c_lib_path).filter(|p| !p.trim().is_empty()).or_else(|| bundled_clib(res).path);
+  match (arkpy_ok, lib) {
+    (false, _) => r.skip("clib", "ArkPy not importable"),
+    (true, None) => r.skip("clib", "no C library configured or bundled"),
+    (true, Some(lib)) => {
+      let t = r.begin("clib");
+      let out = output(Command::new(&py).args(["-c", CLIB_PROBE]).arg(&lib), PROBE_TIMEOUT).await
+        .and_then(|o| if o.status.success() { Ok(lib.clone()) } else { Err(failure(&o)) });
+      r.finish("clib", t, out);
+    }
+  }
+
+  match node {
+    Some(s) => {
+      let t = r.begin("shutdown");
+      let out = stop_node(s).await;
+      r.finish("shutdown", t, out);
+    }
+    None => r.skip("shutdown", "arkd not started"),
+  }
+
+  let report = SelftestReport {
+    ok: r.checks.iter().all(|c| c.state != CheckState::Fail),
+    partial: r.checks.iter().any(|c| c.state == CheckState::Skip),
+    checks: r.checks,
+    bin_path: bin_dir.map(|d| d.to_string_lossy().into_owned()),
+    python: py.is_file().then(|| py.to_string_lossy().into_owned()),
+    arkpy_version,
+    log: std::mem::take(&mut *log.lock()),
+    elapsed_ms: t0.elapsed().as_millis() as u64,
+  };
+  events::emit(sink, EVT_SELFTEST_DONE, &report);
+  report
+}
+
+/* ── COMMANDS ────────────────────────────────────────────────────────────── */
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn install_selftest(window: tauri::Window) -> Result<SelftestReport, String> {
+  use tauri::Manager;
+  Ok(run(&Resources::from_app(window.app_handle()), &window).await)
+}
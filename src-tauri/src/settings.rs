@@ -1,31 +1,69 @@
 use serde::{Deserialize, Serialize};
-use std::{fs, io::Write, path::PathBuf};
+use serde_json::{Map, Value};
+use std::{fmt, fs, io::Write, path::{Path, PathBuf}};
 
+use crate::config::{self, Key};
+
+/// Current `config.json` layout; bump it and append to `MIGRATIONS` on any
+/// change that older files cannot be read as.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Missing fields take their value from `Settings::default()`, so files written
+/// before a field existed still load.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Settings {
+    pub schema_version: u32,
     pub c_lib_path: String,
     pub ark_py_path: String,
     pub p2p_port: u16,
     pub rpc_port: u16,
     pub role: NodeRole,
+    /// Installed arkd versions to keep under versions/ (current and previous are always kept).
+    pub keep_versions: u8,
+    /// Free space on the data volume below which the watchdog warns, in MiB.
+    pub disk_warn_mib: u32,
+    /// Below this the node is stopped and refused to start; 0 never stops it.
+    pub disk_stop_mib: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NodeRole { Relay, Miner }
 
+impl NodeRole {
+    /// Value of arkd's `--role` flag.
+    pub fn as_str(&self) -> &'static str {
+        match self { NodeRole::Relay => "relay", NodeRole::Miner => "miner" }
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
-        Self { c_lib_path: String::new(), ark_py_path: String::new(), p2p_port: 8646, rpc_port: 8645, role: NodeRole::Relay }
+        Self {
+            schema_version: SCHEMA_VERSION,
+            c_lib_path: String::new(),
+            ark_py_path: String::new(),
+            p2p_port: 8646,
+            rpc_port: 8645,
+            role: NodeRole::Relay,
+            keep_versions: 3,
+            disk_warn_mib: 2048,
+            disk_stop_mib: 256,
+        }
     }
 }
 
 /* ── PATHS ───────────────────────────────────────────────────────────────── */
 
+/// The Arknet home: `--ark-home` / `ARK_HOME` when set, else `default_ark_home()`.
+pub fn ark_home() -> PathBuf {
+    config::path(Key::ArkHome).unwrap_or_else(default_ark_home)
+}
+
 /// ~/.arknet on Unix & macOS, %APPDATA%\Arknet on Windows.
 /// No "." fallback. If base dir is unknown, return a sentinel that won't exist.
-pub fn ark_home() -> PathBuf {
+pub fn default_ark_home() -> PathBuf {
     #[cfg(target_os = "windows")]
     {
         if let Some(base) = dirs::data_dir().or_else(|| std::env::var_os("APPDATA").map(PathBuf::from)) {
@@ -38,19 +76,102 @@ pub fn ark_home() -> PathBuf {
         if let Some(home) = dirs::home_dir().or_else(|| std::env::var_os("HOME").map(PathBuf::from)) {
             return home.join(".arknet");
         }
-        return PathBuf::from("/__arknet__/not_found");
+        PathBuf::from("/__arknet__/not_found")
     }
 }
 
 fn cfg_path() -> PathBuf { ark_home().join("config.json") }
 
+/* ── MIGRATIONS ──────────────────────────────────────────────────────────── */
+
+/// `MIGRATIONS[n]` rewrites a version `n` object into version `n + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); SCHEMA_VERSION as usize] = [
+    migrate_v0,
+];
+
+/// v0: files written before `schemaVersion` existed. Same layout; the version
+/// stamp is all that changes.
+fn migrate_v0(_: &mut Map<String, Value>) {}
+
+/// Bring a parsed `config.json` up to `SCHEMA_VERSION`. Files from a newer
+/// ArkDesk are read as is; fields this build does not know are dropped on save.
+pub fn migrate(mut v: Value) -> Result<Settings, String> {
+    let o = v.as_object_mut().ok_or("not a JSON object")?;
+    let from = match o.get("schemaVersion") {
+        None => 0,
+        Some(n) => n.as_u64().ok_or("schemaVersion is not a number")? as usize,
+    };
+    for step in MIGRATIONS.iter().skip(from) { step(o); }
+    o.insert("schemaVersion".into(), Value::from(SCHEMA_VERSION.max(from as u32)));
+    serde_json::from_value(v).map_err(|e| e.to_string())
+}
+
 /* ── SETTINGS IO ─────────────────────────────────────────────────────────── */
 
-pub fn load_settings() -> Settings {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SettingsErrorKind { Read, Parse }
+
+/// Why `config.json` could not be used. The file itself is left in place until
+/// the next save; `backup` is a copy taken when it failed to parse.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsError {
+    pub kind: SettingsErrorKind,
+    pub path: String,
+    pub backup: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind { SettingsErrorKind::Read => "unreadable", SettingsErrorKind::Parse => "invalid" };
+        write!(f, "{} is {what}: {}", self.path, self.message)?;
+        if let Some(b) = &self.backup { write!(f, " (copy saved to {b})")?; }
+        Ok(())
+    }
+}
+
+/// Copy a broken config next to it as `config.json.broken[-<secs>]`, reusing an
+/// existing copy with the same contents so repeated loads don't pile them up.
+fn backup_broken(p: &Path, data: &[u8]) -> Option<PathBuf> {
+    let dir = p.parent()?;
+    let name = format!("{}.broken", p.file_name()?.to_string_lossy());
+    let existing = fs::read_dir(dir).ok()?.flatten()
+        .map(|e| e.path())
+        .filter(|c| c.file_name().is_some_and(|n| n.to_string_lossy().starts_with(&name)))
+        .find(|c| fs::read(c).is_ok_and(|b| b == data));
+    if existing.is_some() { return existing; }
+
+    let mut dest = dir.join(&name);
+    if dest.exists() {
+        let secs = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        dest = dir.join(format!("{name}-{secs}"));
+    }
+    fs::write(&dest, data).ok().map(|_| dest)
+}
+
+/// Load and migrate `config.json`; a missing file is the defaults.
+pub fn try_load_settings() -> Result<Settings, SettingsError> {
     let p = cfg_path();
-    if !p.is_file() { return Settings::default(); }
-    let data = match fs::read(&p) { Ok(b) => b, Err(_) => return Settings::default() };
-    serde_json::from_slice(&data).unwrap_or_default()
+    if !p.is_file() { return Ok(Settings::default()); }
+    let err = |kind, backup: Option<PathBuf>, message: String| SettingsError {
+        kind,
+        path: p.to_string_lossy().into_owned(),
+        backup: backup.map(|b| b.to_string_lossy().into_owned()),
+        message,
+    };
+    let data = fs::read(&p).map_err(|e| err(SettingsErrorKind::Read, None, e.to_string()))?;
+    serde_json::from_slice(&data)
+        .map_err(|e| e.to_string())
+        .and_then(migrate)
+        .map_err(|msg| err(SettingsErrorKind::Parse, backup_broken(&p, &data), msg))
+}
+
+/// Settings for internal use: defaults if `config.json` is broken (the file is
+/// kept and backed up; `get_settings` reports the error).
+pub fn load_settings() -> Settings {
+    try_load_settings().unwrap_or_default()
 }
 
 fn save_settings_inner(s: &Settings) -> std::io::Result<()> {
@@ -64,9 +185,23 @@ fn save_settings_inner(s: &Settings) -> std::io::Result<()> {
     Ok(())
 }
 
-#[tauri::command] pub fn get_settings() -> Result<Settings, String> { Ok(load_settings()) }
-#[tauri::command] pub fn save_settings(settings: Settings) -> Result<(), String> {
-    save_settings_inner(&settings).map_err(|e| e.to_string())
+/// Fails with the structured `SettingsError` so the UI can point at the backup.
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn get_settings() -> Result<Settings, SettingsError> { try_load_settings() }
+/// Validate and save; refuses settings with field errors unless `force`.
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn save_settings(settings: Settings, force: Option<bool>) -> Result<(), String> {
+    let settings = Settings { schema_version: SCHEMA_VERSION, ..settings };
+    if !force.unwrap_or(false) {
+        let errors = crate::validate::describe_errors(&crate::validate::check(&settings, &ark_home()));
+        if !errors.is_empty() { return Err(format!("invalid settings: {errors}")); }
+    }
+    write_settings(&settings)
+}
+
+/// Save without validation, for values the backend chose itself (e.g. reassigned ports).
+pub fn write_settings(settings: &Settings) -> Result<(), String> {
+    save_settings_inner(settings).map_err(|e| e.to_string())
 }
 
 /* ── INSTALL / PROBE ─────────────────────────────────────────────────────── */
@@ -80,14 +215,17 @@ pub struct InstallProbe {
     pub missing: Vec<String>
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn probe_install() -> Result<InstallProbe, String> {
     let home = ark_home();
     let present = home.is_dir();
 
     let mut missing = Vec::<String>::new();
+    // binaries live in the active versions/<ver>/ dir, or legacy bin/
+    if !home.join("bin").is_dir() && crate::versions::active_dir(&home).is_none() {
+        missing.push("bin/".to_string());
+    }
     for (p, name) in [
-        (home.join("bin"),  "bin/"),
         (home.join("data"), "data/"),
         (home.join("logs"), "logs/"),
     ] {
@@ -102,7 +240,7 @@ pub fn probe_install() -> Result<InstallProbe, String> {
     })
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn install_arknet() -> Result<(), String> {
     let home = ark_home();
     fs::create_dir_all(home.join("bin")).map_err(|e| e.to_string())?;
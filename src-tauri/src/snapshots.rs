@@ -0,0 +1,311 @@
+// src/snapshots.rs
+//! Snapshots of `<ark home>/data`: a checksummed `.tar.zst` plus the chain tip,
+//! network and arkd version at the time, kept under `<ark home>/snapshots/<id>/`.
+//! Taking or restoring one stops the node around the file work.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::{
+  fs,
+  io,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::cleanup::{remove_tree, tree_size};
+use crate::events::Sink;
+use crate::install::blocking;
+use crate::node_control::{stop_node_for_home, NodeProc, StoppedNodes};
+use crate::resources::Resources;
+use crate::settings::ark_home;
+use crate::state::NodeBridge;
+use crate::types::ChainTip;
+
+const ARCHIVE: &str = "data.tar.zst";
+const META: &str = "snapshot.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotMeta {
+  pub id: String,
+  #[serde(default)]
+  pub label: Option<String>,
+  pub created_at: u64,
+  #[serde(default)]
+  pub network: Option<String>,
+  #[serde(default)]
+  pub arkd_version: Option<String>,
+  #[serde(default)]
+  pub tip_height: Option<u64>,
+  #[serde(default)]
+  pub tip_hash: Option<String>,
+  /// Size of `data/` when taken, and of the archive.
+  pub data_bytes: u64,
+  pub archive_bytes: u64,
+  /// SHA-256 of the archive, hex.
+  pub sha256: String,
+}
+
+/// What was done to the node around a snapshot or restore.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRun {
+  pub snapshot: SnapshotMeta,
+  /// Pid of the supervised node stopped first (and started again after).
+  pub stopped_node: Option<u32>,
+  pub restarted: bool,
+  /// Pid of a node ArkDesk doesn't supervise, shut down via the admin API and left stopped.
+  pub stopped_external: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotCheck {
+  pub id: String,
+  pub ok: bool,
+  pub sha256: String,
+  pub expected: String,
+}
+
+/* ── STORE ──────────────────────────────────────────────────────────────── */
+
+pub fn snapshots_dir(home: &Path) -> PathBuf { home.join("snapshots") }
+
+fn check_id(id: &str) -> Result<(), String> {
+  let ok = !id.is_empty() && !id.starts_with('.') && id.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+  if ok { Ok(()) } else { Err(format!("invalid snapshot id: {id}")) }
+}
+
+fn read_meta(dir: &Path) -> Result<SnapshotMeta, String> {
+  let p = dir.join(META);
+  let bytes = fs::read(&p).map_err(|e| format!("{}: {e}", p.display()))?;
+  serde_json::from_slice(&bytes).map_err(|e| format!("{}: {e}", p.display()))
+}
+
+/// Snapshots under `home`, newest first. Unfinished ones (`*.part`) are skipped.
+pub fn list(home: &Path) -> Vec<SnapshotMeta> {
+  let Ok(rd) = fs::read_dir(snapshots_dir(home)) else { return Vec::new() };
+  let mut v: Vec<SnapshotMeta> = rd.flatten()
+    .filter(|e| !e.file_name().to_string_lossy().ends_with(".part"))
+    .filter_map(|e| read_meta(&e.path()).ok())
+    .collect();
+  v.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+  v
+}
+
+pub fn get(home: &Path, id: &str) -> Result<SnapshotMeta, String> {
+  check_id(id)?;
+  let dir = snapshots_dir(home).join(id);
+  if !dir.is_dir() { return Err(format!("snapshot not found: {id}")); }
+  read_meta(&dir)
+}
+
+fn sha256_file(p: &Path) -> io::Result<String> {
+  let mut h = Sha256::new();
+  io::copy(&mut fs::File::open(p)?, &mut h)?;
+  Ok(hex::encode(h.finalize()))
+}
+
+/// Recompute the archive's checksum.
+pub fn verify(home: &Path, id: &str) -> Result<SnapshotCheck, String> {
+  let meta = get(home, id)?;
+  let archive = snapshots_dir(home).join(id).join(ARCHIVE);
+  let sha256 = sha256_file(&archive).map_err(|e| format!("{}: {e}", archive.display()))?;
+  Ok(SnapshotCheck { id: meta.id, ok: sha256 == meta.sha256, sha256, expected: meta.sha256 })
+}
+
+pub fn delete(home: &Path, id: &str) -> Result<(), String> {
+  get(home, id)?;
+  remove_tree(&snapshots_dir(home).join(id))
+}
+
+/* ── NODE ───────────────────────────────────────────────────────────────── */
+
+async fn restart(stopped: &StoppedNodes, proc: Option<&NodeProc>, sink: Sink, res: Option<&Resources>) -> Result<bool, String> {
+  match (proc, stopped.supervised) {
+    (Some(proc), Some(_)) => proc.restart(sink, res).await.map(|_| true),
+    _ => Ok(false),
+  }
+}
+
+/// Tip, network and version from the running node; blanks if it doesn't answer.
+async fn node_info(bridge: &NodeBridge) -> (Option<ChainTip>, Option<String>, Option<String>) {
+  let tip = match bridge.rpc() {
+    Ok(rpc) => rpc.call::<ChainTip, _>("chain.tip", json!({})).await.ok(),
+    Err(_) => None,
+  };
+  let health = match bridge.admin() {
+    Ok(admin) => admin.healthz().await.ok(),
+    Err(_) => None,
+  };
+  let network = health.as_ref().and_then(|h| h.net.as_ref().map(|n| n.name.clone()))
+    .or_else(|| bridge.manifest().and_then(|m| m.net.map(|n| n.name)));
+  (tip, network, health.map(|h| h.version))
+}
+
+/* ── CREATE / RESTORE ───────────────────────────────────────────────────── */
+
+fn write_archive(data: &Path, out: &Path) -> io::Result<()> {
+  let mut tar = tar::Builder::new(zstd::Encoder::new(fs::File::create(out)?, 3)?);
+  tar.follow_symlinks(false);
+  tar.append_dir_all("data", data)?;
+  tar.into_inner()?.finish()?.sync_all()
+}
+
+fn take(home: &Path, label: Option<String>, info: (Option<ChainTip>, Option<String>, Option<String>)) -> Result<SnapshotMeta, String> {
+  let data = home.join("data");
+  let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+  let (tip, network, arkd_version) = info;
+  let base = match &tip {
+    Some(t) => format!("{created_at}-h{}", t.height),
+    None => created_at.to_string(),
+  };
+  let root = snapshots_dir(home);
+  let mut id = base.clone();
+  let mut n = 1;
+  while root.join(&id).exists() || root.join(format!("{id}.part")).exists() {
+    n += 1;
+    id = format!("{base}-{n}");
+  }
+
+  let part = root.join(format!("{id}.part"));
+  let fail = |e: String| { let _ = remove_tree(&part); e };
+  fs::create_dir_all(&part).map_err(|e| format!("{}: {e}", part.display()))?;
+  let archive = part.join(ARCHIVE);
+  write_archive(&data, &archive).map_err(|e| fail(format!("archive {}: {e}", data.display())))?;
+  let meta = SnapshotMeta {
+    sha256: sha256_file(&archive).map_err(|e| fail(format!("{}: {e}", archive.display())))?,
+    archive_bytes: tree_size(&archive),
+    data_bytes: tree_size(&data),
+    id: id.clone(),
+    label: label.filter(|l| !l.trim().is_empty()),
+    created_at,
+    network,
+    arkd_version,
+    tip_height: tip.as_ref().map(|t| t.height),
+    tip_hash: tip.and_then(|t| t.block_id),
+  };
+  fs::write(part.join(META), serde_json::to_vec_pretty(&meta).unwrap()).map_err(|e| fail(e.to_string()))?;
+  fs::rename(&part, root.join(&id)).map_err(|e| fail(format!("{}: {e}", part.display())))?;
+  Ok(meta)
+}
+
+/// Snapshot `data/`: read the tip while the node answers, stop it, archive,
+/// then start the supervised node again (even if archiving failed).
+pub async fn create(
+  label: Option<String>,
+  proc: Option<&NodeProc>,
+  bridge: &NodeBridge,
+  sink: Sink,
+  res: Option<&Resources>,
+) -> Result<SnapshotRun, String> {
+  let home = ark_home();
+  if !home.join("data").is_dir() {
+    return Err(format!("nothing to snapshot: {} does not exist", home.join("data").display()));
+  }
+  let info = node_info(bridge).await;
+  let stopped = stop_node_for_home(proc, &home, Some(&*sink)).await?;
+  let taken = { let home = home.clone(); blocking(move || take(&home, label, info)).await };
+  let restarted = restart(&stopped, proc, sink, res).await;
+  let snapshot = taken?;
+  Ok(SnapshotRun { snapshot, stopped_node: stopped.supervised, restarted: restarted?, stopped_external: stopped.external })
+}
+
+/// Unpack into a sibling of `data/` and swap it in; the old data comes back on failure.
+fn swap_in(home: &Path, archive: &Path) -> Result<(), String> {
+  let data = home.join("data");
+  let (incoming, outgoing) = (home.join("data.restore"), home.join("data.pre-restore"));
+  for p in [&incoming, &outgoing] {
+    if p.exists() { remove_tree(p)?; }
+  }
+  let unpack = || -> io::Result<()> {
+    fs::create_dir_all(&incoming)?;
+    tar::Archive::new(zstd::Decoder::new(fs::File::open(archive)?)?).unpack(&incoming)
+  };
+  if let Err(e) = unpack() {
+    let _ = remove_tree(&incoming);
+    return Err(format!("unpack {}: {e}", archive.display()));
+  }
+  if data.exists() {
+    fs::rename(&data, &outgoing).map_err(|e| format!("{}: {e}", data.display()))?;
+  }
+  if let Err(e) = fs::rename(incoming.join("data"), &data) {
+    let _ = fs::rename(&outgoing, &data);
+    let _ = remove_tree(&incoming);
+    return Err(format!("{}: {e}", data.display()));
+  }
+  let _ = remove_tree(&incoming);
+  let _ = remove_tree(&outgoing);
+  Ok(())
+}
+
+/// Replace `data/` with snapshot `id` after checking its checksum; the node is
+/// stopped around the swap like in `create`.
+pub async fn restore(
+  id: &str,
+  proc: Option<&NodeProc>,
+  sink: Sink,
+  res: Option<&Resources>,
+) -> Result<SnapshotRun, String> {
+  let home = ark_home();
+  let check = { let (home, id) = (home.clone(), id.to_string()); blocking(move || verify(&home, &id)).await? };
+  if !check.ok {
+    return Err(format!("snapshot {id} is corrupt: sha256 {} != {}", check.sha256, check.expected));
+  }
+  let snapshot = get(&home, id)?;
+  let stopped = stop_node_for_home(proc, &home, Some(&*sink)).await?;
+  let archive = snapshots_dir(&home).join(id).join(ARCHIVE);
+  let swapped = { let home = home.clone(); blocking(move || swap_in(&home, &archive)).await };
+  let restarted = restart(&stopped, proc, sink, res).await;
+  swapped?;
+  Ok(SnapshotRun { snapshot, stopped_node: stopped.supervised, restarted: restarted?, stopped_external: stopped.external })
+}
+
+/* ── COMMANDS ───────────────────────────────────────────────────────────── */
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn snapshot_list() -> Result<Vec<SnapshotMeta>, String> {
+  Ok(list(&ark_home()))
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn snapshot_verify(id: String) -> Result<SnapshotCheck, String> {
+  let home = ark_home();
+  blocking(move || verify(&home, &id)).await
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn snapshot_delete(id: String) -> Result<(), String> {
+  delete(&ark_home(), &id)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn snapshot_create(
+  app: tauri::AppHandle,
+  proc: tauri::State<'_, NodeProc>,
+  bridge: tauri::State<'_, NodeBridge>,
+  ctl: tauri::State<'_, crate::install::InstallCtl>,
+  label: Option<String>,
+) -> Result<SnapshotRun, String> {
+  ctl.begin()?;
+  let res = Resources::from_app(&app);
+  let r = create(label, Some(&*proc), &bridge, std::sync::Arc::new(app.clone()), Some(&res)).await;
+  ctl.finish();
+  r
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn snapshot_restore(
+  app: tauri::AppHandle,
+  proc: tauri::State<'_, NodeProc>,
+  ctl: tauri::State<'_, crate::install::InstallCtl>,
+  id: String,
+) -> Result<SnapshotRun, String> {
+  ctl.begin()?;
+  let res = Resources::from_app(&app);
+  let r = restore(&id, Some(&*proc), std::sync::Arc::new(app.clone()), Some(&res)).await;
+  ctl.finish();
+  r
+}
@@ -1,4 +1,5 @@
 use crate::{
+  config::{self, Key},
   admin_client::AdminClient,
   manifest::{read_manifest, Manifest},
   rpc::RpcClient,
@@ -18,7 +19,7 @@ pub struct NodeBridge {
 }
 
 struct Inner {
-  manifest_path: PathBuf,
+  manifest_path: RwLock<PathBuf>,
   admin: RwLock<Option<AdminClient>>,
   rpc: RwLock<Option<RpcClient>>,
   manifest: RwLock<Option<Manifest>>,
@@ -29,7 +30,7 @@ impl NodeBridge {
   pub fn new(manifest_path: PathBuf) -> Self {
     Self {
       inner: Arc::new(Inner {
-        manifest_path,
+        manifest_path: RwLock::new(manifest_path),
         admin: RwLock::new(None),
         rpc: RwLock::new(None),
         manifest: RwLock::new(None),
@@ -40,7 +41,8 @@ impl NodeBridge {
 
   /// Try to (re)load clients if node.json appeared or changed.
   pub fn maybe_refresh(&self) {
-    let meta = match fs::metadata(&self.inner.manifest_path) {
+    let path = self.inner.manifest_path.read().clone();
+    let meta = match fs::metadata(&path) {
       Ok(m) => m,
       Err(_) => return,
     };
@@ -52,7 +54,7 @@ impl NodeBridge {
       }
     }
 
-    if let Ok(m) = read_manifest(&self.inner.manifest_path) {
+    if let Ok(m) = read_manifest(&path) {
       if let Some((admin, rpc)) = build_clients(&m) {
         *self.inner.admin.write() = Some(admin);
         *self.inner.rpc.write() = Some(rpc);
@@ -93,9 +95,17 @@ impl NodeBridge {
     self.inner.manifest.read().clone()
   }
 
-  #[allow(dead_code)]
-  pub fn manifest_path(&self) -> &PathBuf {
-    &self.inner.manifest_path
+  pub fn manifest_path(&self) -> PathBuf {
+    self.inner.manifest_path.read().clone()
+  }
+
+  /// Follow node.json to a new path (the Arknet home moved); clients reload lazily.
+  pub fn retarget(&self, manifest_path: PathBuf) {
+    *self.inner.manifest_path.write() = manifest_path;
+    *self.inner.admin.write() = None;
+    *self.inner.rpc.write() = None;
+    *self.inner.manifest.write() = None;
+    *self.inner.last_mtime.write() = None;
   }
 }
 
@@ -104,10 +114,7 @@ fn build_clients(m: &Manifest) -> Option<(AdminClient, RpcClient)> {
 
   // Build RPC base; default path is "/" to match curl and the C server.
   let base = format!("http://{}:{}", m.rpc.host, m.rpc.port);
-  let path = std::env::var("ARK_RPC_PATH")
-    .ok()
-    .filter(|s| !s.trim().is_empty())
-    .unwrap_or_else(|| "/".to_string());
+  let path = config::get(Key::RpcPath).unwrap_or_else(|| "/".to_string());
 
   let headers = HeaderMap::new();
 
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-use crate::settings::{load_settings, NodeRole};
+use crate::config;
+use crate::settings::NodeRole;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,11 +33,14 @@ struct PeersResult {
     total: Option<u32>,
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_status() -> Result<NodeStatus, String> {
-    let s = load_settings();
-    let rpc = format!("http://127.0.0.1:{}", s.rpc_port);
+    let s = config::settings();
+    probe_status(format!("http://127.0.0.1:{}", s.rpc_port), s.role).await
+}
 
+/// Probe the JSON-RPC endpoint at `rpc` (chain.header, then net.peers).
+pub async fn probe_status(rpc: String, role: NodeRole) -> Result<NodeStatus, String> {
     let client = reqwest::Client::builder()
         .timeout(Duration::from_millis(500))
         .build()
@@ -101,7 +105,7 @@ pub async fn get_status() -> Result<NodeStatus, String> {
         connected,
         peers,
         network_height,
-        role: s.role,
+        role,
         rpc_ok,
         rpc_endpoint: rpc,
     })
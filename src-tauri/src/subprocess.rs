@@ -0,0 +1,162 @@
+// src/subprocess.rs
+//! Short-lived helper processes (venv, pip, git, make): output is streamed line by
+//! line as `EVT_PROC_LOG` events tagged with an operation id and kept in a
+//! `Transcript`, whose last lines end up in the error when the process fails.
+//! A transcript opened with `Transcript::logged` also keeps every line in
+//! `logs/<op>-<timestamp>.log` under the Arknet home.
+use serde::Serialize;
+use std::{
+  fs,
+  io::Write,
+  path::{Path, PathBuf},
+  process::Stdio,
+  sync::Arc,
+  time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncRead, BufReader},
+  process::Command,
+  sync::mpsc,
+};
+
+use crate::events::{self, EventSink};
+use crate::install::{Cancel, CANCELLED};
+
+/// Same event and payload shape as the `runner` process logs.
+pub const EVT_PROC_LOG: &str = "ark:proc:log";
+/// Lines kept in memory per transcript; older ones are dropped (and counted).
+const TRANSCRIPT_MAX: usize = 20_000;
+/// Lines of output appended to a failure message.
+const ERROR_TAIL: usize = 15;
+/// First part of the op ids that write transcript files, e.g. `pyenv.repair`.
+const LOGGED_AREAS: &[&str] = &["install", "pyenv", "bundle", "bootstrap"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputLine {
+  pub stream: &'static str,
+  pub line: String,
+}
+
+#[derive(Serialize)]
+struct ProcLogEvt<'a> { id: &'a str, stream: &'static str, line: &'a str }
+
+/// Everything an operation's subprocesses printed, in arrival order.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+  pub op: String,
+  pub lines: Vec<OutputLine>,
+  pub dropped: usize,
+  /// Log file holding every line; created with the first one.
+  pub path: Option<PathBuf>,
+  #[serde(skip)]
+  logs_dir: Option<PathBuf>,
+  #[serde(skip)]
+  file: Option<Arc<fs::File>>,
+}
+
+impl Transcript {
+  pub fn new(op: impl Into<String>) -> Self { Self { op: op.into(), ..Default::default() } }
+
+  /// As `new`, also appending every line to `<home>/logs/<op>-<unix ms>.log`.
+  pub fn logged(op: impl Into<String>, home: &Path) -> Self {
+    Self { logs_dir: Some(home.join("logs")), ..Self::new(op) }
+  }
+
+  /// Open the log file on first use; if that fails the transcript stays in memory only.
+  fn log_file(&mut self) -> Option<&fs::File> {
+    if self.file.is_none() {
+      let dir = self.logs_dir.take()?;
+      let ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+      let path = dir.join(format!("{}-{ms}.log", self.op));
+      let file = fs::create_dir_all(&dir).and_then(|_| fs::File::options().create(true).append(true).open(&path)).ok()?;
+      self.file = Some(Arc::new(file));
+      self.path = Some(path);
+    }
+    self.file.as_deref()
+  }
+
+  fn push(&mut self, stream: &'static str, line: String) {
+    if let Some(mut f) = self.log_file() {
+      let _ = writeln!(f, "{}{line}", if stream == "stderr" { "! " } else { "" });
+    }
+    self.lines.push(OutputLine { stream, line });
+    if self.lines.len() > TRANSCRIPT_MAX {
+      let excess = self.lines.len() - TRANSCRIPT_MAX;
+      self.lines.drain(..excess);
+      self.dropped += excess;
+    }
+  }
+
+  /// Last `n` lines as text, stderr marked with `! `.
+  pub fn tail(&self, n: usize) -> Vec<String> {
+    self.lines[self.lines.len().saturating_sub(n)..]
+      .iter()
+      .map(|l| if l.stream == "stderr" { format!("! {}", l.line) } else { l.line.clone() })
+      .collect()
+  }
+
+  /// `path` for messages and events.
+  pub fn log_path(&self) -> Option<String> { self.path.as_ref().map(|p| p.display().to_string()) }
+}
+
+/// True for files in `logs/` written by `Transcript::logged` rather than by arkd.
+pub fn is_transcript_log(file_name: &str) -> bool {
+  let Some(stem) = file_name.strip_suffix(".log") else { return false };
+  let Some((op, ms)) = stem.rsplit_once('-') else { return false };
+  let area = op.split('.').next().unwrap_or_default();
+  op.contains('.') && LOGGED_AREAS.contains(&area) && !ms.is_empty() && ms.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn forward(r: Option<impl AsyncRead + Unpin + Send + 'static>, stream: &'static str, tx: mpsc::UnboundedSender<(&'static str, String)>) {
+  let Some(r) = r else { return };
+  tokio::spawn(async move {
+    let mut lines = BufReader::new(r).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+      if tx.send((stream, line)).is_err() { break; }
+    }
+  });
+}
+
+/// Run `cmd` to completion, streaming its output to `sink` and into `transcript`.
+/// The child is killed if `cancel` fires (error `CANCELLED`); a non-zero exit
+/// returns the status followed by the last lines of output.
+pub async fn run(cmd: &mut Command, sink: &dyn EventSink, cancel: &Cancel, transcript: &mut Transcript) -> Result<(), String> {
+  cancel.check()?;
+  let program = cmd.as_std().get_program().to_string_lossy().into_owned();
+  let mut child = cmd
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .map_err(|e| format!("{program}: {e}"))?;
+
+  let (tx, mut rx) = mpsc::unbounded_channel();
+  forward(child.stdout.take(), "stdout", tx.clone());
+  forward(child.stderr.take(), "stderr", tx);
+
+  let status = loop {
+    tokio::select! {
+      msg = rx.recv() => match msg {
+        Some((stream, line)) => {
+          events::emit(sink, EVT_PROC_LOG, &ProcLogEvt { id: &transcript.op, stream, line: &line });
+          transcript.push(stream, line);
+        }
+        // both pipes closed: only the exit status is left
+        None => break tokio::select! {
+          st = child.wait() => st.map_err(|e| e.to_string())?,
+          _ = cancel.cancelled() => { let _ = child.kill().await; return Err(CANCELLED.into()); }
+        },
+      },
+      _ = cancel.cancelled() => { let _ = child.kill().await; return Err(CANCELLED.into()); }
+    }
+  };
+
+  if status.success() { return Ok(()); }
+  let mut msg = vec![status.to_string()];
+  msg.extend(transcript.tail(ERROR_TAIL));
+  msg.extend(transcript.log_path().map(|p| format!("full output: {p}")));
+  Err(msg.join("\n"))
+}
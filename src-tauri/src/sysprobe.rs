@@ -0,0 +1,312 @@
+// src/sysprobe.rs
+//! Native process/socket inspection (no `ss`, `lsof`, `pgrep`, `netstat`, `tasklist`).
+//! Linux reads procfs directly; other platforms use `netstat2` + `sysinfo`.
+
+/// PID of the process listening on TCP `port` (any address family).
+pub fn pid_listening_on(port: u16) -> Option<u32> {
+  imp::pid_listening_on(port)
+}
+
+/// Executable path of `pid`, or its command name if the path is unreadable.
+pub fn exe_for_pid(pid: u32) -> Option<String> {
+  imp::exe_for_pid(pid)
+}
+
+/// Whether a process with `pid` currently exists.
+pub fn pid_alive(pid: u32) -> bool {
+  imp::pid_alive(pid)
+}
+
+/// First process whose image name is `name` (e.g. "arkd").
+pub fn find_pid_by_name(name: &str) -> Option<u32> {
+  imp::find_pid_by_name(name)
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+  use super::procfs;
+  use std::path::Path;
+
+  const ROOT: &str = "/proc";
+
+  pub fn pid_listening_on(port: u16) -> Option<u32> {
+    procfs::pid_listening_on(Path::new(ROOT), port)
+  }
+  pub fn exe_for_pid(pid: u32) -> Option<String> {
+    procfs::exe_for_pid(Path::new(ROOT), pid)
+  }
+  pub fn find_pid_by_name(name: &str) -> Option<u32> {
+    procfs::find_pid_by_name(Path::new(ROOT), name)
+  }
+  pub fn pid_alive(pid: u32) -> bool {
+    procfs::pid_alive(Path::new(ROOT), pid)
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+  use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, TcpState};
+  use sysinfo::{Pid, System};
+
+  pub fn pid_listening_on(port: u16) -> Option<u32> {
+    let af = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let sockets = get_sockets_info(af, ProtocolFlags::TCP).ok()?;
+    sockets.into_iter().find_map(|si| match &si.protocol_socket_info {
+      ProtocolSocketInfo::Tcp(t) if t.state == TcpState::Listen && t.local_port == port => {
+        si.associated_pids.first().copied()
+      }
+      _ => None,
+    })
+  }
+
+  fn processes() -> System {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    sys
+  }
+
+  pub fn exe_for_pid(pid: u32) -> Option<String> {
+    let sys = processes();
+    let p = sys.process(Pid::from_u32(pid))?;
+    match p.exe() {
+      Some(exe) if !exe.as_os_str().is_empty() => Some(exe.to_string_lossy().to_string()),
+      _ => Some(p.name().to_string()).filter(|s| !s.is_empty()),
+    }
+  }
+
+  pub fn pid_alive(pid: u32) -> bool {
+    processes().process(Pid::from_u32(pid)).is_some()
+  }
+
+  pub fn find_pid_by_name(name: &str) -> Option<u32> {
+    let sys = processes();
+    let with_ext = format!("{name}.exe");
+    let mut pids: Vec<u32> = sys.processes().iter()
+      .filter(|(_, p)| p.name().eq_ignore_ascii_case(name) || p.name().eq_ignore_ascii_case(&with_ext))
+      .map(|(pid, _)| pid.as_u32())
+      .collect();
+    pids.sort_unstable();
+    pids.first().copied()
+  }
+}
+
+/// procfs parsing against an arbitrary root, so it can run over fixture trees.
+#[cfg(target_os = "linux")]
+pub mod procfs {
+  use std::{fs, path::Path};
+
+  const TCP_LISTEN: &str = "0A";
+
+  /// Socket inodes in LISTEN state bound to `port`, from a `/proc/net/tcp{,6}` table.
+  pub fn listening_inodes(table: &str, port: u16) -> Vec<u64> {
+    table.lines().skip(1).filter_map(|line| {
+      let cols: Vec<&str> = line.split_whitespace().collect();
+      if cols.len() < 10 || cols[3] != TCP_LISTEN { return None; }
+      let (_, hex_port) = cols[1].rsplit_once(':')?;
+      if u16::from_str_radix(hex_port, 16).ok()? != port { return None; }
+      cols[9].parse::<u64>().ok().filter(|&i| i != 0)
+    }).collect()
+  }
+
+  /// Numeric entries of `root`, ascending.
+  pub fn pids(root: &Path) -> Vec<u32> {
+    let mut v: Vec<u32> = fs::read_dir(root)
+      .map(|rd| rd.flatten().filter_map(|e| e.file_name().to_str()?.parse().ok()).collect())
+      .unwrap_or_default();
+    v.sort_unstable();
+    v
+  }
+
+  /// Inode from an fd link target like `socket:[12345]`.
+  pub fn socket_inode(link: &str) -> Option<u64> {
+    link.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+  }
+
+  fn pid_owning(root: &Path, inodes: &[u64]) -> Option<u32> {
+    pids(root).into_iter().find(|pid| {
+      let Ok(rd) = fs::read_dir(root.join(pid.to_string()).join("fd")) else { return false };
+      rd.flatten().any(|fd| {
+        fs::read_link(fd.path()).ok()
+          .and_then(|t| socket_inode(&t.to_string_lossy()))
+          .is_some_and(|i| inodes.contains(&i))
+      })
+    })
+  }
+
+  pub fn pid_listening_on(root: &Path, port: u16) -> Option<u32> {
+    let mut inodes = Vec::new();
+    for table in ["tcp", "tcp6"] {
+      if let Ok(s) = fs::read_to_string(root.join("net").join(table)) {
+        inodes.extend(listening_inodes(&s, port));
+      }
+    }
+    if inodes.is_empty() { return None; }
+    pid_owning(root, &inodes)
+  }
+
+  /// argv[0] from a NUL-separated `cmdline`.
+  fn argv0(root: &Path, pid: u32) -> Option<String> {
+    let raw = fs::read(root.join(pid.to_string()).join("cmdline")).ok()?;
+    let first = raw.split(|&b| b == 0).next()?;
+    let s = String::from_utf8_lossy(first).to_string();
+    if s.is_empty() { None } else { Some(s) }
+  }
+
+  fn comm(root: &Path, pid: u32) -> Option<String> {
+    let s = fs::read_to_string(root.join(pid.to_string()).join("comm")).ok()?;
+    let s = s.trim_end().to_string();
+    if s.is_empty() { None } else { Some(s) }
+  }
+
+  pub fn exe_for_pid(root: &Path, pid: u32) -> Option<String> {
+    if let Ok(p) = fs::read_link(root.join(pid.to_string()).join("exe")) {
+      return Some(p.to_string_lossy().to_string());
+    }
+    argv0(root, pid).or_else(|| comm(root, pid))
+  }
+
+  /// Present and not a zombie.
+  pub fn pid_alive(root: &Path, pid: u32) -> bool {
+    match fs::read_to_string(root.join(pid.to_string()).join("stat")) {
+      // state is the first field after the parenthesised comm
+      Ok(stat) => stat.rsplit_once(')').map(|(_, rest)| !rest.trim_start().starts_with('Z')).unwrap_or(true),
+      Err(_) => false,
+    }
+  }
+
+  pub fn find_pid_by_name(root: &Path, name: &str) -> Option<u32> {
+    let ids = pids(root);
+    // Same order as `pgrep -x name || pgrep -f '/name'`: exact comm first, then argv[0] path.
+    ids.iter().copied().find(|&pid| comm(root, pid).as_deref() == Some(name))
+      .or_else(|| ids.iter().copied().find(|&pid| {
+        argv0(root, pid).is_some_and(|a| Path::new(&a).file_name().is_some_and(|f| f == name))
+      }))
+  }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+  use super::procfs::*;
+  use std::{fs, os::unix::fs::symlink, path::Path};
+
+  const HEADER: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode";
+
+  /// `/proc/net/tcp` row: local `addr:port`, state, inode.
+  fn row(local: &str, state: &str, inode: u64) -> String {
+    format!("   0: {local} 00000000:0000 {state} 00000000:00000000 00:00000000 00000000  1000        0 {inode} 1 0000000000000000 100 0 0 10 0")
+  }
+
+  fn write(p: &Path, body: impl AsRef<[u8]>) {
+    fs::create_dir_all(p.parent().unwrap()).unwrap();
+    fs::write(p, body).unwrap();
+  }
+
+  /// A process dir with optional comm, cmdline, exe link and socket fds.
+  fn process(root: &Path, pid: u32, comm: Option<&str>, cmdline: Option<&[u8]>, exe: Option<&str>, sockets: &[u64]) {
+    let dir = root.join(pid.to_string());
+    fs::create_dir_all(dir.join("fd")).unwrap();
+    if let Some(c) = comm { write(&dir.join("comm"), format!("{c}\n")); }
+    if let Some(c) = cmdline { write(&dir.join("cmdline"), c); }
+    if let Some(e) = exe { symlink(e, dir.join("exe")).unwrap(); }
+    for (fd, inode) in sockets.iter().enumerate() {
+      symlink(format!("socket:[{inode}]"), dir.join("fd").join((fd + 3).to_string())).unwrap();
+    }
+    symlink("/dev/null", dir.join("fd/0")).unwrap();
+  }
+
+  #[test]
+  fn only_listen_rows_on_the_port_count() {
+    let table = [
+      HEADER.to_string(),
+      row("0100007F:1F90", "0A", 111),
+      row("0100007F:1F90", "01", 222), // established
+      row("0100007F:1F91", "0A", 333), // other port
+      row("00000000:1F90", "0A", 0),   // no inode
+    ].join("\n");
+    assert_eq!(listening_inodes(&table, 8080), [111]);
+    assert!(listening_inodes(&table, 9999).is_empty());
+    assert!(listening_inodes("garbage\nnot a row", 8080).is_empty());
+  }
+
+  #[test]
+  fn ipv6_rows_parse() {
+    let table = [HEADER.to_string(), row("00000000000000000000000000000000:1F90", "0A", 444)].join("\n");
+    assert_eq!(listening_inodes(&table, 8080), [444]);
+  }
+
+  #[test]
+  fn socket_links() {
+    assert_eq!(socket_inode("socket:[12345]"), Some(12345));
+    assert_eq!(socket_inode("pipe:[12345]"), None);
+    assert_eq!(socket_inode("socket:[x]"), None);
+    assert_eq!(socket_inode("/dev/null"), None);
+  }
+
+  #[test]
+  fn pids_are_numeric_dirs_in_order() {
+    let root = tempfile::tempdir().unwrap();
+    for d in ["42", "7", "self", "net", "1x"] { fs::create_dir_all(root.path().join(d)).unwrap(); }
+    assert_eq!(pids(root.path()), [7, 42]);
+    assert!(pids(&root.path().join("missing")).is_empty());
+  }
+
+  #[test]
+  fn listening_pid_is_found_by_socket_inode() {
+    let root = tempfile::tempdir().unwrap();
+    let r = root.path();
+    write(&r.join("net/tcp"), [HEADER.to_string(), row("0100007F:1F90", "01", 500), row("0100007F:1F91", "0A", 0)].join("\n"));
+    write(&r.join("net/tcp6"), [HEADER.to_string(), row("00000000000000000000000000000000:1F90", "0A", 600)].join("\n"));
+    process(r, 10, Some("client"), None, None, &[500]);
+    process(r, 20, Some("arkd"), None, None, &[600]);
+    fs::create_dir_all(r.join("self/fd")).unwrap();
+
+    // the established socket on 8080 belongs to pid 10, the v6 listener to pid 20
+    assert_eq!(pid_listening_on(r, 8080), Some(20));
+    // LISTEN on 8081 but inode 0: nothing to match
+    assert_eq!(pid_listening_on(r, 8081), None);
+    assert_eq!(pid_listening_on(r, 9999), None);
+  }
+
+  #[test]
+  fn exe_falls_back_to_argv0_then_comm() {
+    let root = tempfile::tempdir().unwrap();
+    let r = root.path();
+    process(r, 1, Some("arkd"), Some(b"/opt/ark/arkd\0--role\0relay\0"), Some("/opt/ark/bin/arkd"), &[]);
+    process(r, 2, Some("arkd"), Some(b"/opt/ark/arkd\0--role\0"), None, &[]);
+    process(r, 3, Some("arkd"), Some(b""), None, &[]);
+    process(r, 4, None, None, None, &[]);
+    assert_eq!(exe_for_pid(r, 1).as_deref(), Some("/opt/ark/bin/arkd"));
+    assert_eq!(exe_for_pid(r, 2).as_deref(), Some("/opt/ark/arkd"));
+    assert_eq!(exe_for_pid(r, 3).as_deref(), Some("arkd"));
+    assert_eq!(exe_for_pid(r, 4), None);
+    assert_eq!(exe_for_pid(r, 99), None);
+  }
+
+  #[test]
+  fn name_matches_comm_before_cmdline() {
+    let root = tempfile::tempdir().unwrap();
+    let r = root.path();
+    // pid 5 only matches by argv[0]; pid 9 by comm, which wins despite the higher pid
+    process(r, 5, Some("sh"), Some(b"/usr/local/bin/arkd\0"), None, &[]);
+    process(r, 9, Some("arkd"), Some(b"./something-else\0"), None, &[]);
+    assert_eq!(find_pid_by_name(r, "arkd"), Some(9));
+
+    fs::remove_dir_all(r.join("9")).unwrap();
+    assert_eq!(find_pid_by_name(r, "arkd"), Some(5));
+    // a path component that isn't the file name doesn't count
+    process(r, 6, Some("python"), Some(b"/opt/arkd/run.py\0"), None, &[]);
+    fs::remove_dir_all(r.join("5")).unwrap();
+    assert_eq!(find_pid_by_name(r, "arkd"), None);
+  }
+
+  #[test]
+  fn zombies_are_not_alive() {
+    let root = tempfile::tempdir().unwrap();
+    let r = root.path();
+    write(&r.join("1/stat"), "1 (ark d) S 0 1 1");
+    write(&r.join("2/stat"), "2 (arkd) Z 1 2 2");
+    assert!(pid_alive(r, 1));
+    assert!(!pid_alive(r, 2));
+    assert!(!pid_alive(r, 3));
+  }
+}
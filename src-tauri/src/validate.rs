@@ -1,15 +1,59 @@
 use serde::Serialize;
-use std::path::Path;
-use crate::settings::load_settings;
+use std::{
+    fs::File,
+    io::Read,
+    net::{Ipv4Addr, TcpListener},
+    path::Path,
+};
+
+use crate::config;
+use crate::pyenv;
+use crate::settings::{ark_home, load_settings, NodeRole, Settings};
+use crate::status::probe_status;
+
+/* ── TYPES ───────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity { Warn, Error }
+
+/// One problem with one `Settings` field (named as in `config.json`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub field: &'static str,
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+}
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ValidationReport {
+    /// No `Error` diagnostics; warnings don't block a save.
+    pub ok: bool,
+    pub diagnostics: Vec<Diagnostic>,
     pub c_lib_exists: bool,
     pub c_lib_ext_ok: bool,
     pub ark_py_exists: bool,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCheck {
+    pub rpc_url: String,
+    pub reachable: bool,
+    pub version: Option<String>,
+    pub network: Option<String>,
+    pub note: Option<String>,
+}
+
+fn diag(field: &'static str, severity: Severity, code: &'static str, message: impl Into<String>) -> Diagnostic {
+    Diagnostic { field, severity, code, message: message.into() }
+}
+
+/* ── CHECKS ──────────────────────────────────────────────────────────────── */
+
 fn has_ext<P: AsRef<std::path::Path>>(p: P, exts: &[&str]) -> bool {
     p.as_ref()
         .extension()
@@ -18,20 +62,145 @@ fn has_ext<P: AsRef<std::path::Path>>(p: P, exts: &[&str]) -> bool {
         .unwrap_or(false)
 }
 
-#[tauri::command]
-pub fn validate_settings() -> Result<ValidationReport, String> {
-    let s = load_settings();
+#[cfg(windows)]
+const LIB_EXTS: &[&str] = &["dll"];
+#[cfg(target_os = "macos")]
+const LIB_EXTS: &[&str] = &["dylib", "so"];
+#[cfg(all(unix, not(target_os = "macos")))]
+const LIB_EXTS: &[&str] = &["so"];
 
-    let c_path = Path::new(&s.c_lib_path);
-    let a_path = Path::new(&s.ark_py_path);
+/// Leading bytes of a shared library this platform can load.
+#[cfg(windows)]
+const LIB_MAGIC: &[&[u8]] = &[b"MZ"];
+#[cfg(target_os = "macos")]
+const LIB_MAGIC: &[&[u8]] = &[b"\xcf\xfa\xed\xfe", b"\xce\xfa\xed\xfe", b"\xca\xfe\xba\xbe"];
+#[cfg(all(unix, not(target_os = "macos")))]
+const LIB_MAGIC: &[&[u8]] = &[b"\x7fELF"];
 
-    let c_exists = c_path.is_file();
-    let c_ext_ok = has_ext(&c_path, &["dll", "so", "dylib"]);
-    let ark_exists = a_path.is_file();
+fn lib_magic_ok(p: &Path) -> bool {
+    let mut head = [0u8; 4];
+    let n = File::open(p).and_then(|mut f| f.read(&mut head)).unwrap_or(0);
+    LIB_MAGIC.iter().any(|m| head[..n].starts_with(m))
+}
+
+fn check_ports(s: &Settings, out: &mut Vec<Diagnostic>) {
+    for (field, port) in [("p2pPort", s.p2p_port), ("rpcPort", s.rpc_port)] {
+        if port == 0 {
+            out.push(diag(field, Severity::Error, "port-range", "must be between 1 and 65535"));
+        } else if port < 1024 {
+            out.push(diag(field, Severity::Warn, "port-privileged", format!("port {port} needs elevated privileges on most systems")));
+        }
+    }
+    if s.p2p_port == s.rpc_port && s.rpc_port != 0 {
+        out.push(diag("rpcPort", Severity::Error, "port-collision", format!("p2p and rpc both use port {}", s.rpc_port)));
+    }
+}
+
+fn check_disk(s: &Settings, out: &mut Vec<Diagnostic>) {
+    if s.disk_stop_mib > 0 && s.disk_stop_mib >= s.disk_warn_mib {
+        out.push(diag("diskWarnMib", Severity::Warn, "disk-thresholds", format!(
+            "the node is stopped below {} MiB free before any warning at {} MiB", s.disk_stop_mib, s.disk_warn_mib,
+        )));
+    }
+}
+
+/// C library and ArkPy: required (errors) for a miner, optional (warnings) for a relay.
+fn check_role(s: &Settings, home: &Path, out: &mut Vec<Diagnostic>) {
+    let miner = matches!(s.role, NodeRole::Miner);
+    let sev = if miner { Severity::Error } else { Severity::Warn };
+
+    let lib = Path::new(s.c_lib_path.trim());
+    if s.c_lib_path.trim().is_empty() {
+        if miner { out.push(diag("cLibPath", sev, "required", "miner role needs the Arknet C library")); }
+    } else if !lib.is_file() {
+        out.push(diag("cLibPath", sev, "not-found", format!("{} does not exist", lib.display())));
+    } else if !has_ext(lib, LIB_EXTS) {
+        out.push(diag("cLibPath", sev, "bad-extension", format!("expected a .{} file", LIB_EXTS.join(" / ."))));
+    } else if !lib_magic_ok(lib) {
+        out.push(diag("cLibPath", sev, "not-loadable", format!("{} is not a shared library for this platform", lib.display())));
+    }
+
+    let py = Path::new(s.ark_py_path.trim());
+    if !s.ark_py_path.trim().is_empty() {
+        if !py.is_file() { out.push(diag("arkPyPath", sev, "not-found", format!("{} does not exist", py.display()))); }
+    } else if miner {
+        let env = pyenv::inspect(home);
+        if !env.ok {
+            let why = env.error.unwrap_or_default();
+            out.push(diag("arkPyPath", sev, "required", format!("miner role needs ArkPy ({why}); install it from Miner settings")));
+        }
+    }
+}
+
+/// Checks that only depend on the settings and installed files. Their errors are
+/// what `save_settings` refuses on.
+pub fn check(s: &Settings, home: &Path) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+    check_ports(s, &mut out);
+    check_disk(s, &mut out);
+    check_role(s, home, &mut out);
+    out
+}
+
+fn port_bound(port: u16) -> bool {
+    port != 0 && TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_err()
+}
+
+/// Everything in `check`, plus the live state: whether a node answers on the
+/// RPC port, and (if none does) whether something else holds the ports.
+pub async fn validate(s: &Settings, home: &Path) -> ValidationReport {
+    let mut diagnostics = check(s, home);
+    let rpc = rpc_check(None, s.rpc_port).await;
+    if !rpc.reachable {
+        diagnostics.push(diag("rpcPort", Severity::Warn, "rpc-unreachable", format!("no node answering at {}", rpc.rpc_url)));
+        for (field, port) in [("p2pPort", s.p2p_port), ("rpcPort", s.rpc_port)] {
+            if port_bound(port) {
+                diagnostics.push(diag(field, Severity::Warn, "port-in-use", format!("port {port} is held by another process")));
+            }
+        }
+    }
+
+    let (c_lib, ark_py) = (Path::new(&s.c_lib_path), Path::new(&s.ark_py_path));
+    ValidationReport {
+        ok: !diagnostics.iter().any(|d| d.severity == Severity::Error),
+        diagnostics,
+        c_lib_exists: c_lib.is_file(),
+        c_lib_ext_ok: has_ext(c_lib, &["dll", "so", "dylib"]),
+        ark_py_exists: ark_py.is_file(),
+    }
+}
+
+/// `field: message` for every error, joined; empty if there are none.
+pub fn describe_errors(diags: &[Diagnostic]) -> String {
+    diags.iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| format!("{}: {}", d.field, d.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+async fn rpc_check(host: Option<&str>, port: u16) -> RpcCheck {
+    let rpc_url = format!("http://{}:{port}", host.unwrap_or("127.0.0.1"));
+    let (reachable, note) = match probe_status(rpc_url.clone(), load_settings().role).await {
+        Ok(st) if st.rpc_ok => (true, format!("height {}, {} peer(s)", st.network_height, st.peers)),
+        Ok(_) => (false, "no JSON-RPC answer (is arkd running?)".to_string()),
+        Err(e) => (false, e),
+    };
+    RpcCheck { rpc_url, reachable, version: None, network: None, note: Some(note) }
+}
+
+/* ── COMMANDS ────────────────────────────────────────────────────────────── */
+
+/// Validate `settings` (a draft from the UI), or the saved settings if omitted.
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn validate_settings(settings: Option<Settings>) -> Result<ValidationReport, String> {
+    let s = settings.unwrap_or_else(load_settings);
+    Ok(validate(&s, &ark_home()).await)
+}
 
-    Ok(ValidationReport {
-        c_lib_exists: c_exists,
-        c_lib_ext_ok: c_ext_ok,
-        ark_py_exists: ark_exists,
-    })
+/// Whether a node answers JSON-RPC at `host:port` (defaults: localhost and the configured port).
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn validate_rpc(host: Option<String>, port: Option<u16>) -> Result<RpcCheck, String> {
+    let port = port.unwrap_or_else(|| config::settings().rpc_port);
+    Ok(rpc_check(host.as_deref().filter(|h| !h.trim().is_empty()), port).await)
 }
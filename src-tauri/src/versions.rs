@@ -0,0 +1,233 @@
+// src/versions.rs
+//! Side-by-side arkd installs under `ark_home()/versions/<version>/`.
+//! `versions/current.json` names the active version (and the one before it);
+//! it is replaced atomically, so a crash mid-switch leaves the old pointer.
+use serde::{Deserialize, Serialize};
+use std::{
+  fs,
+  io::{self, Write},
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::settings::{ark_home, load_settings};
+
+const POINTER_FILE: &str = "current.json";
+const INFO_FILE: &str = "version.json";
+const STAGING_PREFIX: &str = ".staging-";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pointer {
+  pub version: String,
+  #[serde(default)]
+  pub previous: Option<String>,
+  #[serde(default)]
+  pub activated_at: u64,
+}
+
+/// Written into each version dir at install time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+  pub version: String,
+  #[serde(default)]
+  pub installed_at: u64,
+  /// First line of `arkd --version`, if it answered.
+  #[serde(default)]
+  pub reported: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledVersion {
+  pub version: String,
+  pub path: String,
+  pub installed_at: u64,
+  pub reported: Option<String>,
+  pub active: bool,
+  pub previous: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionList {
+  pub current: Option<String>,
+  pub previous: Option<String>,
+  pub keep: usize,
+  /// Newest install first.
+  pub items: Vec<InstalledVersion>,
+}
+
+fn now_ms() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+pub fn versions_dir(home: &Path) -> PathBuf { home.join("versions") }
+
+/// Directory names are the version strings; keep them path-safe.
+pub fn sanitize(version: &str) -> String {
+  let s: String = version.trim().chars()
+    .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+') { c } else { '_' })
+    .collect();
+  let s = s.trim_start_matches('.').to_string();
+  if s.is_empty() { "unknown".into() } else { s }
+}
+
+fn write_atomic(p: &Path, bytes: &[u8]) -> io::Result<()> {
+  if let Some(parent) = p.parent() { fs::create_dir_all(parent)?; }
+  let tmp = p.with_extension("json.tmp");
+  let mut f = fs::File::create(&tmp)?;
+  f.write_all(bytes)?;
+  f.sync_all()?;
+  fs::rename(tmp, p)
+}
+
+pub fn pointer(home: &Path) -> Option<Pointer> {
+  let bytes = fs::read(versions_dir(home).join(POINTER_FILE)).ok()?;
+  serde_json::from_slice(&bytes).ok()
+}
+
+/// `versions/<current>` if the pointer is set and the directory exists.
+pub fn active_dir(home: &Path) -> Option<PathBuf> {
+  let p = pointer(home)?;
+  let dir = versions_dir(home).join(&p.version);
+  dir.is_dir().then_some(dir)
+}
+
+fn info(dir: &Path) -> Option<VersionInfo> {
+  serde_json::from_slice(&fs::read(dir.join(INFO_FILE)).ok()?).ok()
+}
+
+fn installed(home: &Path) -> Vec<(String, VersionInfo)> {
+  let Ok(rd) = fs::read_dir(versions_dir(home)) else { return Vec::new() };
+  let mut v: Vec<(String, VersionInfo)> = rd.flatten()
+    .filter(|e| e.path().is_dir())
+    .filter_map(|e| {
+      let name = e.file_name().to_str()?.to_string();
+      if name.starts_with('.') { return None; }
+      let info = info(&e.path()).unwrap_or_else(|| VersionInfo { version: name.clone(), ..Default::default() });
+      Some((name, info))
+    })
+    .collect();
+  v.sort_by(|a, b| b.1.installed_at.cmp(&a.1.installed_at).then_with(|| b.0.cmp(&a.0)));
+  v
+}
+
+pub fn list(home: &Path, keep: usize) -> VersionList {
+  let ptr = pointer(home);
+  let current = ptr.as_ref().map(|p| p.version.clone());
+  let previous = ptr.as_ref().and_then(|p| p.previous.clone());
+  let items = installed(home).into_iter().map(|(name, info)| InstalledVersion {
+    path: versions_dir(home).join(&name).to_string_lossy().to_string(),
+    active: current.as_deref() == Some(name.as_str()),
+    previous: previous.as_deref() == Some(name.as_str()),
+    installed_at: info.installed_at,
+    reported: info.reported,
+    version: name,
+  }).collect();
+  VersionList { current, previous, keep, items }
+}
+
+/// Fresh empty staging dir under `versions/`, on the same filesystem as the target.
+pub fn staging_dir(home: &Path) -> Result<PathBuf, String> {
+  let dir = versions_dir(home).join(format!("{STAGING_PREFIX}{}", std::process::id()));
+  if dir.exists() { fs::remove_dir_all(&dir).map_err(|e| e.to_string())?; }
+  fs::create_dir_all(&dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
+  Ok(dir)
+}
+
+/// Move a filled staging dir to `versions/<version>`, activate it and prune.
+/// Returns the (sanitized) version name.
+pub fn commit(home: &Path, staging: &Path, version: &str, reported: Option<String>, keep: usize) -> Result<String, String> {
+  let name = sanitize(version);
+  let info = VersionInfo { version: name.clone(), installed_at: now_ms(), reported };
+  write_atomic(&staging.join(INFO_FILE), &serde_json::to_vec_pretty(&info).unwrap())
+    .map_err(|e| e.to_string())?;
+
+  let dest = versions_dir(home).join(&name);
+  if dest.exists() {
+    // reinstall of the same version: swap the old tree out first
+    let old = versions_dir(home).join(format!("{STAGING_PREFIX}old-{name}"));
+    let _ = fs::remove_dir_all(&old);
+    fs::rename(&dest, &old).map_err(|e| format!("replace {} failed (in use?): {e}", dest.display()))?;
+    let _ = fs::remove_dir_all(&old);
+  }
+  fs::rename(staging, &dest).map_err(|e| format!("move {} -> {} failed: {e}", staging.display(), dest.display()))?;
+
+  activate(home, &name)?;
+  prune(home, keep);
+  Ok(name)
+}
+
+/// Point `current` at an installed version.
+pub fn activate(home: &Path, version: &str) -> Result<(), String> {
+  let dir = versions_dir(home).join(version);
+  if sanitize(version) != version || !dir.is_dir() {
+    return Err(format!("version not installed: {version}"));
+  }
+  let prev = pointer(home).map(|p| p.version);
+  if prev.as_deref() == Some(version) { return Ok(()); }
+  let ptr = Pointer { version: version.to_string(), previous: prev, activated_at: now_ms() };
+  write_atomic(&versions_dir(home).join(POINTER_FILE), &serde_json::to_vec_pretty(&ptr).unwrap())
+    .map_err(|e| e.to_string())
+}
+
+/// Re-activate the previously active version, or else the newest install older than current.
+pub fn rollback(home: &Path) -> Result<String, String> {
+  let ptr = pointer(home).ok_or("no active version to roll back from")?;
+  let all = installed(home);
+  let target = ptr.previous
+    .filter(|p| p != &ptr.version && versions_dir(home).join(p).is_dir())
+    .or_else(|| {
+      let cur_at = all.iter().find(|(n, _)| n == &ptr.version).map(|(_, i)| i.installed_at).unwrap_or(u64::MAX);
+      all.iter()
+        .find(|(n, i)| n != &ptr.version && i.installed_at <= cur_at)
+        .map(|(n, _)| n.clone())
+    })
+    .ok_or("no earlier version installed")?;
+  activate(home, &target)?;
+  Ok(target)
+}
+
+/// Remove all but the `keep` newest installs; never the current or previous one.
+/// Also clears leftover staging dirs. Returns removed versions.
+pub fn prune(home: &Path, keep: usize) -> Vec<String> {
+  let ptr = pointer(home).unwrap_or_default();
+  let protected = |n: &str| n == ptr.version || ptr.previous.as_deref() == Some(n);
+
+  let mut removed = Vec::new();
+  for (n, _) in installed(home).into_iter().skip(keep.max(1)) {
+    if protected(&n) { continue; }
+    if fs::remove_dir_all(versions_dir(home).join(&n)).is_ok() { removed.push(n); }
+  }
+  if let Ok(rd) = fs::read_dir(versions_dir(home)) {
+    let mine = format!("{STAGING_PREFIX}{}", std::process::id());
+    for e in rd.flatten() {
+      let name = e.file_name().to_string_lossy().to_string();
+      if name.starts_with(STAGING_PREFIX) && name != mine { let _ = fs::remove_dir_all(e.path()); }
+    }
+  }
+  removed
+}
+
+/* ── COMMANDS ────────────────────────────────────────────────────────────── */
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn install_list_versions() -> Result<VersionList, String> {
+  Ok(list(&ark_home(), load_settings().keep_versions as usize))
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn install_activate(version: String) -> Result<VersionList, String> {
+  let home = ark_home();
+  activate(&home, &version)?;
+  Ok(list(&home, load_settings().keep_versions as usize))
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn install_rollback() -> Result<VersionList, String> {
+  let home = ark_home();
+  rollback(&home)?;
+  Ok(list(&home, load_settings().keep_versions as usize))
+}
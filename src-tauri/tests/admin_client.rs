@@ -0,0 +1,84 @@
+// tests/admin_client.rs
+mod common;
+
+use arkdesk_lib::admin_client::{AdminClient, AdminError};
+use arkdesk_lib::manifest::read_manifest;
+use common::{mock_node, mock_node::TOKEN, Fault};
+use std::time::Duration;
+
+fn client(port: u16, token: &str, timeout_ms: u64) -> AdminClient {
+  AdminClient::new("127.0.0.1", port, token, Duration::from_millis(timeout_ms))
+}
+
+#[tokio::test]
+async fn healthz_and_status_from_manifest() {
+  let (_dir, node) = mock_node().await;
+  let m = read_manifest(node.manifest_path()).unwrap();
+  let admin = AdminClient::from_manifest(&m);
+
+  let h = admin.healthz().await.unwrap();
+  assert!(h.ok);
+  assert_eq!(h.version, common::mock_node::VERSION);
+  assert_eq!(h.net.unwrap().name, "devnet");
+
+  node.set_chain(|c| { c.height = 4242; c.peers = 0; });
+  let s = admin.status().await.unwrap();
+  assert!(s.node_running);
+  assert!(!s.connected);
+  assert_eq!(s.network_height, 4242);
+  assert_eq!(s.rpc.port, node.rpc_port);
+}
+
+#[tokio::test]
+async fn wrong_token_is_unauthorized() {
+  let (_dir, node) = mock_node().await;
+  let err = client(node.admin_port, "nope", 2000).healthz().await.unwrap_err();
+  assert!(matches!(err, AdminError::Http(s) if s.as_u16() == 401), "{err}");
+}
+
+#[tokio::test]
+async fn shutdown_is_accepted() {
+  let (_dir, node) = mock_node().await;
+  assert!(client(node.admin_port, TOKEN, 2000).shutdown().await.unwrap());
+  assert!(node.shutdown_requested());
+}
+
+#[tokio::test]
+async fn http_500_surfaces_status() {
+  let (_dir, node) = mock_node().await;
+  node.fault("/v1/status", Fault::Status(500));
+  let admin = client(node.admin_port, TOKEN, 2000);
+
+  let err = admin.status().await.unwrap_err();
+  assert!(matches!(err, AdminError::Http(s) if s.as_u16() == 500), "{err}");
+  // other routes unaffected
+  assert!(admin.healthz().await.is_ok());
+}
+
+#[tokio::test]
+async fn slow_admin_times_out() {
+  let (_dir, node) = mock_node().await;
+  node.fault("/v1/healthz", Fault::Delay(Duration::from_millis(1500)));
+
+  let err = client(node.admin_port, TOKEN, 200).healthz().await.unwrap_err();
+  match err {
+    AdminError::Net(e) => assert!(e.is_timeout(), "{e:?}"),
+    other => panic!("expected timeout, got {other}"),
+  }
+}
+
+#[tokio::test]
+async fn non_json_body_is_a_decode_error() {
+  let (_dir, node) = mock_node().await;
+  node.fault("/v1/healthz", Fault::Garbage);
+
+  let err = client(node.admin_port, TOKEN, 2000).healthz().await.unwrap_err();
+  assert!(matches!(err, AdminError::Net(ref e) if e.is_decode()), "{err}");
+}
+
+#[tokio::test]
+async fn token_is_redacted() {
+  let admin = client(1, TOKEN, 100);
+  assert_eq!(admin.token_redacted(), "mock…6789");
+  assert_eq!(admin.base(), "http://127.0.0.1:1");
+}
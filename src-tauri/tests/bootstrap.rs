@@ -0,0 +1,90 @@
+// tests/bootstrap.rs
+//! Build-from-source bootstrap against a local git repo whose Makefile "builds"
+//! shell-script binaries. One test: it owns HOME.
+#![cfg(unix)]
+
+use arkdesk_lib::events::NullSink;
+use arkdesk_lib::install::Cancel;
+use arkdesk_lib::runner;
+use arkdesk_lib::versions;
+use std::{fs, path::Path, process::Command};
+
+const MAKEFILE: &str = "all:\n\tsh gen.sh\n";
+
+fn gen_sh(ver: &str) -> String {
+  format!(r#"mkdir -p out/bin
+for n in arkd cli call cc tests; do
+  printf '#!/bin/sh\necho "%s v{ver}"\n' "$n" > out/bin/$n
+  chmod +x out/bin/$n
+done
+echo "built {ver}"
+"#)
+}
+
+fn git(dir: &Path, args: &[&str]) {
+  let st = Command::new("git").arg("-C").arg(dir).args(args)
+    .env("GIT_AUTHOR_NAME", "t").env("GIT_AUTHOR_EMAIL", "t@example.com")
+    .env("GIT_COMMITTER_NAME", "t").env("GIT_COMMITTER_EMAIL", "t@example.com")
+    .status().unwrap();
+  assert!(st.success(), "git {args:?}");
+}
+
+fn release(repo: &Path, ver: &str) {
+  fs::write(repo.join("gen.sh"), gen_sh(ver)).unwrap();
+  git(repo, &["add", "-A"]);
+  git(repo, &["commit", "-q", "-m", ver]);
+  git(repo, &["tag", &format!("v{ver}")]);
+}
+
+#[tokio::test]
+async fn fetches_pinned_ref_builds_and_installs_a_version() {
+  let user = tempfile::tempdir().unwrap();
+  std::env::set_var("HOME", user.path());
+  let home = user.path().join(".arknet");
+
+  let repo = user.path().join("upstream");
+  fs::create_dir_all(&repo).unwrap();
+  git(&repo, &["init", "-q"]);
+  fs::write(repo.join("Makefile"), MAKEFILE).unwrap();
+  release(&repo, "0.8.0");
+  release(&repo, "0.8.1");
+  let url = repo.to_str().unwrap();
+  let src = user.path().join("src");
+
+  // pinned to the older tag
+  let f = runner::fetch(url, "v0.8.0", &src, &NullSink, &Cancel::default()).await.unwrap();
+  assert!(!f.updated && f.commit.len() == 40);
+  assert!(fs::read_to_string(src.join("gen.sh")).unwrap().contains("v0.8.0"));
+
+  let b = runner::build(&src, &home, &NullSink, &Cancel::default()).await.unwrap();
+  assert_eq!(b.version, format!("src-v0.8.0-{}", &f.commit[..10]));
+  assert_eq!(b.products.len(), 5);
+  assert!(b.products["arkd"].ends_with("out/bin/arkd"));
+  assert_eq!(versions::pointer(&home).unwrap().version, b.version);
+  let info = fs::read_to_string(versions::versions_dir(&home).join(&b.version).join("arkd")).unwrap();
+  assert!(info.contains("v0.8.0"));
+
+  // an existing checkout is moved to the new ref, not left alone
+  let f2 = runner::fetch(url, "v0.8.1", &src, &NullSink, &Cancel::default()).await.unwrap();
+  assert!(f2.updated && f2.commit != f.commit);
+  let b2 = runner::build(&src, &home, &NullSink, &Cancel::default()).await.unwrap();
+  let ptr = versions::pointer(&home).unwrap();
+  assert_eq!((ptr.version.as_str(), ptr.previous.as_deref()), (b2.version.as_str(), Some(b.version.as_str())));
+
+  // unknown ref: the error carries git's own message
+  let e = runner::fetch(url, "v9.9.9", &src, &NullSink, &Cancel::default()).await.unwrap_err();
+  assert!(e.starts_with("git fetch v9.9.9 failed") && e.contains("v9.9.9"), "{e}");
+
+  // a build that produces no arkd is reported, nothing is installed
+  fs::write(src.join("gen.sh"), "rm -rf out\necho nothing\n").unwrap();
+  let e = runner::build(&src, &home, &NullSink, &Cancel::default()).await.unwrap_err();
+  assert!(e.contains("no arkd was found"), "{e}");
+  assert_eq!(versions::pointer(&home).unwrap().version, b2.version);
+
+  // a non-checkout dest is refused rather than clobbered
+  let other = user.path().join("not-a-checkout");
+  fs::create_dir_all(&other).unwrap();
+  fs::write(other.join("keep.txt"), "x").unwrap();
+  let e = runner::fetch(url, "v0.8.1", &other, &NullSink, &Cancel::default()).await.unwrap_err();
+  assert!(e.contains("not a git checkout"), "{e}");
+}
@@ -0,0 +1,69 @@
+// tests/bridge.rs
+mod common;
+
+use arkdesk_lib::manifest::read_manifest;
+use arkdesk_lib::state::NodeBridge;
+use arkdesk_lib::types::ChainTip;
+use common::{mock_node, MockNode};
+use serde_json::json;
+
+#[tokio::test]
+async fn not_ready_without_manifest() {
+  let dir = tempfile::tempdir().unwrap();
+  let bridge = NodeBridge::new(dir.path().join("node.json"));
+  assert_eq!(bridge.admin().err(), Some("admin not ready"));
+  assert_eq!(bridge.rpc().err(), Some("rpc not ready"));
+  assert!(bridge.manifest().is_none());
+}
+
+#[tokio::test]
+async fn lazily_picks_up_manifest() {
+  let (_dir, node) = mock_node().await;
+  let bridge = NodeBridge::new(node.manifest_path().to_path_buf());
+
+  let admin = bridge.admin().unwrap();
+  assert_eq!(admin.base(), format!("http://127.0.0.1:{}", node.admin_port));
+  assert!(admin.healthz().await.unwrap().ok);
+
+  let tip: ChainTip = bridge.rpc().unwrap().call("chain.tip", json!({})).await.unwrap();
+  assert_eq!(tip.height, 100);
+  assert_eq!(bridge.manifest().unwrap().rpc.port, node.rpc_port);
+}
+
+#[tokio::test]
+async fn update_manifest_sets_clients() {
+  let (dir, node) = mock_node().await;
+  let bridge = NodeBridge::new(dir.path().join("elsewhere.json"));
+  bridge.update_manifest(read_manifest(node.manifest_path()).unwrap());
+  assert!(bridge.admin().unwrap().status().await.is_ok());
+}
+
+#[tokio::test]
+async fn follows_node_restart_on_new_ports() {
+  let (dir, first) = mock_node().await;
+  let run = first.manifest_path().parent().unwrap().to_path_buf();
+  let bridge = NodeBridge::new(first.manifest_path().to_path_buf());
+  assert_eq!(bridge.manifest().map(|m| m.admin.port), None);
+  bridge.admin().unwrap();
+  assert_eq!(bridge.manifest().unwrap().admin.port, first.admin_port);
+
+  // arkd restarts: old ports go away, node.json is rewritten
+  drop(first);
+  let second = MockNode::start(&run).await;
+  bridge.maybe_refresh();
+  assert_eq!(bridge.manifest().unwrap().admin.port, second.admin_port);
+  assert!(bridge.admin().unwrap().healthz().await.is_ok());
+  drop(dir);
+}
+
+#[tokio::test]
+async fn corrupt_manifest_keeps_last_good_clients() {
+  let (_dir, node) = mock_node().await;
+  let bridge = NodeBridge::new(node.manifest_path().to_path_buf());
+  bridge.admin().unwrap();
+
+  std::fs::write(node.manifest_path(), b"{ half written").unwrap();
+  bridge.maybe_refresh();
+  assert_eq!(bridge.manifest().unwrap().admin.port, node.admin_port);
+  assert!(bridge.admin().unwrap().healthz().await.is_ok());
+}
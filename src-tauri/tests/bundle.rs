@@ -0,0 +1,143 @@
+// tests/bundle.rs
+#![cfg(unix)]
+
+use arkdesk_lib::bundle;
+use arkdesk_lib::install::Cancel;
+use arkdesk_lib::versions;
+use ed25519_dalek::SigningKey;
+use std::{fs, io::Write, os::unix::fs::PermissionsExt, path::{Path, PathBuf}};
+use zip::write::SimpleFileOptions;
+
+fn key(seed: u8) -> SigningKey { SigningKey::from_bytes(&[seed; 32]) }
+
+fn plat() -> &'static str { if cfg!(target_os = "macos") { "darwin" } else { "linux" } }
+
+/// Trust `key(1)` (runtime override, debug builds).
+fn trust() {
+  std::env::set_var("ARKDESK_INTEGRITY_PUBKEY", hex::encode(key(1).verifying_key().to_bytes()));
+}
+
+/// Staged resources with arkd reporting `ver`.
+fn resources(ver: &str) -> tempfile::TempDir {
+  let dir = tempfile::tempdir().unwrap();
+  let bin = dir.path().join("bin").join(plat());
+  fs::create_dir_all(&bin).unwrap();
+  for n in ["arkd", "cli", "call", "cc", "tests"] {
+    let p = bin.join(n);
+    fs::write(&p, format!("#!/bin/sh\necho \"{n} v{ver}\"\n")).unwrap();
+    fs::set_permissions(&p, fs::Permissions::from_mode(0o755)).unwrap();
+  }
+  fs::create_dir_all(dir.path().join("wheels")).unwrap();
+  fs::write(dir.path().join("wheels/arknet_py-0.2.0-py3-none-any.whl"), b"PK").unwrap();
+  dir
+}
+
+fn build(out: &Path, abi: u32, platform: &str, signer: Option<&SigningKey>) -> PathBuf {
+  let res = resources("0.9.0");
+  bundle::create(res.path(), "0.9.0", abi, platform, signer, out).unwrap();
+  out.to_path_buf()
+}
+
+#[tokio::test]
+async fn signed_bundle_installs_as_new_version() {
+  trust();
+  let tmp = tempfile::tempdir().unwrap();
+  let home = tempfile::tempdir().unwrap();
+  let zip = build(&tmp.path().join("u.zip"), 1, plat(), Some(&key(1)));
+
+  let r = bundle::import(&zip, home.path(), &Cancel::default()).await.unwrap();
+  assert_eq!(r.version, "0.9.0");
+  assert!(r.signature_ok);
+  // no venv yet: wheels are skipped with a note, bins still installed
+  assert!(!r.arkpy_updated && r.notes.iter().any(|n| n.contains("venv")));
+  assert_eq!(r.versions.current.as_deref(), Some("0.9.0"));
+  let arkd = versions::versions_dir(home.path()).join("0.9.0/arkd");
+  assert!(fs::metadata(&arkd).unwrap().permissions().mode() & 0o111 != 0);
+  // extraction dir cleaned up
+  assert!(fs::read_dir(versions::versions_dir(home.path())).unwrap().flatten()
+    .all(|e| !e.file_name().to_string_lossy().starts_with(".import")));
+}
+
+#[tokio::test]
+async fn arkpy_is_upgraded_in_a_copy_before_the_version_goes_live() {
+  trust();
+  let tmp = tempfile::tempdir().unwrap();
+  let home = tempfile::tempdir().unwrap();
+  let fail = tmp.path().join("pip-fails");
+  let py = home.path().join("pyenv/bin/python");
+  fs::create_dir_all(py.parent().unwrap()).unwrap();
+  fs::write(&py, format!(
+    "#!/bin/sh\n[ -f '{}' ] && exit 1\necho upgraded > \"$(dirname \"$0\")/../marker\"\n",
+    fail.display()
+  )).unwrap();
+  fs::set_permissions(&py, fs::Permissions::from_mode(0o755)).unwrap();
+  let zip = build(&tmp.path().join("u.zip"), 1, plat(), Some(&key(1)));
+
+  // a failed upgrade activates nothing
+  fs::write(&fail, "").unwrap();
+  let e = bundle::import(&zip, home.path(), &Cancel::default()).await.unwrap_err();
+  assert!(e.contains("ArkPy"), "{e}");
+  assert!(versions::pointer(home.path()).is_none());
+  assert!(!home.path().join("pyenv/marker").exists() && py.is_file());
+
+  fs::remove_file(&fail).unwrap();
+  let r = bundle::import(&zip, home.path(), &Cancel::default()).await.unwrap();
+  assert!(r.arkpy_updated && r.versions.current.as_deref() == Some("0.9.0"));
+  assert_eq!(fs::read_to_string(home.path().join("pyenv/marker")).unwrap(), "upgraded\n");
+}
+
+#[tokio::test]
+async fn rejects_untrusted_or_incompatible_bundles() {
+  trust();
+  let tmp = tempfile::tempdir().unwrap();
+  let home = tempfile::tempdir().unwrap();
+  let import = |zip: PathBuf| {
+    let home = home.path().to_path_buf();
+    async move { bundle::import(&zip, &home, &Cancel::default()).await.unwrap_err() }
+  };
+
+  let e = import(build(&tmp.path().join("k2.zip"), 1, plat(), Some(&key(2)))).await;
+  assert!(e.contains("integrity check failed"), "{e}");
+
+  let e = import(build(&tmp.path().join("abi.zip"), 99, plat(), Some(&key(1)))).await;
+  assert!(e.contains("abiRev 99"), "{e}");
+
+  let other = if plat() == "linux" { "windows" } else { "linux" };
+  let res = resources("0.9.0");
+  fs::rename(res.path().join("bin").join(plat()), res.path().join("bin").join(other)).unwrap();
+  let zip = tmp.path().join("plat.zip");
+  bundle::create(res.path(), "0.9.0", 1, other, Some(&key(1)), &zip).unwrap();
+  let e = import(zip).await;
+  assert!(e.contains(&format!("bundle is for {other}")), "{e}");
+
+  assert!(versions::pointer(home.path()).is_none());
+}
+
+fn raw_zip(out: &Path, entries: &[(&str, &[u8])]) -> PathBuf {
+  let mut z = zip::ZipWriter::new(fs::File::create(out).unwrap());
+  for (name, body) in entries {
+    z.start_file(*name, SimpleFileOptions::default()).unwrap();
+    z.write_all(body).unwrap();
+  }
+  z.finish().unwrap();
+  out.to_path_buf()
+}
+
+#[tokio::test]
+async fn rejects_unsafe_paths_and_foreign_entries() {
+  let tmp = tempfile::tempdir().unwrap();
+  let home = tempfile::tempdir().unwrap();
+
+  let slip = raw_zip(&tmp.path().join("slip.zip"), &[("bin/../../evil", b"x")]);
+  let e = bundle::import(&slip, home.path(), &Cancel::default()).await.unwrap_err();
+  assert!(e.contains("unsafe path"), "{e}");
+  assert!(!home.path().join("evil").exists() && !tmp.path().join("evil").exists());
+
+  let foreign = raw_zip(&tmp.path().join("etc.zip"), &[("etc/profile", b"x")]);
+  let e = bundle::import(&foreign, home.path(), &Cancel::default()).await.unwrap_err();
+  assert!(e.contains("unexpected entry"), "{e}");
+
+  let no_meta = raw_zip(&tmp.path().join("nometa.zip"), &[("bin/linux/arkd", b"x")]);
+  let e = bundle::import(&no_meta, home.path(), &Cancel::default()).await.unwrap_err();
+  assert!(e.contains("bundle.json missing"), "{e}");
+}
@@ -0,0 +1,95 @@
+// tests/cleanup.rs
+//! Dry runs and backups for the cleanup commands.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::cleanup::{cleanup_spurious_dirs, wipe_ark_home, CleanupOptions};
+use common::TestHome;
+use std::{fs, os::unix::fs::symlink, path::Path};
+
+const TOKEN: &str = "ARKNET-NUKE";
+
+fn dry() -> Option<CleanupOptions> { Some(CleanupOptions { dry_run: true, backup: None }) }
+
+fn backup_to(dir: &Path) -> Option<CleanupOptions> {
+  Some(CleanupOptions { dry_run: false, backup: Some(dir.to_string_lossy().into()) })
+}
+
+fn unpack(archive: &str) -> Vec<String> {
+  let dec = zstd::Decoder::new(fs::File::open(archive).unwrap()).unwrap();
+  let mut names: Vec<String> = tar::Archive::new(dec)
+    .entries()
+    .unwrap()
+    .map(|e| e.unwrap().path().unwrap().to_string_lossy().trim_end_matches('/').to_string())
+    .collect();
+  names.sort();
+  names
+}
+
+#[test]
+fn dry_run_lists_every_file_and_touches_nothing() {
+  let t = TestHome::new();
+  t.put("keys/wallet.key", 32);
+  t.put("data/chain.db", 1000);
+  common::put(&t.user_dir().join(".arknet.staging/journal.json"), 5);
+  symlink("/nonexistent", t.path("data/link")).unwrap();
+  let r = wipe_ark_home(String::new(), dry()).unwrap();
+  assert!(r.dry_run && r.removed.is_empty() && r.archive.is_none());
+  let user = t.user_dir().to_string_lossy().into_owned();
+  let files: Vec<&str> = r.files.iter().map(|f| f.path.strip_prefix(&*user).unwrap()).collect();
+  assert_eq!(files, ["/.arknet/data/chain.db", "/.arknet/data/link", "/.arknet/keys/wallet.key", "/.arknet.staging/journal.json"]);
+  assert_eq!(r.bytes_freed, 1000 + "/nonexistent".len() as u64 + 32 + 5);
+  assert!(t.path("keys/wallet.key").exists());
+}
+
+#[test]
+fn wipe_needs_the_exact_token() {
+  let t = TestHome::new();
+  t.put("keys/wallet.key", 32);
+  assert!(wipe_ark_home(TOKEN.to_lowercase(), None).is_err());
+  assert!(t.path("keys/wallet.key").exists());
+}
+
+#[test]
+fn a_backup_inside_the_home_is_refused_up_front() {
+  let t = TestHome::new();
+  t.put("keys/wallet.key", 32);
+  assert!(wipe_ark_home(TOKEN.into(), backup_to(&t.path("bak"))).unwrap_err().contains("inside"));
+  assert!(t.path("keys/wallet.key").exists());
+}
+
+#[test]
+fn wipe_backs_up_then_removes() {
+  let t = TestHome::new();
+  t.put("keys/wallet.key", 32);
+  common::put(&t.user_dir().join(".arknet.staging/journal.json"), 5);
+  t.put("data/chain.db", 1000);
+  symlink("/nonexistent", t.path("data/link")).unwrap();
+  let backups = t.user_dir().join("backups");
+  let r = wipe_ark_home(TOKEN.into(), backup_to(&backups)).unwrap();
+  assert!(!t.home.exists() && !t.user_dir().join(".arknet.staging").exists());
+  assert_eq!(r.removed.len(), 2);
+  let archive = r.archive.unwrap();
+  assert!(archive.starts_with(&*backups.to_string_lossy()) && archive.ends_with(".tar.zst"), "{archive}");
+  let names = unpack(&archive);
+  for want in [".arknet/keys/wallet.key", ".arknet/data/chain.db", ".arknet/data/link", ".arknet.staging/journal.json"] {
+    assert!(names.iter().any(|n| n == want), "{want} not in {names:?}");
+  }
+  assert_eq!(fs::read_dir(&backups).unwrap().count(), 1);
+}
+
+#[test]
+fn spurious_dirs_need_force_and_are_backed_up() {
+  let t = TestHome::new();
+  let cwd = t.user_dir().join("project");
+  common::put(&cwd.join(".arknet/stray.txt"), 7);
+  std::env::set_current_dir(&cwd).unwrap();
+
+  let r = cleanup_spurious_dirs(false, dry()).unwrap();
+  assert!(r.files.is_empty() && r.skipped[0].reason.contains("not empty"));
+  let r = cleanup_spurious_dirs(true, backup_to(&t.user_dir().join("backups"))).unwrap();
+  assert_eq!((r.bytes_freed, r.removed.len()), (7, 1));
+  assert_eq!(unpack(&r.archive.unwrap()), [".arknet", ".arknet/stray.txt"]);
+  assert!(!cwd.join(".arknet").exists());
+}
@@ -0,0 +1,320 @@
+// tests/common/mock_node.rs
+//! In-process stand-in for arkd: admin HTTP (`/v1/healthz`, `/v1/status`,
+//! `/v1/shutdown`, bearer auth) and JSON-RPC on a second port, plus a `node.json`
+//! pointing at both. Faults can be injected per admin route or RPC method.
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::{Duration, Instant},
+};
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{TcpListener, TcpStream},
+  task::JoinHandle,
+};
+
+pub const TOKEN: &str = "mock-admin-token-0123456789";
+pub const VERSION: &str = "0.0.0-mock";
+pub const METHODS: &[&str] = &["chain.tip", "chain.header", "mempool.info", "net.peers", "rpc.list"];
+
+/// What to do instead of (or before) the normal reply.
+#[derive(Debug, Clone)]
+pub enum Fault {
+  /// Sleep, then answer normally.
+  Delay(Duration),
+  /// Reply with this HTTP status and an empty body.
+  Status(u16),
+  /// JSON-RPC error envelope (HTTP 200).
+  RpcError(i64, String),
+  /// HTTP 200 with a body that is not JSON.
+  Garbage,
+}
+
+#[derive(Debug, Clone)]
+pub struct Chain {
+  pub height: u64,
+  pub hash: String,
+  pub mempool_txs: u64,
+  pub mempool_bytes: u64,
+  pub peers: u32,
+}
+
+impl Default for Chain {
+  fn default() -> Self {
+    Self { height: 100, hash: hash_for(100), mempool_txs: 3, mempool_bytes: 512, peers: 2 }
+  }
+}
+
+pub fn hash_for(height: u64) -> String { format!("{height:064x}") }
+
+#[derive(Default)]
+struct Shared {
+  chain: Chain,
+  faults: HashMap<String, Fault>,
+  hits: HashMap<String, u32>,
+  shutdown: bool,
+  /// Touched on `/v1/shutdown` so a child process standing in for arkd can exit.
+  shutdown_marker: PathBuf,
+}
+
+pub struct MockNode {
+  pub admin_port: u16,
+  pub rpc_port: u16,
+  manifest: PathBuf,
+  shared: Arc<Mutex<Shared>>,
+  tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockNode {
+  /// Bind both listeners on 127.0.0.1 and write `<run_dir>/node.json`.
+  pub async fn start(run_dir: &Path) -> Self {
+    let admin = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let rpc = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let admin_port = admin.local_addr().unwrap().port();
+    let rpc_port = rpc.local_addr().unwrap().port();
+
+    let shared = Arc::new(Mutex::new(Shared { shutdown_marker: run_dir.join("shutdown"), ..Default::default() }));
+    let started = Instant::now();
+    let tasks = vec![
+      tokio::spawn(serve(admin, Api::Admin { rpc_port, started }, shared.clone())),
+      tokio::spawn(serve(rpc, Api::Rpc, shared.clone())),
+    ];
+
+    let node = Self { admin_port, rpc_port, manifest: run_dir.join("node.json"), shared, tasks };
+    node.write_manifest();
+    node
+  }
+
+  pub fn manifest_path(&self) -> &Path { &self.manifest }
+
+  pub fn manifest_json(&self) -> Value {
+    json!({
+      "pid": std::process::id(),
+      "startedAt": 1_700_000_000_000u64,
+      "version": VERSION,
+      "abiRev": 1,
+      "features": ["admin", "rpc"],
+      "admin": { "host": "127.0.0.1", "port": self.admin_port, "token": TOKEN },
+      "rpc": { "host": "127.0.0.1", "port": self.rpc_port },
+      "net": { "name": "devnet", "id": 1 },
+    })
+  }
+
+  /// (Re)write node.json atomically, like arkd does.
+  pub fn write_manifest(&self) {
+    if let Some(parent) = self.manifest.parent() { fs::create_dir_all(parent).unwrap(); }
+    let tmp = self.manifest.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(&self.manifest_json()).unwrap()).unwrap();
+    fs::rename(tmp, &self.manifest).unwrap();
+  }
+
+  /// Inject `fault` for an admin route (`/v1/status`) or RPC method (`chain.tip`).
+  pub fn fault(&self, key: &str, fault: Fault) {
+    self.shared.lock().faults.insert(key.to_string(), fault);
+  }
+
+  pub fn clear_faults(&self) { self.shared.lock().faults.clear(); }
+
+  pub fn set_chain(&self, f: impl FnOnce(&mut Chain)) { f(&mut self.shared.lock().chain); }
+
+  /// Requests seen for a route or method, including faulted ones.
+  pub fn hits(&self, key: &str) -> u32 { self.shared.lock().hits.get(key).copied().unwrap_or(0) }
+
+  /// Also visible as a `shutdown` file next to node.json.
+  pub fn shutdown_requested(&self) -> bool { self.shared.lock().shutdown }
+}
+
+impl Drop for MockNode {
+  fn drop(&mut self) {
+    for t in &self.tasks { t.abort(); }
+  }
+}
+
+/* ── HTTP plumbing ───────────────────────────────────────────────────────── */
+
+#[derive(Clone, Copy)]
+enum Api {
+  Admin { rpc_port: u16, started: Instant },
+  Rpc,
+}
+
+struct Request {
+  method: String,
+  path: String,
+  auth: Option<String>,
+  body: Vec<u8>,
+}
+
+struct Reply {
+  status: u16,
+  body: Vec<u8>,
+}
+
+impl Reply {
+  fn json(status: u16, v: Value) -> Self { Self { status, body: serde_json::to_vec(&v).unwrap() } }
+  fn empty(status: u16) -> Self { Self { status, body: Vec::new() } }
+}
+
+async fn serve(listener: TcpListener, api: Api, shared: Arc<Mutex<Shared>>) {
+  loop {
+    let Ok((sock, _)) = listener.accept().await else { continue };
+    tokio::spawn(handle_conn(sock, api, shared.clone()));
+  }
+}
+
+async fn handle_conn(mut sock: TcpStream, api: Api, shared: Arc<Mutex<Shared>>) {
+  let Some(req) = read_request(&mut sock).await else { return };
+  let reply = match api {
+    Api::Admin { rpc_port, started } => admin_reply(&req, rpc_port, started, &shared).await,
+    Api::Rpc => rpc_reply(&req, &shared).await,
+  };
+  let head = format!(
+    "HTTP/1.1 {} {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+    reply.status, reason(reply.status), reply.body.len(),
+  );
+  let _ = sock.write_all(head.as_bytes()).await;
+  let _ = sock.write_all(&reply.body).await;
+  let _ = sock.shutdown().await;
+}
+
+async fn read_request(sock: &mut TcpStream) -> Option<Request> {
+  let mut buf = Vec::new();
+  let mut chunk = [0u8; 4096];
+  let head_end = loop {
+    let n = sock.read(&mut chunk).await.ok()?;
+    if n == 0 { return None; }
+    buf.extend_from_slice(&chunk[..n]);
+    if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") { break i + 4; }
+  };
+
+  let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+  let mut lines = head.split("\r\n");
+  let mut start = lines.next()?.split_whitespace();
+  let method = start.next()?.to_string();
+  let path = start.next()?.to_string();
+
+  let mut len = 0usize;
+  let mut auth = None;
+  for line in lines {
+    let Some((k, v)) = line.split_once(':') else { continue };
+    match k.trim().to_ascii_lowercase().as_str() {
+      "content-length" => len = v.trim().parse().unwrap_or(0),
+      "authorization" => auth = Some(v.trim().to_string()),
+      _ => {}
+    }
+  }
+
+  let mut body = buf[head_end..].to_vec();
+  while body.len() < len {
+    let n = sock.read(&mut chunk).await.ok()?;
+    if n == 0 { break; }
+    body.extend_from_slice(&chunk[..n]);
+  }
+  Some(Request { method, path, auth, body })
+}
+
+fn reason(status: u16) -> &'static str {
+  match status {
+    200 => "OK",
+    202 => "Accepted",
+    401 => "Unauthorized",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    500 => "Internal Server Error",
+    503 => "Service Unavailable",
+    _ => "Status",
+  }
+}
+
+/// Count the hit and apply any fault; `Some` short-circuits the normal reply.
+async fn apply_fault(key: &str, shared: &Mutex<Shared>, id: &Value) -> Option<Reply> {
+  let fault = {
+    let mut s = shared.lock();
+    *s.hits.entry(key.to_string()).or_default() += 1;
+    s.faults.get(key).cloned()
+  };
+  match fault? {
+    Fault::Delay(d) => { tokio::time::sleep(d).await; None }
+    Fault::Status(code) => Some(Reply::empty(code)),
+    Fault::RpcError(code, message) => Some(Reply::json(200, json!({
+      "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message },
+    }))),
+    Fault::Garbage => Some(Reply { status: 200, body: b"<html>not json</html>".to_vec() }),
+  }
+}
+
+/* ── admin API ───────────────────────────────────────────────────────────── */
+
+async fn admin_reply(req: &Request, rpc_port: u16, started: Instant, shared: &Mutex<Shared>) -> Reply {
+  if let Some(r) = apply_fault(&req.path, shared, &Value::Null).await { return r; }
+  if req.auth.as_deref() != Some(format!("Bearer {TOKEN}").as_str()) {
+    return Reply::json(401, json!({ "error": "unauthorized" }));
+  }
+
+  let chain = shared.lock().chain.clone();
+  match (req.method.as_str(), req.path.as_str()) {
+    ("GET", "/v1/healthz") => Reply::json(200, json!({
+      "ok": true,
+      "version": VERSION,
+      "abiRev": 1,
+      "uptimeMs": started.elapsed().as_millis() as u64,
+      "features": ["admin", "rpc"],
+      "net": { "name": "devnet", "id": 1 },
+    })),
+    ("GET", "/v1/status") => Reply::json(200, json!({
+      "nodeRunning": true,
+      "connected": chain.peers > 0,
+      "peers": chain.peers,
+      "networkHeight": chain.height,
+      "role": "relay",
+      "producerOn": false,
+      "rpc": { "host": "127.0.0.1", "port": rpc_port },
+    })),
+    ("POST", "/v1/shutdown") => {
+      let marker = {
+        let mut g = shared.lock();
+        g.shutdown = true;
+        g.shutdown_marker.clone()
+      };
+      let _ = fs::write(marker, b"");
+      Reply::empty(202)
+    }
+    (_, "/v1/healthz" | "/v1/status" | "/v1/shutdown") => Reply::empty(405),
+    _ => Reply::empty(404),
+  }
+}
+
+/* ── JSON-RPC ────────────────────────────────────────────────────────────── */
+
+async fn rpc_reply(req: &Request, shared: &Mutex<Shared>) -> Reply {
+  let Ok(call) = serde_json::from_slice::<Value>(&req.body) else {
+    return Reply::json(200, json!({
+      "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": "parse error" },
+    }));
+  };
+  let id = call.get("id").cloned().unwrap_or(Value::Null);
+  let method = call.get("method").and_then(Value::as_str).unwrap_or_default().to_string();
+
+  if let Some(r) = apply_fault(&method, shared, &id).await { return r; }
+
+  let chain = shared.lock().chain.clone();
+  let result = match method.as_str() {
+    "chain.tip" => json!({ "height": chain.height, "hash": chain.hash, "timeMs": 1_700_000_000_000u64 + chain.height }),
+    // arkd reports header heights as decimal strings
+    "chain.header" => json!({ "height": chain.height.to_string(), "hash": chain.hash }),
+    "mempool.info" => json!({ "count": chain.mempool_txs, "bytes": chain.mempool_bytes }),
+    "net.peers" => json!({
+      "total": chain.peers,
+      "peers": (0..chain.peers).map(|i| json!({ "addr": format!("10.0.0.{}:8646", i + 1) })).collect::<Vec<_>>(),
+    }),
+    "rpc.list" => json!(METHODS),
+    _ => return Reply::json(200, json!({
+      "jsonrpc": "2.0", "id": id, "error": { "code": -32601, "message": "method not found" },
+    })),
+  };
+  Reply::json(200, json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
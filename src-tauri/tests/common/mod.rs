@@ -0,0 +1,118 @@
+// tests/common/mod.rs
+//! Shared integration-test support. Not every test binary uses every helper.
+#![allow(dead_code, unused_imports)]
+
+pub mod mock_node;
+
+use arkdesk_lib::events::EventSink;
+use arkdesk_lib::settings::{save_settings, Settings};
+use parking_lot::{Mutex, MutexGuard};
+use serde_json::Value;
+use std::{fs, net::TcpListener, path::{Path, PathBuf}};
+
+pub use mock_node::{Fault, MockNode};
+
+/// Collects every emitted event in order.
+#[derive(Default)]
+pub struct RecordingSink {
+  events: Mutex<Vec<(String, Value)>>,
+}
+
+impl EventSink for RecordingSink {
+  fn emit_value(&self, event: &str, payload: Value) {
+    self.events.lock().push((event.to_string(), payload));
+  }
+}
+
+impl RecordingSink {
+  pub fn take(&self) -> Vec<(String, Value)> { std::mem::take(&mut *self.events.lock()) }
+
+  /// Payloads of `event`, drained from the buffer.
+  pub fn take_named(&self, event: &str) -> Vec<Value> {
+    let mut all = self.events.lock();
+    let (hit, rest): (Vec<_>, Vec<_>) = all.drain(..).partition(|(e, _)| e == event);
+    *all = rest;
+    hit.into_iter().map(|(_, v)| v).collect()
+  }
+
+  pub fn names(&self) -> Vec<String> { self.events.lock().iter().map(|(e, _)| e.clone()).collect() }
+}
+
+/// Temp run dir plus a mock node writing `node.json` into it.
+pub async fn mock_node() -> (tempfile::TempDir, MockNode) {
+  let dir = tempfile::tempdir().unwrap();
+  let node = MockNode::start(&dir.path().join("run")).await;
+  (dir, node)
+}
+
+/* ── isolated Arknet homes ──────────────────────────────────────────────── */
+
+/// HOME and the override env vars are process-wide: a test that sets them holds
+/// this for its whole run, so tests in one binary take turns.
+static ENV_LOCK: Mutex<()> = parking_lot::const_mutex(());
+
+/// Cleared for every `TestHome` so nothing leaks in from the shell or an earlier test.
+const ENV_VARS: &[&str] = &[
+  "ARK_HOME", "ARK_RUN_DIR", "ARK_ARKD", "ARK_RPC_PATH", "ARK_RPC_PORT",
+  "ARKDESK_BIN_DIR", "ARKDESK_PY", "ARKDESK_RESOURCES", "ARKDESK_INTEGRITY_PUBKEY",
+  "XDG_CONFIG_HOME", "XDG_DATA_HOME",
+];
+
+/// A temp dir as HOME, with the default Arknet home (`~/.arknet`) under it.
+pub struct TestHome {
+  pub user: tempfile::TempDir,
+  pub home: PathBuf,
+  _env: MutexGuard<'static, ()>,
+}
+
+impl TestHome {
+  pub fn new() -> Self {
+    let env = ENV_LOCK.lock();
+    let user = tempfile::tempdir().unwrap();
+    std::env::set_var("HOME", user.path());
+    for var in ENV_VARS { std::env::remove_var(var); }
+    arkdesk_lib::config::set_cli(Default::default());
+    let home = user.path().join(".arknet");
+    TestHome { user, home, _env: env }
+  }
+
+  /// The temp dir standing in for the user's home directory.
+  pub fn user_dir(&self) -> &Path { self.user.path() }
+
+  pub fn path(&self, rel: &str) -> PathBuf { self.home.join(rel) }
+
+  /// `path` as the display string reports use.
+  pub fn rel(&self, rel: &str) -> String { self.path(rel).display().to_string() }
+
+  /// A file of `bytes` bytes under the Arknet home.
+  pub fn put(&self, rel: &str, bytes: usize) -> PathBuf {
+    let p = self.path(rel);
+    put(&p, bytes);
+    p
+  }
+
+  /// `config.json` on free ports so a started node doesn't clash with anything.
+  pub fn save_free_ports(&self) {
+    save_settings(Settings { p2p_port: free_port(), rpc_port: free_port(), ..Settings::default() }, None).unwrap();
+  }
+
+  /// A stand-in arkd that idles until killed, used through `ARK_ARKD`.
+  #[cfg(unix)]
+  pub fn fake_arkd(&self) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+    let arkd = self.user_dir().join("fake-arkd");
+    fs::write(&arkd, "#!/bin/sh\nwhile true; do sleep 0.1; done\n").unwrap();
+    fs::set_permissions(&arkd, fs::Permissions::from_mode(0o755)).unwrap();
+    std::env::set_var("ARK_ARKD", &arkd);
+    arkd
+  }
+}
+
+pub fn put(p: &Path, bytes: usize) {
+  fs::create_dir_all(p.parent().unwrap()).unwrap();
+  fs::write(p, vec![b'x'; bytes]).unwrap();
+}
+
+pub fn free_port() -> u16 {
+  TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
@@ -0,0 +1,123 @@
+// tests/config.rs
+//! Layered configuration: defaults, `config.json`, env, CLI flags.
+
+mod common;
+
+use arkdesk_lib::config::{self, Key, Source};
+use arkdesk_lib::integrity::TrustPolicy;
+use arkdesk_lib::manifest::default_manifest_path;
+use arkdesk_lib::resources::Resources;
+use arkdesk_lib::settings::ark_home;
+use common::TestHome;
+use std::fs;
+
+fn source(key: Key) -> (Option<String>, Source) {
+  let e = config::resolve(key);
+  (e.value, e.source)
+}
+
+/// A `config.json` holding only `rpcPort`.
+fn file_rpc_port(t: &TestHome, port: u16) {
+  fs::create_dir_all(&t.home).unwrap();
+  fs::write(t.path("config.json"), format!(r#"{{"rpcPort": {port}}}"#)).unwrap();
+}
+
+fn flags(args: &[&str]) -> Vec<String> {
+  let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+  config::take_flags(&args).unwrap().to_vec()
+}
+
+#[test]
+fn defaults() {
+  let t = TestHome::new();
+  assert_eq!(ark_home(), t.home);
+  assert_eq!(source(Key::RpcPort), (Some("8645".into()), Source::Default));
+  assert_eq!(source(Key::RpcPath), (Some("/".into()), Source::Default));
+  assert_eq!(source(Key::Arkd), (None, Source::Default));
+  assert_eq!(source(Key::Resources), (None, Source::Default));
+}
+
+#[test]
+fn env_beats_config_file() {
+  let t = TestHome::new();
+  file_rpc_port(&t, 9100);
+  assert_eq!(source(Key::RpcPort), (Some("9100".into()), Source::File));
+  std::env::set_var("ARK_RPC_PORT", "9200");
+  assert_eq!(source(Key::RpcPort), (Some("9200".into()), Source::Env));
+  assert_eq!(config::settings().rpc_port, 9200);
+}
+
+#[test]
+fn unusable_values_fall_through_and_are_reported() {
+  let t = TestHome::new();
+  file_rpc_port(&t, 9100);
+  std::env::set_var("ARK_RPC_PORT", "not-a-port");
+  let e = config::resolve(Key::RpcPort);
+  assert_eq!((e.value.as_deref(), e.source), (Some("9100"), Source::File));
+  assert_eq!(e.ignored, [r#"ARK_RPC_PORT="not-a-port""#]);
+}
+
+#[test]
+fn ark_home_moves_config_and_run_dir() {
+  let t = TestHome::new();
+  let other = t.user_dir().join("elsewhere");
+  file_rpc_port(&t, 9100);
+  std::env::set_var("ARK_HOME", &other);
+  assert_eq!(ark_home(), other);
+  // config.json moved with the home
+  assert_eq!(source(Key::RpcPort).1, Source::Default);
+  fs::create_dir_all(other.join("var/devnet/run")).unwrap();
+  assert_eq!(default_manifest_path(), other.join("var/devnet/run/node.json"));
+  std::env::set_var("ARK_RUN_DIR", t.user_dir().join("run"));
+  assert_eq!(default_manifest_path(), t.user_dir().join("run/node.json"));
+}
+
+#[test]
+fn cli_flags_beat_env_and_stop_at_the_command() {
+  let _t = TestHome::new();
+  std::env::set_var("ARK_RPC_PORT", "9200");
+  assert_eq!(flags(&["--ark-home", "/from/cli", "--rpc-port=9300", "status", "--rpc-port", "1"]), ["status", "--rpc-port", "1"]);
+  assert_eq!(ark_home(), std::path::Path::new("/from/cli"));
+  assert_eq!(source(Key::RpcPort), (Some("9300".into()), Source::Cli));
+  assert!(config::take_flags(&["--python".to_string()]).unwrap_err().contains("--python"));
+}
+
+#[test]
+fn resources_dir_comes_from_config() {
+  let t = TestHome::new();
+  let res = t.user_dir().join("res");
+  fs::create_dir_all(&res).unwrap();
+  std::env::set_var("ARKDESK_RESOURCES", t.user_dir().join("missing"));
+  flags(&["--resources", res.to_str().unwrap(), "install"]);
+  assert_eq!(source(Key::Resources), (Some(res.display().to_string()), Source::Cli));
+  assert_eq!(Resources::discover().dir(), Some(&res));
+}
+
+#[test]
+fn integrity_key_must_parse() {
+  let _t = TestHome::new();
+  std::env::set_var("ARKDESK_INTEGRITY_PUBKEY", "not-hex");
+  let e = config::resolve(Key::IntegrityPubkey);
+  assert_ne!(e.source, Source::Env);
+  assert_eq!(e.ignored, [r#"ARKDESK_INTEGRITY_PUBKEY="not-hex""#]);
+
+  let key = "11".repeat(32);
+  std::env::set_var("ARKDESK_INTEGRITY_PUBKEY", &key);
+  if cfg!(debug_assertions) {
+    assert_eq!(source(Key::IntegrityPubkey), (Some(key), Source::Env));
+    assert!(TrustPolicy::for_build().key.is_some());
+  }
+}
+
+#[test]
+fn effective_lists_every_key() {
+  let t = TestHome::new();
+  std::env::set_var("ARK_RUN_DIR", t.user_dir().join("run"));
+  let eff = config::effective();
+  assert_eq!(eff.config_file, t.rel("config.json"));
+  assert_eq!(eff.entries.len(), 9);
+  assert_eq!(eff.entries.iter().find(|e| e.key == Key::RunDir).unwrap().source, Source::Env);
+  for key in [Key::Resources, Key::IntegrityPubkey] {
+    assert!(eff.entries.iter().any(|e| e.key == key), "{key:?}");
+  }
+}
@@ -0,0 +1,105 @@
+// tests/disk.rs
+//! Disk usage breakdown and the low-disk watchdog.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::disk::{self, DiskLevel, DiskWatch, EVT_DISK_LOW, EVT_DISK_OK};
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::node_control::NodeProc;
+use arkdesk_lib::profiles::LaunchSpec;
+use arkdesk_lib::settings::{load_settings, save_settings, Settings};
+use common::{RecordingSink, TestHome};
+use std::{fs, sync::Arc};
+
+fn thresholds(warn: u32, stop: u32) {
+  save_settings(Settings { disk_warn_mib: warn, disk_stop_mib: stop, ..load_settings() }, None).unwrap();
+}
+
+#[test]
+fn usage_is_split_per_area() {
+  let t = TestHome::new();
+  t.put("bin/arkd", 100);
+  t.put("versions/1.0.0/arkd", 200);
+  t.put("data/devnet/blocks/000001.blk", 4000);
+  t.put("logs/arkd.out", 30);
+  t.put("snapshots/1-h5/data.tar.zst", 500);
+  t.put("var/devnet/run/node.json", 10);
+  fs::write(t.path("config.json"), "{}").unwrap();
+
+  let u = disk::usage(&t.home);
+  let sizes: Vec<(&str, u64)> = u.areas.iter().map(|a| (a.area, a.bytes)).collect();
+  assert_eq!(sizes, [("bin", 100), ("versions", 200), ("data", 4000), ("logs", 30), ("pyenv", 0), ("snapshots", 500)]);
+  assert_eq!(u.other_bytes, 10 + 2);
+  assert_eq!(u.total_bytes, 4830 + 10 + 2);
+  assert_eq!(u.volume, t.rel("data"));
+  assert!(u.free_bytes.is_some());
+  assert_eq!(u.level, DiskLevel::Ok);
+}
+
+#[test]
+fn volume_falls_back_to_the_nearest_existing_ancestor() {
+  let t = TestHome::new();
+  assert_eq!(disk::data_volume(&t.user_dir().join("nowhere/.arknet")), t.user_dir());
+}
+
+#[tokio::test]
+async fn low_alerts_once_and_still_allows_a_start() {
+  let t = TestHome::new();
+  let sink = RecordingSink::default();
+  let mut watch = DiskWatch::default();
+  assert_eq!(watch.tick(&sink, None).await, Some(DiskLevel::Ok));
+  assert!(sink.take().is_empty());
+
+  // warn threshold above any real free space: one alert, not one per tick
+  thresholds(u32::MAX, 0);
+  assert_eq!(watch.tick(&sink, None).await, Some(DiskLevel::Low));
+  assert_eq!(watch.tick(&sink, None).await, Some(DiskLevel::Low));
+  let low = sink.take_named(EVT_DISK_LOW);
+  assert_eq!(low.len(), 1);
+  assert_eq!(low[0]["level"], "low");
+  assert!(low[0]["stoppedNode"].is_null());
+  assert!(disk::start_guard(&t.home).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn critical_stops_the_node_and_refuses_a_start() {
+  let t = TestHome::new();
+  t.save_free_ports();
+  t.fake_arkd();
+  let proc = NodeProc::default();
+  let node_sink: Sink = Arc::new(RecordingSink::default());
+  proc.start(node_sink.clone(), None, LaunchSpec::adhoc(vec![]), false).await.unwrap();
+  let pid = proc.pid().unwrap();
+
+  let sink = RecordingSink::default();
+  let mut watch = DiskWatch::default();
+  thresholds(u32::MAX, u32::MAX);
+  assert_eq!(watch.tick(&sink, Some(&proc)).await, Some(DiskLevel::Critical));
+  let crit = sink.take_named(EVT_DISK_LOW);
+  assert_eq!(crit.len(), 1);
+  assert_eq!(crit[0]["level"], "critical");
+  assert_eq!(crit[0]["stoppedNode"], pid);
+  assert!(proc.pid().is_none());
+  let err = proc.start(node_sink, None, LaunchSpec::adhoc(vec![]), false).await.unwrap_err();
+  assert!(err.contains("diskStopMib"), "{err}");
+
+  // nothing left to stop: no repeat alert
+  watch.tick(&sink, Some(&proc)).await;
+  assert!(sink.take_named(EVT_DISK_LOW).is_empty());
+}
+
+#[tokio::test]
+async fn zero_thresholds_never_trip() {
+  let t = TestHome::new();
+  let sink = RecordingSink::default();
+  let mut watch = DiskWatch::default();
+  thresholds(u32::MAX, 0);
+  watch.tick(&sink, None).await;
+  sink.take();
+
+  thresholds(0, 0);
+  assert_eq!(watch.tick(&sink, None).await, Some(DiskLevel::Ok));
+  assert_eq!(sink.take_named(EVT_DISK_OK).len(), 1);
+  assert!(disk::start_guard(&t.home).is_ok());
+}
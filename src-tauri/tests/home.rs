@@ -0,0 +1,109 @@
+// tests/home.rs
+//! Choosing and moving the Arknet home.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::cleanup::{self, UninstallPlan};
+use arkdesk_lib::config::Source;
+use arkdesk_lib::home;
+use arkdesk_lib::install::staging_root;
+use arkdesk_lib::manifest::default_manifest_path;
+use arkdesk_lib::settings::{ark_home, load_settings, save_settings, Settings};
+use common::TestHome;
+use std::{fs, os::unix::fs::symlink, path::PathBuf};
+
+/// `~/bigdisk/arknet`, not created yet.
+fn bigdisk(t: &TestHome) -> PathBuf { t.user_dir().join("bigdisk/arknet") }
+
+fn save_rpc_port(port: u16) {
+  save_settings(Settings { rpc_port: port, p2p_port: port + 1, ..Settings::default() }, None).unwrap();
+}
+
+#[tokio::test]
+async fn relative_and_nested_targets_are_refused() {
+  let t = TestHome::new();
+  t.put("config.json", 2);
+  assert!(home::change("rel/dir".as_ref(), true, None, None).await.unwrap_err().contains("absolute"));
+  assert!(home::change(&t.path("inner"), true, None, None).await.unwrap_err().contains("contain"));
+}
+
+#[tokio::test]
+async fn a_non_empty_target_is_refused() {
+  let t = TestHome::new();
+  t.put("config.json", 2);
+  let new = bigdisk(&t);
+  common::put(&new.join("stray"), 1);
+  assert!(home::change(&new, true, None, None).await.unwrap_err().contains("not empty"));
+  assert!(t.path("config.json").is_file());
+}
+
+#[tokio::test]
+async fn content_links_and_the_manifest_move_and_the_choice_is_saved() {
+  let t = TestHome::new();
+  assert_eq!(home::info().source, Source::Default);
+  save_rpc_port(9400);
+  fs::create_dir_all(t.path("pyenv/bin")).unwrap();
+  symlink("/usr/bin/python3", t.path("pyenv/bin/python")).unwrap();
+  t.put("var/devnet/run/node.json", 2);
+  let new = bigdisk(&t);
+
+  let r = home::change(&new, true, None, None).await.unwrap();
+  assert!(r.migrated && r.bytes > 0 && r.leftovers.is_empty(), "{r:?}");
+  assert!(!t.home.exists());
+  assert_eq!(fs::read_link(new.join("pyenv/bin/python")).unwrap(), std::path::Path::new("/usr/bin/python3"));
+  assert_eq!((ark_home(), home::info().source), (new.clone(), Source::App));
+  assert_eq!(load_settings().rpc_port, 9400);
+  assert_eq!(default_manifest_path(), new.join("var/devnet/run/node.json"));
+}
+
+#[tokio::test]
+async fn a_pending_install_moves_with_the_home() {
+  let t = TestHome::new();
+  t.put("config.json", 2);
+  fs::create_dir_all(staging_root(&t.home)).unwrap();
+  let new = bigdisk(&t);
+  home::change(&new, true, None, None).await.unwrap();
+  assert!(!staging_root(&t.home).exists() && staging_root(&new).is_dir());
+}
+
+#[tokio::test]
+async fn uninstall_lists_the_saved_choice() {
+  let t = TestHome::new();
+  home::change(&bigdisk(&t), false, None, None).await.unwrap();
+  let saved = home::saved_home_path().unwrap();
+  let plan = UninstallPlan { dry_run: true, ..UninstallPlan::default() };
+  let report = cleanup::run_uninstall(&plan, None, None).await.unwrap();
+  assert!(report.items.iter().any(|i| i.path == saved.display().to_string()), "{:?}", report.items);
+}
+
+#[tokio::test]
+async fn env_override_blocks_a_change() {
+  let t = TestHome::new();
+  std::env::set_var("ARK_HOME", t.user_dir().join("pinned"));
+  assert!(home::change(&bigdisk(&t), true, None, None).await.unwrap_err().contains("ARK_HOME"));
+}
+
+#[tokio::test]
+async fn moving_back_to_the_default_forgets_the_choice() {
+  let t = TestHome::new();
+  save_rpc_port(9400);
+  home::change(&bigdisk(&t), true, None, None).await.unwrap();
+  let saved = home::saved_home_path().unwrap();
+
+  let r = home::change(&t.home, true, None, None).await.unwrap();
+  assert!(r.migrated && !saved.exists());
+  assert_eq!((ark_home(), home::info().source), (t.home.clone(), Source::Default));
+  assert_eq!(load_settings().rpc_port, 9400);
+}
+
+#[tokio::test]
+async fn without_migrate_only_the_choice_changes() {
+  let t = TestHome::new();
+  save_rpc_port(9400);
+  let new = bigdisk(&t);
+  let r = home::change(&new, false, None, None).await.unwrap();
+  assert!(!r.migrated && new.is_dir() && t.path("config.json").is_file());
+  assert_eq!(ark_home(), new);
+  assert_eq!(load_settings().rpc_port, Settings::default().rpc_port);
+}
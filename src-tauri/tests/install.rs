@@ -0,0 +1,206 @@
+// tests/install.rs
+//! Staged installer end to end against a fake `python` (enough of `-m venv` and
+//! `-m pip` to be driven by flags). One test: it owns HOME and the ARKDESK_* env.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::events::NullSink;
+use arkdesk_lib::install::{self, Cancel, InstallCtl, CANCELLED, EVT_INSTALL_PROGRESS};
+use arkdesk_lib::resources::Resources;
+use arkdesk_lib::subprocess::{is_transcript_log, EVT_PROC_LOG};
+use arkdesk_lib::versions;
+use common::RecordingSink;
+use serde_json::Value;
+use std::{fs, os::unix::fs::PermissionsExt, path::Path, time::{Duration, Instant}};
+
+const FAKE_PY: &str = r#"#!/bin/sh
+echo "$*" >> "$FAKE_PY_LOG"
+case "$1 $2" in
+  "-c import json"*) echo '{"python":"3.11.4","packages":[["pip","24.0"],["arknet_py","0.1.0"]]}' ;;
+  "-m venv") mkdir -p "$3/bin" && cp "$0" "$3/bin/python" && exit 0 ;;
+  "-m pip")
+    case "$*" in *--no-index*)
+      [ -n "$FAKE_PIP_SLEEP" ] && sleep "$FAKE_PIP_SLEEP"
+      [ -f "$FAKE_PIP_FAIL" ] && { echo "Looking in links: wheels"; echo "ERROR: No matching distribution found for arknet-py" >&2; exit 1; } ;;
+    esac ;;
+esac
+exit 0
+"#;
+
+fn script(p: &Path, body: &str) {
+  fs::write(p, body).unwrap();
+  fs::set_permissions(p, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn write_bins(res: &Path, ver: &str) {
+  let bin = res.join("bin").join(if cfg!(target_os = "macos") { "darwin" } else { "linux" });
+  fs::create_dir_all(&bin).unwrap();
+  for n in ["arkd", "cli", "call", "cc", "tests"] {
+    script(&bin.join(n), &format!("#!/bin/sh\necho \"{n} v{ver}\"\n"));
+  }
+}
+
+fn of_kind<'a>(evts: &'a [Value], kind: &str) -> Vec<&'a Value> {
+  evts.iter().filter(|e| e["kind"] == kind).collect()
+}
+
+fn journal_done(stage: &Path) -> Vec<String> {
+  let j: Value = serde_json::from_slice(&fs::read(stage.join("journal.json")).unwrap()).unwrap();
+  j["done"].as_array().unwrap().iter().map(|v| v.as_str().unwrap().to_string()).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn staged_install_cancels_resumes_and_commits() {
+  let user = tempfile::tempdir().unwrap();
+  let res_dir = tempfile::tempdir().unwrap();
+  let tools = tempfile::tempdir().unwrap();
+  let py_log = tools.path().join("py.log");
+  let fail_flag = tools.path().join("fail");
+  script(&tools.path().join("python"), FAKE_PY);
+
+  std::env::set_var("HOME", user.path());
+  std::env::remove_var("ARK_HOME");
+  std::env::set_var("ARKDESK_PY", tools.path().join("python"));
+  std::env::set_var("FAKE_PY_LOG", &py_log);
+  std::env::set_var("FAKE_PIP_FAIL", &fail_flag);
+
+  write_bins(res_dir.path(), "0.5.0");
+  fs::create_dir_all(res_dir.path().join("wheels")).unwrap();
+  fs::write(res_dir.path().join("wheels/arknet_py-0.1.0-py3-none-any.whl"), b"PK").unwrap();
+  let res = Resources::new(Some(res_dir.path().to_path_buf()));
+
+  let home = user.path().join(".arknet");
+  let stage = install::staging_root(&home);
+  assert_eq!(stage, user.path().join(".arknet.staging"));
+
+  // 1. cancelled while pip runs: the child is killed and nothing reaches the home
+  std::env::set_var("FAKE_PIP_SLEEP", "30");
+  let cancel = Cancel::default();
+  let c = cancel.clone();
+  tokio::spawn(async move { tokio::time::sleep(Duration::from_millis(500)).await; c.cancel(); });
+  let t0 = Instant::now();
+  let sink = RecordingSink::default();
+  assert_eq!(install::install(&res, &sink, &cancel).await.unwrap_err(), CANCELLED);
+  assert!(t0.elapsed() < Duration::from_secs(10));
+  let evts = sink.take_named(EVT_INSTALL_PROGRESS);
+  let last = evts.last().unwrap();
+  assert_eq!((last["kind"].as_str(), last["done"].as_bool(), last["ok"].as_bool()), (Some("summary"), Some(true), Some(false)));
+  assert_eq!(last["error"], CANCELLED);
+  assert_eq!(of_kind(&evts, "fail")[0]["id"], "arkPy");
+  std::env::remove_var("FAKE_PIP_SLEEP");
+  assert_eq!(journal_done(&stage), ["bins", "config", "venv", "pip"]);
+  assert!(versions::active_dir(&home).is_none());
+  assert!(!home.join("pyenv").exists() && !home.join("config.json").exists());
+
+  // 2. pip fails: still staged, still resumable
+  fs::write(&fail_flag, "").unwrap();
+  let sink = RecordingSink::default();
+  let err = install::install(&res, &sink, &Cancel::default()).await.unwrap_err();
+  assert!(err.contains("ArkPy wheel install failed"), "{err}");
+  // the error carries the tail of pip's output, which was also streamed live
+  assert!(err.contains("\nLooking in links: wheels") && err.contains("\n! ERROR: No matching distribution"), "{err}");
+  let live = sink.take_named(EVT_PROC_LOG);
+  assert_eq!(live.len(), 2);
+  assert!(live.iter().all(|l| l["id"] == "install.arkPy"));
+  assert!(live.iter().any(|l| l["stream"] == "stderr" && l["line"] == "ERROR: No matching distribution found for arknet-py"));
+  let evts = sink.take_named(EVT_INSTALL_PROGRESS);
+  let fail = of_kind(&evts, "fail");
+  assert_eq!(fail.len(), 1);
+  assert_eq!(fail[0]["id"], "arkPy");
+  let out: Vec<_> = fail[0]["output"].as_array().unwrap().iter().map(|l| l.as_str().unwrap()).collect();
+  assert!(out.contains(&"! ERROR: No matching distribution found for arknet-py"), "{out:?}");
+  // the whole transcript is kept under logs/, named in the event and the error
+  let log = fail[0]["log"].as_str().unwrap();
+  assert!(Path::new(log).starts_with(home.join("logs")), "{log}");
+  assert!(err.ends_with(&format!("full output: {log}")), "{err}");
+  // stdout and stderr are read separately, so only the set of lines is fixed
+  let mut logged: Vec<_> = fs::read_to_string(log).unwrap().lines().map(str::to_string).collect();
+  logged.sort();
+  assert_eq!(logged, ["! ERROR: No matching distribution found for arknet-py", "Looking in links: wheels"]);
+  let name = Path::new(log).file_name().unwrap().to_str().unwrap();
+  assert!(name.starts_with("install.arkPy-") && is_transcript_log(name), "{name}");
+  assert!(!is_transcript_log("arkd.log") && !is_transcript_log("arkd-2024.log"));
+  let summary = evts.last().unwrap();
+  assert_eq!((summary["done"].as_bool(), summary["ok"].as_bool()), (Some(true), Some(false)));
+  let states: Vec<_> = summary["steps"].as_array().unwrap().iter().map(|s| s["state"].as_str().unwrap()).collect();
+  assert_eq!(states, ["done", "skipped", "skipped", "skipped", "skipped", "failed", "pending"]);
+  assert_eq!(journal_done(&stage), ["bins", "config", "venv", "pip"]);
+
+  // 3. resumes at the wheel install, then commits everything
+  fs::remove_file(&fail_flag).unwrap();
+  fs::write(&py_log, "").unwrap();
+  let sink = RecordingSink::default();
+  install::install(&res, &sink, &Cancel::default()).await.unwrap();
+  let calls = fs::read_to_string(&py_log).unwrap();
+  assert!(!calls.contains("venv") && !calls.contains("setuptools"), "{calls}");
+  assert!(calls.contains("--no-index"), "{calls}");
+  assert!(!stage.exists());
+  assert_eq!(versions::pointer(&home).unwrap().version, "0.5.0");
+  assert!(home.join("pyenv/bin/python").is_file());
+  assert!(home.join("config.json").is_file() && home.join("data").is_dir());
+
+  // declared plan first, each step started once and closed once, progress only grows
+  let evts = sink.take_named(EVT_INSTALL_PROGRESS);
+  let plan = evts[0]["steps"].as_array().unwrap();
+  assert_eq!(evts[0]["kind"], "plan");
+  let ids: Vec<_> = plan.iter().map(|s| s["id"].as_str().unwrap()).collect();
+  assert_eq!(ids, ["prepare", "bins", "config", "venv", "pip", "arkPy", "commit"]);
+  assert_eq!(of_kind(&evts, "start").len(), of_kind(&evts, "finish").len());
+  assert_eq!(of_kind(&evts, "start").len() + of_kind(&evts, "skip").len(), ids.len());
+  let pcts: Vec<_> = evts.iter().map(|e| e["pct"].as_u64().unwrap()).collect();
+  assert!(pcts.windows(2).all(|w| w[0] <= w[1]), "{pcts:?}");
+  let summary = evts.last().unwrap();
+  assert_eq!((summary["kind"].as_str(), summary["ok"].as_bool(), summary["pct"].as_u64()), (Some("summary"), Some(true), Some(100)));
+  assert_eq!(summary["label"], "Installed arkd 0.5.0");
+  assert_eq!(evts.iter().filter(|e| e["done"] == true).count(), 1);
+
+  // 4. a new payload discards nothing in the home; the working venv is kept
+  write_bins(res_dir.path(), "0.6.0");
+  fs::write(&py_log, "").unwrap();
+  install::install(&res, &NullSink, &Cancel::default()).await.unwrap();
+  let calls = fs::read_to_string(&py_log).unwrap();
+  assert!(calls.starts_with("-c ") && !calls.contains("-m "), "{calls}");
+  let ptr = versions::pointer(&home).unwrap();
+  assert_eq!((ptr.version.as_str(), ptr.previous.as_deref()), ("0.6.0", Some("0.5.0")));
+
+  // a lock the venv doesn't satisfy: ArkPy is upgraded in a staged copy of the venv,
+  // so a failed upgrade leaves the live one alone
+  fs::create_dir_all(res_dir.path().join("bootstrap")).unwrap();
+  fs::write(res_dir.path().join("bootstrap/requirements.lock.txt"), "arknet-py==0.2.0\n").unwrap();
+  fs::write(home.join("pyenv/marker"), "live").unwrap();
+  fs::write(&fail_flag, "").unwrap();
+  install::install(&res, &NullSink, &Cancel::default()).await.unwrap_err();
+  assert_eq!(fs::read_to_string(stage.join("pyenv/marker")).unwrap(), "live");
+  assert_eq!(journal_done(&stage), ["venv", "pip", "bins", "config"]);
+  fs::write(stage.join("pyenv/marker"), "upgraded").unwrap();
+  assert_eq!(fs::read_to_string(home.join("pyenv/marker")).unwrap(), "live");
+  fs::remove_file(&fail_flag).unwrap();
+  fs::write(&py_log, "").unwrap();
+  let sink = RecordingSink::default();
+  install::install(&res, &sink, &Cancel::default()).await.unwrap();
+  let calls = fs::read_to_string(&py_log).unwrap();
+  assert!(!calls.contains("-m venv") && calls.contains("--no-index") && calls.contains("requirements.lock.txt"), "{calls}");
+  let evts = sink.take_named(EVT_INSTALL_PROGRESS);
+  let skipped: Vec<_> = of_kind(&evts, "skip").iter().map(|e| e["id"].as_str().unwrap()).collect();
+  assert_eq!(skipped, ["bins", "config", "venv", "pip"]);
+  assert!(of_kind(&evts, "finish").iter().any(|e| e["id"] == "arkPy"));
+  assert!(home.join("pyenv/bin/python").is_file());
+  assert_eq!(fs::read_to_string(home.join("pyenv/marker")).unwrap(), "upgraded");
+  fs::remove_dir_all(res_dir.path().join("bootstrap")).unwrap();
+
+  // 5. a stale journal from another payload is thrown away, not resumed
+  fs::create_dir_all(&stage).unwrap();
+  fs::write(stage.join("journal.json"), r#"{"fingerprint":"other","done":["bins","config"]}"#).unwrap();
+  fs::write(stage.join("leftover"), "x").unwrap();
+  install::install(&res, &NullSink, &Cancel::default()).await.unwrap();
+  assert!(!stage.exists());
+
+  // one install at a time
+  let ctl = InstallCtl::default();
+  let running = ctl.begin().unwrap();
+  assert!(ctl.begin().is_err());
+  assert!(ctl.cancel() && running.is_cancelled());
+  ctl.finish();
+  assert!(!ctl.cancel());
+}
@@ -0,0 +1,170 @@
+// tests/integrity.rs
+use arkdesk_lib::install;
+use arkdesk_lib::integrity::{self, Problem, TrustPolicy, MANIFEST_FILE, SIG_FILE};
+use arkdesk_lib::resources::Resources;
+use ed25519_dalek::SigningKey;
+use std::{fs, path::PathBuf};
+
+const BINS: &[&str] = &["arkd", "cli", "call", "cc", "tests"];
+
+fn key(seed: u8) -> SigningKey { SigningKey::from_bytes(&[seed; 32]) }
+
+fn strict(k: &SigningKey) -> TrustPolicy {
+  TrustPolicy { key: Some(k.verifying_key()), allow_unsigned: false }
+}
+
+fn plat() -> &'static str {
+  if cfg!(windows) { "windows" } else if cfg!(target_os = "macos") { "darwin" } else { "linux" }
+}
+
+fn exe(n: &str) -> String { if cfg!(windows) { format!("{n}.exe") } else { n.to_string() } }
+
+/// resources/{bin/<plat>/*, wheels/arknet_py-*.whl}, signed with `key(1)`.
+fn payload() -> (tempfile::TempDir, Vec<PathBuf>) {
+  let dir = tempfile::tempdir().unwrap();
+  let bin = dir.path().join("bin").join(plat());
+  let wheels = dir.path().join("wheels");
+  fs::create_dir_all(&bin).unwrap();
+  fs::create_dir_all(&wheels).unwrap();
+  let mut files = Vec::new();
+  for n in BINS {
+    let p = bin.join(exe(n));
+    fs::write(&p, format!("#!/bin/sh\necho {n}\n")).unwrap();
+    files.push(p);
+  }
+  let whl = wheels.join("arknet_py-0.1.0-py3-none-any.whl");
+  fs::write(&whl, b"PK\x03\x04 wheel").unwrap();
+  files.push(whl);
+  integrity::sign(dir.path(), &["bin", "wheels"], &key(1)).unwrap();
+  (dir, files)
+}
+
+fn problems(r: &integrity::IntegrityReport) -> Vec<(String, Problem)> {
+  r.issues.iter().map(|i| (i.path.clone(), i.problem.clone())).collect()
+}
+
+#[test]
+fn signed_payload_verifies() {
+  let (dir, files) = payload();
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(1)));
+  assert!(r.ok && r.signature_ok, "{:?}", r.issues);
+  assert_eq!(r.checked, files.len());
+}
+
+#[test]
+fn tampered_file_is_modified() {
+  let (dir, files) = payload();
+  fs::write(&files[0], b"evil").unwrap();
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(1)));
+  assert!(!r.ok);
+  assert!(r.signature_ok);
+  assert_eq!(problems(&r), [(format!("bin/{}/{}", plat(), exe("arkd")), Problem::Modified)]);
+}
+
+#[test]
+fn added_file_is_unlisted_and_removed_is_missing() {
+  let (dir, mut files) = payload();
+  let extra = dir.path().join("bin").join(plat()).join("libevil.so");
+  fs::write(&extra, b"x").unwrap();
+  let gone = files.remove(1);
+  fs::remove_file(&gone).unwrap();
+  files.push(extra);
+
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(1)));
+  let p = problems(&r);
+  assert!(p.contains(&(format!("bin/{}/libevil.so", plat()), Problem::Unlisted)), "{p:?}");
+  assert!(p.contains(&(format!("bin/{}/{}", plat(), exe("cli")), Problem::Missing)), "{p:?}");
+}
+
+#[test]
+fn edited_manifest_breaks_signature() {
+  let (dir, files) = payload();
+  // attacker swaps a binary and updates its hash
+  fs::write(&files[0], b"evil").unwrap();
+  let mut m: integrity::IntegrityManifest =
+    serde_json::from_slice(&fs::read(dir.path().join(MANIFEST_FILE)).unwrap()).unwrap();
+  let k = format!("bin/{}/{}", plat(), exe("arkd"));
+  m.files.insert(k, integrity::sha256_file(&files[0]).unwrap());
+  fs::write(dir.path().join(MANIFEST_FILE), serde_json::to_vec_pretty(&m).unwrap()).unwrap();
+
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(1)));
+  assert!(!r.ok && !r.signature_ok);
+  assert_eq!(problems(&r), [(SIG_FILE.to_string(), Problem::Untrusted)]);
+}
+
+#[test]
+fn wrong_key_or_missing_signature_is_untrusted() {
+  let (dir, files) = payload();
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(2)));
+  assert!(!r.ok && !r.signature_ok);
+
+  fs::remove_file(dir.path().join(SIG_FILE)).unwrap();
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(1)));
+  assert_eq!(problems(&r), [(SIG_FILE.to_string(), Problem::Untrusted)]);
+}
+
+#[test]
+fn missing_manifest_depends_on_policy() {
+  let (dir, files) = payload();
+  fs::remove_file(dir.path().join(MANIFEST_FILE)).unwrap();
+
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(1)));
+  assert_eq!(problems(&r), [(MANIFEST_FILE.to_string(), Problem::Untrusted)]);
+
+  let dev = TrustPolicy { key: None, allow_unsigned: true };
+  let r = integrity::verify(Some(dir.path()), &files, &dev);
+  assert!(r.ok && !r.signature_ok && !r.notes.is_empty());
+
+  let release_without_key = TrustPolicy { key: None, allow_unsigned: false };
+  assert!(!integrity::verify(Some(dir.path()), &files, &release_without_key).ok);
+}
+
+#[test]
+fn unsigned_dev_build_still_checks_hashes() {
+  let (dir, files) = payload();
+  fs::write(&files[2], b"corrupted in transit").unwrap();
+  let dev = TrustPolicy { key: None, allow_unsigned: true };
+  let r = integrity::verify(Some(dir.path()), &files, &dev);
+  assert_eq!(r.issues.len(), 1);
+  assert_eq!(r.issues[0].problem, Problem::Modified);
+}
+
+#[test]
+fn file_outside_root_is_unlisted() {
+  let (dir, mut files) = payload();
+  let other = tempfile::NamedTempFile::new().unwrap();
+  files.push(other.path().to_path_buf());
+  let r = integrity::verify(Some(dir.path()), &files, &strict(&key(1)));
+  assert_eq!(r.issues.len(), 1);
+  assert_eq!(r.issues[0].problem, Problem::Unlisted);
+}
+
+/// The installer's view: preflight reports per-file issues and refuses to install.
+/// Only test here that touches the environment.
+#[test]
+fn preflight_reports_tampering() {
+  let (dir, files) = payload();
+  std::env::set_var("ARKDESK_INTEGRITY_PUBKEY", hex::encode(key(1).verifying_key().to_bytes()));
+  let res = Resources::new(Some(dir.path().to_path_buf()));
+
+  let pf = install::preflight(&res).unwrap();
+  assert!(pf.integrity_ok && pf.signature_ok, "{:?}", pf.integrity_issues);
+
+  fs::write(&files[3], b"evil").unwrap();
+  let pf = install::preflight(&res).unwrap();
+  assert!(!pf.integrity_ok && !pf.ok);
+  assert_eq!(pf.integrity_issues.len(), 1);
+  assert_eq!(pf.integrity_issues[0].path, format!("bin/{}/{}", plat(), exe("cc")));
+  assert!(install::verify_payload(&res).describe().contains("expected"));
+}
+
+#[test]
+fn signing_key_file() {
+  let dir = tempfile::tempdir().unwrap();
+  let root = dir.path();
+  let keyfile = root.join("seed.hex");
+  fs::write(&keyfile, hex::encode([9u8; 32])).unwrap();
+  let k = integrity::read_signing_key(&keyfile).unwrap();
+  assert_eq!(k.to_bytes(), [9u8; 32]);
+  assert!(integrity::read_signing_key(&root.join("nope")).is_err());
+}
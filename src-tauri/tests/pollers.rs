@@ -0,0 +1,118 @@
+// tests/pollers.rs
+mod common;
+
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::pollers::{self, RpcPoller, Stamps, EVT_CAPS, EVT_HEALTH, EVT_MEMPOOL, EVT_STALE, EVT_STATUS, EVT_TIP};
+use arkdesk_lib::state::NodeBridge;
+use common::{mock_node, mock_node::hash_for, Fault, RecordingSink};
+use std::sync::{atomic::Ordering, Arc};
+use std::time::Duration;
+
+fn bridge_for(node: &common::MockNode) -> NodeBridge {
+  NodeBridge::new(node.manifest_path().to_path_buf())
+}
+
+#[tokio::test]
+async fn status_tick_emits_health_and_status() {
+  let (_dir, node) = mock_node().await;
+  let (sink, stamps) = (RecordingSink::default(), Stamps::default());
+
+  pollers::status_tick(&sink, &bridge_for(&node), &stamps).await;
+  assert_eq!(sink.names(), [EVT_HEALTH, EVT_STATUS]);
+  let status = sink.take_named(EVT_STATUS).remove(0);
+  assert_eq!(status["networkHeight"], 100);
+  assert_eq!(status["peers"], 2);
+
+  let stale = stamps.stale();
+  assert!(stale.admin_age_ms.is_some() && stale.status_age_ms.is_some());
+  assert_eq!((stale.tip_age_ms, stale.mempool_age_ms), (None, None));
+}
+
+#[tokio::test]
+async fn status_tick_skips_failed_calls() {
+  let (_dir, node) = mock_node().await;
+  node.fault("/v1/status", Fault::Status(500));
+  let (sink, stamps) = (RecordingSink::default(), Stamps::default());
+
+  pollers::status_tick(&sink, &bridge_for(&node), &stamps).await;
+  assert_eq!(sink.names(), [EVT_HEALTH]);
+  assert_eq!(stamps.status_ok_ms.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test]
+async fn status_tick_without_node_is_silent() {
+  let dir = tempfile::tempdir().unwrap();
+  let (sink, stamps) = (RecordingSink::default(), Stamps::default());
+  pollers::status_tick(&sink, &NodeBridge::new(dir.path().join("node.json")), &stamps).await;
+  assert!(sink.take().is_empty());
+}
+
+#[tokio::test]
+async fn rpc_poller_caps_once_tip_on_change() {
+  let (_dir, node) = mock_node().await;
+  let bridge = bridge_for(&node);
+  let (sink, stamps) = (RecordingSink::default(), Stamps::default());
+  let mut poller = RpcPoller::default();
+
+  poller.tick(&sink, &bridge, &stamps).await;
+  assert_eq!(sink.names(), [EVT_CAPS, EVT_TIP, EVT_MEMPOOL]);
+  sink.take();
+
+  // unchanged tip: mempool only, no second rpc.list
+  poller.tick(&sink, &bridge, &stamps).await;
+  assert_eq!(sink.names(), [EVT_MEMPOOL]);
+  assert_eq!(node.hits("rpc.list"), 1);
+  sink.take();
+
+  node.set_chain(|c| { c.height = 101; c.hash = hash_for(101); });
+  poller.tick(&sink, &bridge, &stamps).await;
+  let tips = sink.take_named(EVT_TIP);
+  assert_eq!(tips.len(), 1);
+  assert_eq!(tips[0]["height"], 101);
+  assert_eq!(tips[0]["block_id"], hash_for(101));
+}
+
+#[tokio::test]
+async fn rpc_poller_retries_caps_after_error() {
+  let (_dir, node) = mock_node().await;
+  let bridge = bridge_for(&node);
+  let (sink, stamps) = (RecordingSink::default(), Stamps::default());
+  let mut poller = RpcPoller::default();
+
+  node.fault("rpc.list", Fault::RpcError(-32603, "internal".into()));
+  node.fault("mempool.info", Fault::Status(500));
+  poller.tick(&sink, &bridge, &stamps).await;
+  assert_eq!(sink.names(), [EVT_TIP]);
+  assert_eq!(stamps.mem_ok_ms.load(Ordering::Relaxed), 0);
+  sink.take();
+
+  node.clear_faults();
+  poller.tick(&sink, &bridge, &stamps).await;
+  assert_eq!(sink.names(), [EVT_CAPS, EVT_MEMPOOL]);
+  assert_eq!(node.hits("rpc.list"), 2);
+}
+
+#[tokio::test]
+async fn loops_emit_on_their_own_and_report_staleness() {
+  let (_dir, node) = mock_node().await;
+  let bridge = bridge_for(&node);
+  let rec = Arc::new(RecordingSink::default());
+  let sink: Sink = rec.clone();
+  let stamps = Arc::new(Stamps::default());
+
+  let tasks = [
+    tokio::spawn(pollers::status_poller(sink.clone(), bridge.clone(), stamps.clone())),
+    tokio::spawn(pollers::rpc_poller(sink.clone(), bridge, stamps.clone())),
+    tokio::spawn(pollers::stale_emitter(sink, stamps)),
+  ];
+  tokio::time::sleep(Duration::from_millis(300)).await;
+  for t in &tasks { t.abort(); }
+
+  let names = rec.names();
+  for evt in [EVT_HEALTH, EVT_STATUS, EVT_CAPS, EVT_TIP, EVT_MEMPOOL, EVT_STALE] {
+    assert!(names.iter().any(|n| n == evt), "missing {evt} in {names:?}");
+  }
+  // staleness goes out immediately, before any poll has necessarily landed
+  let stale = rec.take_named(EVT_STALE).remove(0);
+  assert!(stale["now_ms"].as_u64().unwrap() > 0);
+}
@@ -0,0 +1,103 @@
+// tests/ports.rs
+//! Port preflight before arkd starts: which ports are probed, what gets saved,
+//! and what `stop_external` will stop on the rpc port.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::node_control::{stop_external, NodeProc};
+use arkdesk_lib::profiles::LaunchSpec;
+use arkdesk_lib::settings::{load_settings, save_settings, Settings};
+use arkdesk_lib::state::NodeBridge;
+use common::{free_port, RecordingSink, TestHome};
+use std::{fs, net::TcpListener, sync::Arc};
+
+fn sink() -> Sink { Arc::new(RecordingSink::default()) }
+
+fn hold(port: u16) -> TcpListener { TcpListener::bind(("127.0.0.1", port)).unwrap() }
+
+fn args(p2p: u16) -> Vec<String> { vec!["--p2p-port".into(), p2p.to_string()] }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conflict_on_a_port_from_the_args_is_detected() {
+  let t = TestHome::new();
+  t.save_free_ports();
+  t.fake_arkd();
+  let busy = free_port();
+  let _l = hold(busy);
+
+  let err = NodeProc::default().start(sink(), None, LaunchSpec::adhoc(args(busy)), false).await.unwrap_err();
+  assert!(err.contains(&format!("p2p port {busy}")), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reassigned_arg_port_is_not_saved() {
+  let t = TestHome::new();
+  t.save_free_ports();
+  t.fake_arkd();
+  let saved = load_settings();
+  let busy = free_port();
+  let _l = hold(busy);
+
+  let proc = NodeProc::default();
+  proc.start(sink(), None, LaunchSpec::adhoc(args(busy)), true).await.unwrap();
+  proc.stop(None).await.unwrap();
+  assert_eq!((load_settings().p2p_port, load_settings().rpc_port), (saved.p2p_port, saved.rpc_port));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reassigned_settings_port_is_saved() {
+  let t = TestHome::new();
+  t.fake_arkd();
+  let busy = free_port();
+  let _l = hold(busy);
+  save_settings(Settings { p2p_port: busy, rpc_port: free_port(), ..Settings::default() }, None).unwrap();
+
+  let proc = NodeProc::default();
+  proc.start(sink(), None, LaunchSpec::adhoc(vec![]), true).await.unwrap();
+  proc.stop(None).await.unwrap();
+  assert_ne!(load_settings().p2p_port, busy);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn broken_config_is_not_replaced_when_reassigning() {
+  let t = TestHome::new();
+  t.fake_arkd();
+  let cfg = t.path("config.json");
+  fs::create_dir_all(&t.home).unwrap();
+  fs::write(&cfg, "{ not json").unwrap();
+  // the defaults are what a broken config falls back to; make the p2p one busy
+  let _l = TcpListener::bind(("127.0.0.1", Settings::default().p2p_port));
+
+  let err = NodeProc::default().start(sink(), None, LaunchSpec::adhoc(vec![]), true).await.unwrap_err();
+  assert!(err.contains("cannot reassign ports"), "{err}");
+  assert_eq!(fs::read_to_string(&cfg).unwrap(), "{ not json");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restart_keeps_the_port_policy_of_the_last_start() {
+  let t = TestHome::new();
+  t.save_free_ports();
+  t.fake_arkd();
+  let proc = NodeProc::default();
+  proc.start(sink(), None, LaunchSpec::adhoc(vec![]), true).await.unwrap();
+  let busy = load_settings().p2p_port;
+  let _l = hold(busy);
+
+  proc.restart(sink(), None).await.unwrap();
+  proc.stop(None).await.unwrap();
+  assert_ne!(load_settings().p2p_port, busy);
+}
+
+#[tokio::test]
+async fn stop_external_leaves_a_foreign_process_on_the_rpc_port_alone() {
+  let t = TestHome::new();
+  let port = free_port();
+  let _l = hold(port);
+  save_settings(Settings { rpc_port: port, p2p_port: free_port(), ..Settings::default() }, None).unwrap();
+
+  let bridge = NodeBridge::new(t.path("var/devnet/run/node.json"));
+  let err = stop_external(&bridge, &t.home).await.unwrap_err();
+  assert!(err.contains(&format!("pid {}", std::process::id())) && err.contains("not an arkd"), "{err}");
+}
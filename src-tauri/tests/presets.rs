@@ -0,0 +1,134 @@
+// tests/presets.rs
+//! Settings bundles: export on one "machine" (HOME), import on another.
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use arkdesk_lib::endpoints::{self, Endpoint};
+use arkdesk_lib::presets::{self, REDACTED};
+use arkdesk_lib::profiles::{launch_profile_create, launch_profiles_list, LaunchProfile};
+use arkdesk_lib::reconfig::Effect;
+use arkdesk_lib::settings::{load_settings, save_settings, Settings};
+use common::TestHome;
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+
+fn endpoint(id: &str, headers: &[(&str, &str)]) -> Endpoint {
+  Endpoint {
+    id: id.into(),
+    label: id.into(),
+    base: format!("https://{id}.example"),
+    headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>(),
+    insecure: false,
+  }
+}
+
+fn set_endpoint(headers: &[(&str, &str)]) {
+  let mut eps = endpoints::load();
+  endpoints::upsert(&mut eps, endpoint("devnet", headers));
+  eps.active_id = Some("devnet".into());
+  endpoints::save(&eps).unwrap();
+}
+
+fn lead_profile() {
+  launch_profile_create(LaunchProfile {
+    name: "devnet".into(),
+    args: vec!["--network".into(), "devnet".into()],
+    env: Default::default(),
+    work_dir: None,
+    data_dir: None,
+    log_level: Some("debug".into()),
+    role: None,
+  })
+  .unwrap();
+}
+
+fn lead_endpoint() { set_endpoint(&[("Authorization", "Bearer s3cret"), ("X-Team", "core")]); }
+
+/// Configure a second machine (`<tmp>/lead` as HOME) with `setup`, export its
+/// bundle and switch back to the test's own HOME, the newcomer.
+fn lead_bundle(t: &TestHome, secrets: bool, setup: impl FnOnce()) -> PathBuf {
+  let lead = t.user_dir().join("lead");
+  let file = lead.join("out/devnet.json");
+  std::env::set_var("HOME", &lead);
+  setup();
+  presets::export(&file, secrets).unwrap();
+  std::env::set_var("HOME", t.user_dir());
+  file
+}
+
+fn write<'a>(file: &'a Path, json: &str) -> &'a Path {
+  fs::write(file, json).unwrap();
+  file
+}
+
+#[test]
+fn export_redacts_headers_by_default() {
+  let t = TestHome::new();
+  let file = lead_bundle(&t, false, || { lead_profile(); lead_endpoint(); });
+  let text = fs::read_to_string(&file).unwrap();
+  assert!(!text.contains("s3cret") && text.contains(REDACTED), "{text}");
+  assert_eq!(presets::read(&file).unwrap().launch_profiles.items["devnet"].args, ["--network", "devnet"]);
+}
+
+#[test]
+fn import_brings_settings_profiles_and_endpoints() {
+  let t = TestHome::new();
+  let file = lead_bundle(&t, false, || {
+    save_settings(Settings { rpc_port: 9645, p2p_port: 9646, keep_versions: 5, ..Settings::default() }, None).unwrap();
+    lead_profile();
+    set_endpoint(&[]);
+  });
+  let r = presets::import(&file).unwrap();
+  assert_eq!(r.changes.iter().map(|c| (c.field.as_str(), c.effect)).collect::<Vec<_>>(), [
+    ("keepVersions", Effect::Live),
+    ("p2pPort", Effect::NodeRestart),
+    ("rpcPort", Effect::NodeRestart),
+  ]);
+  assert!(r.diagnostics.is_empty(), "{:?}", r.diagnostics);
+  assert_eq!((r.profiles.as_slice(), r.endpoints.as_slice()), (&["devnet".to_string()][..], &["devnet".to_string()][..]));
+  assert_eq!((load_settings().rpc_port, load_settings().keep_versions), (9645, 5));
+  assert!(launch_profiles_list().unwrap().items.contains_key("devnet"));
+  assert_eq!(endpoints::load().active_id.as_deref(), Some("devnet"));
+}
+
+#[test]
+fn redacted_headers_are_dropped_and_reported() {
+  let t = TestHome::new();
+  let file = lead_bundle(&t, false, lead_endpoint);
+  let r = presets::import(&file).unwrap();
+  assert_eq!(r.missing_secrets, ["devnet: Authorization", "devnet: X-Team"]);
+  assert!(endpoints::load().items["devnet"].headers.is_empty());
+}
+
+#[test]
+fn reimport_keeps_headers_set_locally() {
+  let t = TestHome::new();
+  let file = lead_bundle(&t, false, lead_endpoint);
+  presets::import(&file).unwrap();
+  set_endpoint(&[("Authorization", "Bearer mine")]);
+
+  let r = presets::import(&file).unwrap();
+  assert!(r.changes.is_empty());
+  assert_eq!(r.missing_secrets, ["devnet: X-Team"]);
+  assert_eq!(endpoints::load().items["devnet"].headers["Authorization"], "Bearer mine");
+}
+
+#[test]
+fn secrets_travel_when_asked_for() {
+  let t = TestHome::new();
+  let file = lead_bundle(&t, true, lead_endpoint);
+  assert!(presets::import(&file).unwrap().missing_secrets.is_empty());
+  assert_eq!(endpoints::load().items["devnet"].headers["X-Team"], "core");
+}
+
+#[test]
+fn foreign_or_invalid_bundles_change_nothing() {
+  let t = TestHome::new();
+  let before = load_settings().rpc_port;
+  let file = t.user_dir().join("bundle.json");
+  let foreign = write(&file, r#"{"format":"something-else","version":1,"settings":{}}"#);
+  assert!(presets::import(foreign).unwrap_err().contains("not an ArkDesk settings bundle"));
+  let invalid = write(&file, r#"{"format":"arkdesk-settings","version":1,"settings":{"rpcPort":"x"}}"#);
+  assert!(presets::import(invalid).unwrap_err().starts_with("settings:"));
+  assert_eq!(load_settings().rpc_port, before);
+}
@@ -0,0 +1,44 @@
+// tests/profiles.rs
+//! Launch profiles and which one a plain start reuses.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::node_control::{launch_for, NodeProc};
+use arkdesk_lib::profiles::{launch_profile_create, LaunchProfile, LaunchSpec};
+use common::{RecordingSink, TestHome};
+use std::sync::Arc;
+
+fn profile(name: &str) -> LaunchProfile {
+  LaunchProfile {
+    name: name.into(),
+    args: vec!["--network".into(), "devnet".into()],
+    env: Default::default(),
+    work_dir: None,
+    data_dir: None,
+    log_level: None,
+    role: None,
+  }
+}
+
+async fn run(launch: LaunchSpec) {
+  let proc = NodeProc::default();
+  let sink: Sink = Arc::new(RecordingSink::default());
+  proc.start(sink, None, launch, false).await.unwrap();
+  proc.stop(None).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn adhoc_start_keeps_the_last_used_profile() {
+  let t = TestHome::new();
+  t.save_free_ports();
+  t.fake_arkd();
+  launch_profile_create(profile("devnet")).unwrap();
+
+  run(launch_for(Some("devnet".into()), None).unwrap()).await;
+  assert_eq!(launch_for(None, None).unwrap().profile.as_deref(), Some("devnet"));
+
+  run(launch_for(None, Some(vec!["--verbose".into()])).unwrap()).await;
+  assert_eq!(launch_for(None, None).unwrap().profile.as_deref(), Some("devnet"));
+}
@@ -0,0 +1,129 @@
+// tests/pyenv.rs
+//! Managed ArkPy venv against a fake `python` whose "installed packages" live in
+//! a state file next to it: pip installs write it, the inspect probe prints it.
+//! One test: it owns HOME and the ARKDESK_* env.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::install::Cancel;
+use arkdesk_lib::miner;
+use arkdesk_lib::pyenv::{self, Mismatch};
+use arkdesk_lib::resources::Resources;
+use arkdesk_lib::subprocess::EVT_PROC_LOG;
+use common::RecordingSink;
+use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+
+const FAKE_PY: &str = r#"#!/bin/sh
+state="$(dirname "$0")/pkgs.json"
+case "$1 $2" in
+  "-c import json"*)
+    [ -f "$FAKE_PY_BROKEN" ] && { echo "Fatal Python error: init_fs_encoding" >&2; exit 1; }
+    if [ -f "$state" ]; then cat "$state"; else echo '{"python":"3.11.4","packages":[["pip","23.0"]]}'; fi ;;
+  "-m venv") mkdir -p "$3/bin" && cp "$0" "$3/bin/python" ;;
+  "-m pip")
+    case "$*" in *--no-index*)
+      [ -f "$FAKE_PIP_FAIL" ] && { echo "ERROR: No matching distribution found for arknet-py" >&2; exit 1; }
+      echo "Successfully installed arknet-py"
+      echo "{\"python\":\"3.11.4\",\"packages\":[[\"pip\",\"24.0\"],[\"arknet_py\",\"$FAKE_ARKPY\"],[\"numpy\",\"1.26.4\"]]}" > "$state" ;;
+    esac ;;
+esac
+exit 0
+"#;
+
+fn script(p: &Path, body: &str) {
+  fs::create_dir_all(p.parent().unwrap()).unwrap();
+  fs::write(p, body).unwrap();
+  fs::set_permissions(p, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn lock_file_pins_ignore_options_markers_and_extras() {
+  let pins = pyenv::parse_lock(
+    "# generated\n--find-links wheels\narknet_py==0.3.0 \\\n    --hash=sha256:abc\nNumPy[extra]==1.26.4 ; python_version >= \"3.9\"\nrequests>=2\n",
+  );
+  assert_eq!(pins, [("arknet-py".to_string(), "0.3.0".to_string()), ("numpy".into(), "1.26.4".into())]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn managed_venv_is_inspected_verified_upgraded_and_repaired() {
+  let user = tempfile::tempdir().unwrap();
+  let res_dir = tempfile::tempdir().unwrap();
+  let tools = tempfile::tempdir().unwrap();
+  let (broken, fail) = (tools.path().join("broken"), tools.path().join("fail"));
+  script(&tools.path().join("python"), FAKE_PY);
+
+  std::env::set_var("HOME", user.path());
+  std::env::remove_var("ARK_HOME");
+  std::env::set_var("ARKDESK_PY", tools.path().join("python"));
+  std::env::set_var("FAKE_PY_BROKEN", &broken);
+  std::env::set_var("FAKE_PIP_FAIL", &fail);
+  std::env::set_var("FAKE_ARKPY", "0.2.0");
+
+  fs::create_dir_all(res_dir.path().join("wheels")).unwrap();
+  fs::write(res_dir.path().join("wheels/arknet_py-0.3.0-py3-none-any.whl"), b"PK").unwrap();
+  let res = Resources::new(Some(res_dir.path().to_path_buf()));
+  let home = user.path().join(".arknet");
+  let venv = pyenv::venv_dir(&home);
+  let cancel = Cancel::default();
+
+  // nothing installed yet: reported, not an error; upgrade needs a venv
+  let info = pyenv::inspect(&home);
+  assert!(!info.exists && !info.ok);
+  assert!(!miner::probe_host(&res).python_ok);
+  assert_eq!(miner::locate_python().unwrap(), None);
+  let err = pyenv::upgrade(&res, &home, &RecordingSink::default(), &cancel).await.unwrap_err();
+  assert!(err.contains("repair"), "{err}");
+
+  // repair builds the venv, streaming pip output
+  let sink = RecordingSink::default();
+  let info = pyenv::repair(&res, &home, &sink, &cancel).await.unwrap();
+  assert!(info.ok, "{info:?}");
+  assert_eq!((info.python_version.as_deref(), info.arkpy_version.as_deref()), (Some("3.11.4"), Some("0.2.0")));
+  assert_eq!(info.pip_version.as_deref(), Some("24.0"));
+  assert!(sink.take_named(EVT_PROC_LOG).iter().any(|e| e["id"] == "pyenv.repair" && e["line"] == "Successfully installed arknet-py"));
+  assert!(!home.join("pyenv.new").exists());
+
+  // host probe reports the venv, not the system interpreter
+  let probe = miner::probe_host(&res);
+  assert!(probe.python_ok && probe.pip_ok && probe.ark_py_ok);
+  assert_eq!(probe.ark_py_version.as_deref(), Some("0.2.0"));
+  assert_eq!(miner::locate_python().unwrap().map(Into::into), Some(venv.join("bin/python")));
+
+  // without a lock file only arknet-py is required; with one every pin must match
+  assert!(pyenv::verify(&res, &home).ok);
+  fs::create_dir_all(res_dir.path().join("bootstrap")).unwrap();
+  fs::write(res_dir.path().join("bootstrap/requirements.lock.txt"), "arknet-py==0.3.0\nnumpy==1.26.4\nrich==13.7.0\n").unwrap();
+  let v = pyenv::verify(&res, &home);
+  assert!(!v.ok && v.lock.is_some());
+  assert_eq!(v.mismatches, [
+    Mismatch { name: "arknet-py".into(), want: Some("0.3.0".into()), have: Some("0.2.0".into()) },
+    Mismatch { name: "rich".into(), want: Some("13.7.0".into()), have: None },
+  ]);
+
+  // upgrade reinstalls in place from the wheels
+  std::env::set_var("FAKE_ARKPY", "0.3.0");
+  let info = pyenv::upgrade(&res, &home, &RecordingSink::default(), &cancel).await.unwrap();
+  assert_eq!(info.arkpy_version.as_deref(), Some("0.3.0"));
+  let v = pyenv::verify(&res, &home);
+  assert_eq!(v.mismatches.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["rich"]);
+
+  // a broken interpreter is reported; a failed repair keeps the old venv
+  fs::write(&broken, "").unwrap();
+  let info = pyenv::inspect(&home);
+  assert!(info.exists && !info.ok && info.python_version.is_none());
+  assert!(info.error.unwrap().contains("init_fs_encoding"));
+  fs::remove_file(&broken).unwrap();
+  fs::write(&fail, "").unwrap();
+  let err = pyenv::repair(&res, &home, &RecordingSink::default(), &cancel).await.unwrap_err();
+  assert!(err.contains("No matching distribution"), "{err}");
+  assert!(!home.join("pyenv.new").exists());
+  assert_eq!(pyenv::inspect(&home).arkpy_version.as_deref(), Some("0.3.0"));
+
+  // a successful repair replaces it
+  fs::remove_file(&fail).unwrap();
+  std::env::set_var("FAKE_ARKPY", "0.3.1");
+  let info = pyenv::repair(&res, &home, &RecordingSink::default(), &cancel).await.unwrap();
+  assert_eq!(info.arkpy_version.as_deref(), Some("0.3.1"));
+  assert!(!home.join("pyenv.old").exists());
+}
@@ -0,0 +1,101 @@
+// tests/reconfig.rs
+//! Settings change classification, and applying it to a supervised fake arkd
+//! that records its command line. One stateful test: it owns HOME and ARK_ARKD.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::node_control::NodeProc;
+use arkdesk_lib::profiles::LaunchSpec;
+use arkdesk_lib::reconfig::{self, Action, Effect};
+use arkdesk_lib::settings::{load_settings, save_settings, NodeRole, Settings};
+use common::RecordingSink;
+use std::{fs, net::TcpListener, os::unix::fs::PermissionsExt, path::Path, sync::Arc, time::Duration};
+
+const FAKE_ARKD: &str = "#!/bin/sh\necho \"$*\" >> \"$ARKD_ARGS\"\nwhile true; do sleep 0.1; done\n";
+
+fn free_port() -> u16 {
+  TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+async fn launches(log: &Path, n: usize) -> Vec<String> {
+  for _ in 0..100 {
+    let lines: Vec<String> = fs::read_to_string(log).unwrap_or_default().lines().map(String::from).collect();
+    if lines.len() >= n { return lines; }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+  }
+  panic!("arkd launched fewer than {n} times");
+}
+
+#[test]
+fn changes_are_classified_by_what_applies_them() {
+  let old = Settings::default();
+  let new = Settings { rpc_port: 9001, keep_versions: 5, ..Settings::default() };
+  let d = reconfig::diff(&old, &new);
+  assert_eq!(d.iter().map(|c| (c.field.as_str(), c.effect)).collect::<Vec<_>>(), [
+    ("keepVersions", Effect::Live),
+    ("rpcPort", Effect::NodeRestart),
+  ]);
+  assert_eq!((d[1].from.as_u64(), d[1].to.as_u64()), (Some(8645), Some(9001)));
+
+  // only a node ArkDesk supervises is restarted; live changes need nothing
+  assert_eq!(reconfig::plan(&old, &new, true, true).action, Action::RestartNode);
+  assert!(reconfig::plan(&old, &new, true, true).needs_confirmation);
+  assert_eq!(reconfig::plan(&old, &new, true, false).action, Action::None);
+  let live = Settings { keep_versions: 5, ..Settings::default() };
+  assert_eq!(reconfig::plan(&old, &live, true, true).action, Action::None);
+  assert!(reconfig::diff(&old, &Settings::default()).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn apply_restarts_supervised_node_only_when_confirmed() {
+  let user = tempfile::tempdir().unwrap();
+  let arkd = user.path().join("fake-arkd");
+  let log = user.path().join("args.log");
+  fs::write(&arkd, FAKE_ARKD).unwrap();
+  fs::set_permissions(&arkd, fs::Permissions::from_mode(0o755)).unwrap();
+  std::env::set_var("HOME", user.path());
+  std::env::remove_var("ARK_HOME");
+  std::env::set_var("ARK_ARKD", &arkd);
+  std::env::set_var("ARKD_ARGS", &log);
+
+  let (p2p, rpc) = (free_port(), free_port());
+  save_settings(Settings { p2p_port: p2p, rpc_port: rpc, ..Settings::default() }, None).unwrap();
+  let proc = NodeProc::default();
+  let sink: Sink = Arc::new(RecordingSink::default());
+
+  // free configured ports add nothing to arkd's command line
+  proc.start(sink.clone(), None, LaunchSpec::adhoc(vec![]), false).await.unwrap();
+  let first = launches(&log, 1).await;
+  assert_eq!(first[0], "");
+  let pid = proc.pid().unwrap();
+
+  // unconfirmed: saved, node left alone
+  let rpc2 = free_port();
+  let r = reconfig::apply(Settings { rpc_port: rpc2, ..load_settings() }, false, false, &proc, sink.clone(), None).await.unwrap();
+  assert_eq!((r.plan.action, r.performed), (Action::RestartNode, Action::None));
+  assert!(r.message.contains("restart the node"), "{}", r.message);
+  assert_eq!(load_settings().rpc_port, rpc2);
+  assert_eq!(proc.pid(), Some(pid));
+
+  // live-only change: nothing to restart even though the node runs
+  let r = reconfig::apply(Settings { keep_versions: 7, ..load_settings() }, false, true, &proc, sink.clone(), None).await.unwrap();
+  assert_eq!((r.plan.action, r.performed), (Action::None, Action::None));
+  assert_eq!(proc.pid(), Some(pid));
+
+  // confirmed: restarted with the same launch
+  let r = reconfig::apply(Settings { role: NodeRole::Relay, p2p_port: free_port(), ..load_settings() }, false, true, &proc, sink.clone(), None)
+    .await
+    .unwrap();
+  assert_eq!(r.performed, Action::RestartNode);
+  assert_eq!(launches(&log, 2).await[1], "");
+  assert_ne!(proc.pid(), Some(pid));
+
+  // invalid settings are refused before anything happens
+  let err = reconfig::apply(Settings { p2p_port: 0, ..load_settings() }, false, true, &proc, sink.clone(), None).await.unwrap_err();
+  assert!(err.contains("p2pPort"), "{err}");
+
+  proc.stop(None).await.unwrap();
+  assert!(proc.pid().is_none());
+}
@@ -0,0 +1,103 @@
+// tests/reset.rs
+//! Selective node data reset.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::cleanup::{reset_data, DataScope, ResetReport};
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::node_control::NodeProc;
+use arkdesk_lib::profiles::LaunchSpec;
+use common::{RecordingSink, TestHome};
+use std::sync::Arc;
+
+fn paths(r: &ResetReport) -> Vec<String> {
+  r.items.iter().map(|i| i.path.clone()).collect()
+}
+
+#[test]
+fn chain_dry_run_skips_keys_mempool_and_peers() {
+  let t = TestHome::new();
+  t.put("data/devnet/blocks/000001.blk", 4000);
+  t.put("data/devnet/state.db", 1000);
+  t.put("data/devnet/keys/node.key", 64);
+  t.put("data/devnet/mempool.dat", 50);
+  t.put("data/devnet/peers.json", 20);
+
+  let r = reset_data(&[DataScope::Chain], true, None).unwrap();
+  assert_eq!(paths(&r), [t.rel("data/devnet/blocks"), t.rel("data/devnet/state.db")]);
+  assert_eq!(r.bytes_freed, 5000);
+  assert!(r.removed.is_empty() && t.path("data/devnet/state.db").exists());
+}
+
+#[test]
+fn mempool_and_peers_are_found_under_data_and_var() {
+  let t = TestHome::new();
+  t.put("data/devnet/mempool.dat", 50);
+  t.put("data/devnet/peers.json", 20);
+  t.put("var/devnet/addrbook.json", 5);
+
+  let r = reset_data(&[DataScope::Peers, DataScope::Mempool], true, None).unwrap();
+  assert_eq!(r.items.iter().map(|i| (i.scope, i.path.clone())).collect::<Vec<_>>(), [
+    (DataScope::Mempool, t.rel("data/devnet/mempool.dat")),
+    (DataScope::Peers, t.rel("data/devnet/peers.json")),
+    (DataScope::Peers, t.rel("var/devnet/addrbook.json")),
+  ]);
+}
+
+#[test]
+fn no_scope_is_an_error() {
+  let _t = TestHome::new();
+  assert!(reset_data(&[], true, None).is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_running_node_is_reported_by_a_dry_run_and_refused_otherwise() {
+  let t = TestHome::new();
+  t.save_free_ports();
+  t.fake_arkd();
+  t.put("data/devnet/state.db", 1000);
+  let proc = NodeProc::default();
+  let sink: Sink = Arc::new(RecordingSink::default());
+  proc.start(sink, None, LaunchSpec::adhoc(vec![]), false).await.unwrap();
+  let pid = proc.pid();
+
+  let r = reset_data(&[DataScope::Chain], true, Some(&proc)).unwrap();
+  assert_eq!(r.running_node, pid);
+
+  let err = reset_data(&[DataScope::Chain], false, Some(&proc)).unwrap_err();
+  assert!(err.contains("stop the node"), "{err}");
+  assert!(proc.pid().is_some() && t.path("data/devnet/state.db").exists());
+  proc.stop(None).await.unwrap();
+}
+
+#[test]
+fn chain_logs_and_run_are_removed_and_the_rest_stays() {
+  let t = TestHome::new();
+  t.put("data/devnet/blocks/000001.blk", 4000);
+  t.put("data/devnet/state.db", 1000);
+  t.put("data/devnet/keys/node.key", 64);
+  t.put("data/devnet/mempool.dat", 50);
+  t.put("logs/arkd.out", 30);
+  t.put("var/devnet/run/node.json", 10);
+  t.put("keys/wallet.key", 32);
+
+  let r = reset_data(&[DataScope::Chain, DataScope::Logs, DataScope::Run], false, None).unwrap();
+  assert_eq!(r.removed.len(), 4, "{:?}", r.removed);
+  assert!(r.skipped.is_empty() && r.running_node.is_none());
+  assert_eq!(r.bytes_freed, 5000 + 30 + 10);
+  for gone in ["data/devnet/blocks", "data/devnet/state.db", "logs/arkd.out", "var/devnet/run/node.json"] {
+    assert!(!t.path(gone).exists(), "{gone}");
+  }
+  for kept in ["data/devnet/keys/node.key", "data/devnet/mempool.dat", "keys/wallet.key", "logs", "var/devnet/run"] {
+    assert!(t.path(kept).exists(), "{kept}");
+  }
+}
+
+#[test]
+fn pyenv_removes_the_venv() {
+  let t = TestHome::new();
+  t.put("pyenv/bin/python", 1);
+  let r = reset_data(&[DataScope::Pyenv], false, None).unwrap();
+  assert_eq!(r.removed, [t.rel("pyenv")]);
+}
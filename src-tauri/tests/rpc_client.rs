@@ -0,0 +1,102 @@
+// tests/rpc_client.rs
+mod common;
+
+use arkdesk_lib::rpc::{RpcClient, RpcError};
+use arkdesk_lib::types::{ChainTip, MempoolInfo};
+use common::{mock_node, mock_node::hash_for, Fault};
+use reqwest::header::HeaderMap;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn client(port: u16, timeout_ms: u64) -> RpcClient {
+  RpcClient::new(format!("http://127.0.0.1:{port}/"), "", Duration::from_millis(timeout_ms), false, &HeaderMap::new()).unwrap()
+}
+
+#[tokio::test]
+async fn typed_calls() {
+  let (_dir, node) = mock_node().await;
+  let rpc = client(node.rpc_port, 2000);
+
+  let tip: ChainTip = rpc.call("chain.tip", json!({})).await.unwrap();
+  assert_eq!(tip.height, 100);
+  assert_eq!(tip.block_id.as_deref(), Some(hash_for(100).as_str()));
+  assert_eq!(tip.timestamp_ms, Some(1_700_000_000_100));
+
+  let mp: MempoolInfo = rpc.call("mempool.info", json!({})).await.unwrap();
+  assert_eq!((mp.txs, mp.bytes), (Some(3), Some(512)));
+
+  let list: Vec<String> = rpc.call("rpc.list", json!({})).await.unwrap();
+  assert!(list.iter().any(|m| m == "net.peers"));
+}
+
+#[tokio::test]
+async fn call_value_keeps_envelope_and_cache_key() {
+  let (_dir, node) = mock_node().await;
+  let rpc = client(node.rpc_port, 2000);
+
+  let out = rpc.call_value("chain.header", json!({ "tip": true })).await.unwrap();
+  assert_eq!(out.raw["jsonrpc"], "2.0");
+  assert_eq!(out.result.unwrap()["height"], "100");
+  assert_eq!(out.cache_key, r#"chain.header:{"tip":true}"#);
+}
+
+#[tokio::test]
+async fn json_rpc_error_is_remote() {
+  let (_dir, node) = mock_node().await;
+  node.fault("chain.tip", Fault::RpcError(-32000, "tip unavailable".into()));
+  let rpc = client(node.rpc_port, 2000);
+
+  let err = rpc.call::<ChainTip, _>("chain.tip", json!({})).await.unwrap_err();
+  match err.downcast_ref::<RpcError>() {
+    Some(RpcError::Remote { method, code, message, .. }) => {
+      assert_eq!((method.as_str(), *code, message.as_str()), ("chain.tip", -32000, "tip unavailable"));
+    }
+    other => panic!("expected remote error, got {other:?}"),
+  }
+
+  let err = rpc.call::<Value, _>("no.such.method", json!({})).await.unwrap_err();
+  assert!(matches!(err.downcast_ref::<RpcError>(), Some(RpcError::Remote { code: -32601, .. })), "{err:?}");
+}
+
+#[tokio::test]
+async fn http_500_is_http_error() {
+  let (_dir, node) = mock_node().await;
+  node.fault("mempool.info", Fault::Status(500));
+  let rpc = client(node.rpc_port, 2000);
+
+  let err = rpc.call::<MempoolInfo, _>("mempool.info", json!({})).await.unwrap_err();
+  assert!(
+    matches!(err.downcast_ref::<RpcError>(), Some(RpcError::Http { status, .. }) if status.as_u16() == 500),
+    "{err:?}",
+  );
+}
+
+#[tokio::test]
+async fn slow_method_times_out() {
+  let (_dir, node) = mock_node().await;
+  node.fault("chain.tip", Fault::Delay(Duration::from_millis(1500)));
+  let rpc = client(node.rpc_port, 200);
+
+  let err = rpc.call::<ChainTip, _>("chain.tip", json!({})).await.unwrap_err();
+  let net = err.chain().find_map(|e| e.downcast_ref::<reqwest::Error>());
+  assert!(net.is_some_and(|e| e.is_timeout()), "{err:?}");
+
+  // the fault is per method
+  assert!(rpc.call::<MempoolInfo, _>("mempool.info", json!({})).await.is_ok());
+}
+
+#[tokio::test]
+async fn garbage_body_fails_to_decode() {
+  let (_dir, node) = mock_node().await;
+  node.fault("chain.tip", Fault::Garbage);
+
+  let err = client(node.rpc_port, 2000).call::<ChainTip, _>("chain.tip", json!({})).await.unwrap_err();
+  assert_eq!(err.to_string(), "RPC decode failed");
+}
+
+#[tokio::test]
+async fn closed_port_fails_fast() {
+  let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+  let err = client(port, 2000).call::<ChainTip, _>("chain.tip", json!({})).await.unwrap_err();
+  assert_eq!(err.to_string(), "RPC chain.tip send failed");
+}
@@ -0,0 +1,102 @@
+// tests/selftest.rs
+//! Installed-node self-test against a fake arkd that serves the mock node's
+//! node.json and exits once the mock sees `/v1/shutdown`. One test: it owns HOME.
+#![cfg(unix)]
+
+mod common;
+
+use arkdesk_lib::resources::Resources;
+use arkdesk_lib::selftest::{self, CheckState, CHECKS, EVT_SELFTEST_CHECK, EVT_SELFTEST_DONE};
+use arkdesk_lib::settings::{save_settings, Settings};
+use common::RecordingSink;
+use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+
+const FAKE_ARKD: &str = r#"#!/bin/sh
+[ "$1" = "--version" ] && { echo "arkd 0.7.0-fake"; exit 0; }
+echo "$*" > "$MOCK_RUN/args"
+echo "fake arkd $*"
+echo "warming up" >&2
+rm -f "$MOCK_RUN/shutdown"
+cp "$MOCK_RUN/node.json" "$ARK_RUN_DIR/node.json.tmp" && mv "$ARK_RUN_DIR/node.json.tmp" "$ARK_RUN_DIR/node.json"
+while [ ! -f "$MOCK_RUN/shutdown" ]; do sleep 0.1; done
+echo "bye"
+"#;
+
+const FAKE_PY: &str = r#"#!/bin/sh
+case "$2" in
+  *CDLL*) [ -f "$3" ] || { echo "OSError: $3: cannot open shared object file" >&2; exit 1; }; echo "loaded $3" ;;
+  *) echo "0.2.0" ;;
+esac
+"#;
+
+fn script(p: &Path, body: &str) {
+  fs::create_dir_all(p.parent().unwrap()).unwrap();
+  fs::write(p, body).unwrap();
+  fs::set_permissions(p, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn states(r: &selftest::SelftestReport) -> Vec<(&str, CheckState)> {
+  r.checks.iter().map(|c| (c.id, c.state)).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn selftest_runs_scratch_node_and_reports_each_check() {
+  let user = tempfile::tempdir().unwrap();
+  std::env::set_var("HOME", user.path());
+  std::env::remove_var("ARK_HOME");
+  let home = user.path().join(".arknet");
+  let res = Resources::new(None);
+
+  // nothing installed: every check after the first is skipped
+  let sink = RecordingSink::default();
+  let r = selftest::run(&res, &sink).await;
+  assert!(!r.ok && r.partial);
+  assert_eq!(r.checks[0].state, CheckState::Fail);
+  assert!(r.checks[0].detail.contains("run install first"), "{}", r.checks[0].detail);
+  assert!(r.checks.iter().skip(1).all(|c| c.state != CheckState::Pass));
+
+  let (mock_dir, node) = common::mock_node().await;
+  let mock_run = mock_dir.path().join("run");
+  std::env::set_var("MOCK_RUN", &mock_run);
+  script(&home.join("bin/arkd"), FAKE_ARKD);
+  script(&home.join("bin/tests"), "#!/bin/sh\necho \"running\"\necho \"12 passed\"\n");
+  script(&home.join("pyenv/bin/python"), FAKE_PY);
+  let lib = user.path().join("libarknet.so");
+  fs::write(&lib, b"\x7fELF").unwrap();
+  save_settings(Settings { c_lib_path: lib.to_string_lossy().into(), ..Settings::default() }, None).unwrap();
+
+  let sink = RecordingSink::default();
+  let r = selftest::run(&res, &sink).await;
+  let ids: Vec<_> = CHECKS.iter().map(|(id, _)| *id).collect();
+  assert_eq!(r.checks.iter().map(|c| c.id).collect::<Vec<_>>(), ids);
+  assert!(r.ok && !r.partial, "{:#?}", r.checks);
+  assert_eq!(r.arkpy_version.as_deref(), Some("0.2.0"));
+  assert!(r.checks.iter().find(|c| c.id == "healthz").unwrap().detail.contains("abiRev 1"));
+  assert!(r.checks.iter().find(|c| c.id == "shutdown").unwrap().detail.contains("admin"));
+  assert!(node.shutdown_requested());
+
+  // throwaway data dir and ephemeral ports, removed afterwards
+  let args = fs::read_to_string(mock_run.join("args")).unwrap();
+  let data = args.split_whitespace().skip_while(|a| *a != "--data-dir").nth(1).unwrap();
+  assert!(!data.starts_with(home.to_str().unwrap()), "{args}");
+  assert!(!Path::new(data).exists());
+  assert!(args.contains("--p2p-port") && args.contains("--rpc-port"), "{args}");
+
+  assert!(r.log.iter().any(|l| l == "[tests] 12 passed"), "{:?}", r.log);
+  assert!(r.log.iter().any(|l| l == "[arkd:stderr] warming up"), "{:?}", r.log);
+
+  // a running and a final event per check, then the report
+  assert_eq!(sink.take_named(EVT_SELFTEST_CHECK).len(), 2 * CHECKS.len());
+  assert_eq!(sink.take_named(EVT_SELFTEST_DONE).len(), 1);
+
+  // a missing C library or tests binary fails that check only
+  fs::remove_file(&lib).unwrap();
+  fs::remove_file(home.join("bin/tests")).unwrap();
+  let r = selftest::run(&res, &RecordingSink::default()).await;
+  assert!(!r.ok);
+  let st = states(&r);
+  assert!(st.contains(&("clib", CheckState::Fail)) && st.contains(&("tests", CheckState::Fail)), "{st:?}");
+  assert!(st.iter().filter(|(id, _)| !["clib", "tests"].contains(id)).all(|(_, s)| *s == CheckState::Pass), "{st:?}");
+  let clib = r.checks.iter().find(|c| c.id == "clib").unwrap();
+  assert!(clib.detail.contains("cannot open shared object"), "{}", clib.detail);
+}
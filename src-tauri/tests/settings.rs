@@ -0,0 +1,62 @@
+// tests/settings.rs
+//! `config.json` loading: schema migrations, per-field defaults and backups of
+//! broken files. One test: it owns HOME.
+
+use arkdesk_lib::settings::{
+  self, get_settings, load_settings, save_settings, try_load_settings, NodeRole, SettingsErrorKind, SCHEMA_VERSION,
+};
+use serde_json::{json, Value};
+use std::fs;
+
+#[test]
+fn config_is_migrated_defaulted_and_backed_up_when_broken() {
+  let user = tempfile::tempdir().unwrap();
+  std::env::set_var("HOME", user.path());
+  std::env::remove_var("ARK_HOME");
+  let home = settings::ark_home();
+  let cfg = home.join("config.json");
+
+  // no file: defaults at the current schema
+  assert_eq!(try_load_settings().unwrap().schema_version, SCHEMA_VERSION);
+
+  // a pre-schema file missing newer fields keeps what it has
+  fs::create_dir_all(&home).unwrap();
+  fs::write(&cfg, r#"{"cLibPath":"/opt/libarknet.so","arkPyPath":"","p2pPort":9000,"rpcPort":9001,"role":"miner"}"#).unwrap();
+  let s = try_load_settings().unwrap();
+  assert_eq!((s.schema_version, s.p2p_port, s.rpc_port, s.keep_versions), (SCHEMA_VERSION, 9000, 9001, 3));
+  assert!(matches!(s.role, NodeRole::Miner));
+  assert_eq!(s.c_lib_path, "/opt/libarknet.so");
+
+  // saving stamps the schema version (forced: the library path is made up)
+  save_settings(s, Some(true)).unwrap();
+  let on_disk: Value = serde_json::from_slice(&fs::read(&cfg).unwrap()).unwrap();
+  assert_eq!(on_disk["schemaVersion"], SCHEMA_VERSION);
+
+  // a file from a newer build still loads
+  fs::write(&cfg, json!({ "schemaVersion": SCHEMA_VERSION + 1, "rpcPort": 7000, "futureKnob": true }).to_string()).unwrap();
+  assert_eq!(try_load_settings().unwrap().rpc_port, 7000);
+
+  // malformed: structured error, one backup, the file itself untouched
+  let broken = r#"{"rpcPort": 8645, "role": "miner",,}"#;
+  fs::write(&cfg, broken).unwrap();
+  let e = try_load_settings().unwrap_err();
+  assert_eq!(e.kind, SettingsErrorKind::Parse);
+  let backup = e.backup.clone().unwrap();
+  assert_eq!(fs::read_to_string(&backup).unwrap(), broken);
+  assert_eq!(fs::read_to_string(&cfg).unwrap(), broken);
+  assert_eq!(try_load_settings().unwrap_err().backup.as_deref(), Some(backup.as_str()));
+  let json = serde_json::to_value(get_settings().unwrap_err()).unwrap();
+  assert_eq!((json["kind"].as_str(), json["backup"].as_str()), (Some("parse"), Some(backup.as_str())));
+  assert!(json["path"].as_str().unwrap().ends_with("config.json"), "{json}");
+
+  // wrong types fail the same way; a second broken file gets its own copy
+  fs::write(&cfg, r#"{"rpcPort": "not a port"}"#).unwrap();
+  let e = try_load_settings().unwrap_err();
+  assert_ne!(e.backup.as_deref(), Some(backup.as_str()));
+  assert_eq!(fs::read_to_string(&backup).unwrap(), broken);
+
+  // internal callers run on defaults until the user saves over it
+  assert_eq!(load_settings().rpc_port, 8645);
+  save_settings(settings::Settings::default(), None).unwrap();
+  assert!(try_load_settings().is_ok());
+}
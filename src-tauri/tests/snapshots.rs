@@ -0,0 +1,104 @@
+// tests/snapshots.rs
+//! Snapshot and restore of `data/` against the mock node.
+mod common;
+
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::snapshots::{self, snapshots_dir, SnapshotMeta};
+use arkdesk_lib::state::NodeBridge;
+use common::{mock_node, RecordingSink, TestHome};
+use std::{fs, sync::Arc};
+
+/// No node behind it: snapshots carry no tip.
+fn no_node(t: &TestHome) -> NodeBridge { NodeBridge::new(t.path("var/devnet/run/node.json")) }
+
+fn sink() -> Sink { Arc::new(RecordingSink::default()) }
+
+fn write(t: &TestHome, rel: &str, body: &str) {
+  let p = t.path(rel);
+  fs::create_dir_all(p.parent().unwrap()).unwrap();
+  fs::write(p, body).unwrap();
+}
+
+fn read(t: &TestHome, rel: &str) -> String { fs::read_to_string(t.path(rel)).unwrap() }
+
+async fn take(t: &TestHome) -> SnapshotMeta {
+  snapshots::create(None, None, &no_node(t), sink(), None).await.unwrap().snapshot
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn nothing_to_snapshot_without_data() {
+  let t = TestHome::new();
+  assert!(snapshots::create(None, None, &no_node(&t), sink(), None).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn metadata_records_the_chain_tip() {
+  let t = TestHome::new();
+  let (_run, node) = mock_node().await;
+  node.set_chain(|c| { c.height = 4242; c.hash = common::mock_node::hash_for(4242); });
+  let bridge = NodeBridge::new(node.manifest_path().to_path_buf());
+  write(&t, "data/devnet/state.db", "before deploy");
+
+  let run = snapshots::create(Some("pre-deploy".into()), None, &bridge, sink(), None).await.unwrap();
+  let meta = &run.snapshot;
+  assert_eq!(meta.tip_height, Some(4242));
+  assert_eq!(meta.tip_hash.as_deref(), Some(common::mock_node::hash_for(4242).as_str()));
+  assert_eq!(meta.network.as_deref(), Some("devnet"));
+  assert!(meta.arkd_version.is_some());
+  assert_eq!(meta.label.as_deref(), Some("pre-deploy"));
+  assert_eq!(meta.data_bytes, 13);
+  assert!(meta.id.ends_with("-h4242"), "{}", meta.id);
+  assert!(!run.restarted && run.stopped_node.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ids_are_unique_within_a_second() {
+  let t = TestHome::new();
+  write(&t, "data/devnet/state.db", "x");
+  let (first, second) = (take(&t).await, take(&t).await);
+  assert_ne!(second.id, first.id);
+  let listed: Vec<String> = snapshots::list(&t.home).into_iter().map(|m| m.id).collect();
+  assert_eq!(listed.len(), 2);
+  assert!(listed.contains(&first.id) && listed.contains(&second.id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delete_and_path_ids() {
+  let t = TestHome::new();
+  write(&t, "data/devnet/state.db", "x");
+  let meta = take(&t).await;
+  snapshots::delete(&t.home, &meta.id).unwrap();
+  assert!(snapshots::get(&t.home, &meta.id).is_err());
+  assert!(snapshots::get(&t.home, "../data").is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restore_rolls_data_back() {
+  let t = TestHome::new();
+  write(&t, "data/devnet/state.db", "before deploy");
+  write(&t, "data/devnet/blocks/000001.blk", "block one");
+  let meta = take(&t).await;
+  write(&t, "data/devnet/state.db", "after deploy");
+  write(&t, "data/devnet/contract.db", "junk");
+
+  assert!(snapshots::verify(&t.home, &meta.id).unwrap().ok);
+  snapshots::restore(&meta.id, None, sink(), None).await.unwrap();
+  assert_eq!(read(&t, "data/devnet/state.db"), "before deploy");
+  assert_eq!(read(&t, "data/devnet/blocks/000001.blk"), "block one");
+  assert!(!t.path("data/devnet/contract.db").exists());
+  assert!(!t.path("data.restore").exists() && !t.path("data.pre-restore").exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_damaged_archive_is_refused() {
+  let t = TestHome::new();
+  write(&t, "data/devnet/state.db", "before deploy");
+  let meta = take(&t).await;
+  write(&t, "data/devnet/state.db", "after deploy");
+  fs::write(snapshots_dir(&t.home).join(&meta.id).join("data.tar.zst"), b"not zstd").unwrap();
+
+  assert!(!snapshots::verify(&t.home, &meta.id).unwrap().ok);
+  let err = snapshots::restore(&meta.id, None, sink(), None).await.unwrap_err();
+  assert!(err.contains("corrupt"), "{err}");
+  assert_eq!(read(&t, "data/devnet/state.db"), "after deploy");
+}
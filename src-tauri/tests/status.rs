@@ -0,0 +1,85 @@
+// tests/status.rs
+mod common;
+
+use arkdesk_lib::settings::{save_settings, NodeRole, Settings};
+use arkdesk_lib::status::{get_status, probe_status};
+use common::{mock_node, Fault};
+use std::time::Duration;
+
+fn endpoint(port: u16) -> String { format!("http://127.0.0.1:{port}") }
+
+#[tokio::test]
+async fn running_node_with_peers() {
+  let (_dir, node) = mock_node().await;
+  node.set_chain(|c| { c.height = 777; c.peers = 5; });
+
+  let s = probe_status(endpoint(node.rpc_port), NodeRole::Miner).await.unwrap();
+  assert!(s.node_running && s.rpc_ok && s.connected);
+  assert_eq!((s.network_height, s.peers), (777, 5));
+  assert!(matches!(s.role, NodeRole::Miner));
+}
+
+#[tokio::test]
+async fn no_peers_is_not_connected() {
+  let (_dir, node) = mock_node().await;
+  node.set_chain(|c| c.peers = 0);
+  let s = probe_status(endpoint(node.rpc_port), NodeRole::Relay).await.unwrap();
+  assert!(s.node_running && !s.connected);
+}
+
+#[tokio::test]
+async fn nothing_listening() {
+  let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+  let s = probe_status(endpoint(port), NodeRole::Relay).await.unwrap();
+  assert!(!s.node_running && !s.rpc_ok);
+  assert_eq!(s.rpc_endpoint, endpoint(port));
+}
+
+#[tokio::test]
+async fn header_http_500_means_not_running() {
+  let (_dir, node) = mock_node().await;
+  node.fault("chain.header", Fault::Status(500));
+  let s = probe_status(endpoint(node.rpc_port), NodeRole::Relay).await.unwrap();
+  assert!(!s.node_running && !s.rpc_ok);
+  assert_eq!(node.hits("net.peers"), 0);
+}
+
+#[tokio::test]
+async fn header_timeout_means_not_running() {
+  let (_dir, node) = mock_node().await;
+  node.fault("chain.header", Fault::Delay(Duration::from_millis(1500)));
+  let s = probe_status(endpoint(node.rpc_port), NodeRole::Relay).await.unwrap();
+  assert!(!s.node_running);
+}
+
+#[tokio::test]
+async fn header_rpc_error_still_counts_as_up() {
+  let (_dir, node) = mock_node().await;
+  node.fault("chain.header", Fault::RpcError(-32000, "warming up".into()));
+  let s = probe_status(endpoint(node.rpc_port), NodeRole::Relay).await.unwrap();
+  assert!(s.node_running && s.rpc_ok);
+  assert_eq!(s.network_height, 0);
+}
+
+#[tokio::test]
+async fn peers_error_leaves_height() {
+  let (_dir, node) = mock_node().await;
+  node.fault("net.peers", Fault::RpcError(-32603, "internal".into()));
+  let s = probe_status(endpoint(node.rpc_port), NodeRole::Relay).await.unwrap();
+  assert!(s.node_running && !s.connected);
+  assert_eq!((s.network_height, s.peers), (100, 0));
+}
+
+/// The only test here that touches the environment: `get_status` reads the
+/// rpc port from `$HOME/.arknet/config.json`.
+#[tokio::test]
+async fn get_status_uses_configured_rpc_port() {
+  let (dir, node) = mock_node().await;
+  std::env::set_var("HOME", dir.path());
+  save_settings(Settings { rpc_port: node.rpc_port, ..Settings::default() }, None).unwrap();
+
+  let s = get_status().await.unwrap();
+  assert_eq!(s.rpc_endpoint, endpoint(node.rpc_port));
+  assert!(s.node_running);
+  assert_eq!(s.network_height, 100);
+}
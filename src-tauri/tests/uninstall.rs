@@ -0,0 +1,122 @@
+// tests/uninstall.rs
+//! Itemized uninstall: what goes, what stays.
+#![cfg(all(unix, not(target_os = "macos")))]
+
+mod common;
+
+use arkdesk_lib::cleanup::{self, ItemKind, UninstallPlan, UninstallReport};
+use arkdesk_lib::events::Sink;
+use arkdesk_lib::node_control::NodeProc;
+use arkdesk_lib::profiles::LaunchSpec;
+use common::{RecordingSink, TestHome};
+use std::sync::Arc;
+
+const DRY_KEEP_ALL: UninstallPlan = UninstallPlan { dry_run: true, keep_data: true, keep_keys: true };
+const KEEP_KEYS: UninstallPlan = UninstallPlan { dry_run: false, keep_data: false, keep_keys: true };
+
+async fn uninstall(plan: &UninstallPlan) -> UninstallReport {
+  cleanup::run_uninstall(plan, None, None).await.unwrap()
+}
+
+fn size(r: &UninstallReport, kind: ItemKind) -> u64 {
+  r.items.iter().filter(|i| i.kind == kind).map(|i| i.bytes).sum()
+}
+
+#[tokio::test]
+async fn dry_run_sizes_each_kind_and_touches_nothing() {
+  let t = TestHome::new();
+  t.put("versions/0.1.0/arkd", 1000);
+  t.put("pyenv/bin/python", 200);
+  t.put("logs/arkd.out", 30);
+  t.put("data/chain.db", 5000);
+  t.put("keys/wallet.key", 32);
+  t.put("profiles.json", 10);
+
+  let r = uninstall(&DRY_KEEP_ALL).await;
+  assert_eq!(size(&r, ItemKind::Binaries), 1000);
+  assert_eq!(size(&r, ItemKind::Data), 5000);
+  assert_eq!(size(&r, ItemKind::Keys), 32);
+  assert_eq!((r.remove_bytes, r.keep_bytes), (1000 + 200 + 30 + 10, 5032));
+  assert!(r.removed.is_empty() && t.path("versions/0.1.0/arkd").is_file());
+}
+
+#[tokio::test]
+async fn node_keys_inside_data_count_as_keys() {
+  let t = TestHome::new();
+  t.put("data/chain.db", 5000);
+  t.put("data/devnet/keys/node.key", 64);
+  let r = uninstall(&DRY_KEEP_ALL).await;
+  assert_eq!((size(&r, ItemKind::Data), size(&r, ItemKind::Keys)), (5000, 64));
+}
+
+#[tokio::test]
+async fn staging_and_the_app_endpoints_file_are_listed() {
+  let t = TestHome::new();
+  std::env::set_var("XDG_CONFIG_HOME", t.user_dir().join(".config"));
+  common::put(&t.user_dir().join(".arknet.staging/journal.json"), 5);
+  let endpoints = cleanup::app_endpoints_path().unwrap();
+  assert!(endpoints.starts_with(t.user_dir()));
+  common::put(&endpoints, 7);
+
+  let r = uninstall(&DRY_KEEP_ALL).await;
+  assert_eq!((size(&r, ItemKind::Staging), size(&r, ItemKind::Config)), (5, 7));
+  let r = uninstall(&KEEP_KEYS).await;
+  assert!(!endpoints.exists() && !t.user_dir().join(".arknet.staging").exists(), "{:?}", r.skipped);
+}
+
+#[tokio::test]
+async fn keeping_keys_drops_chain_data_but_not_the_keys_inside_it() {
+  let t = TestHome::new();
+  t.put("data/chain.db", 5000);
+  t.put("data/devnet/keys/node.key", 64);
+  t.put("keys/wallet.key", 32);
+
+  let r = uninstall(&KEEP_KEYS).await;
+  assert!(!r.home_removed && !t.path("data/chain.db").exists());
+  assert!(t.path("data/devnet/keys/node.key").is_file() && t.path("keys/wallet.key").is_file());
+}
+
+#[tokio::test]
+async fn unknown_files_are_left_and_keep_the_home() {
+  let t = TestHome::new();
+  t.put("versions/0.1.0/arkd", 1000);
+  t.put("notes.txt", 1);
+
+  let r = uninstall(&UninstallPlan::default()).await;
+  assert_eq!(r.skipped.len(), 1);
+  assert!(r.skipped[0].path.ends_with("notes.txt"));
+  assert!(!r.home_removed && t.path("notes.txt").is_file() && !t.path("versions").exists());
+}
+
+#[tokio::test]
+async fn nothing_kept_and_nothing_foreign_removes_the_home() {
+  let t = TestHome::new();
+  t.put("versions/0.1.0/arkd", 1000);
+  t.put("data/chain.db", 5000);
+  t.put("keys/wallet.key", 32);
+  let r = uninstall(&UninstallPlan::default()).await;
+  assert!(r.home_removed && !t.home.exists(), "{:?}", r.skipped);
+}
+
+#[tokio::test]
+async fn a_config_set_aside_as_broken_goes_too() {
+  let t = TestHome::new();
+  t.put("config.json", 10);
+  let r = uninstall(&UninstallPlan::default()).await;
+  assert!(r.home_removed && !t.home.exists(), "{:?}", r.skipped);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_supervised_node_is_stopped_first() {
+  let t = TestHome::new();
+  t.save_free_ports();
+  t.fake_arkd();
+  let proc = NodeProc::default();
+  let sink: Sink = Arc::new(RecordingSink::default());
+  proc.start(sink, None, LaunchSpec::adhoc(vec![]), false).await.unwrap();
+  let pid = proc.pid();
+
+  let r = cleanup::run_uninstall(&KEEP_KEYS, Some(&proc), None).await.unwrap();
+  assert_eq!(r.stopped_node, pid);
+  assert!(proc.pid().is_none());
+}
@@ -0,0 +1,89 @@
+// tests/validate.rs
+//! Field-level settings validation and the save gate. One test: it owns HOME
+//! (role checks look at the managed venv, saves write `config.json`).
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use arkdesk_lib::settings::{save_settings, try_load_settings, NodeRole, Settings};
+use arkdesk_lib::validate::{check, validate, validate_rpc, Diagnostic, Severity};
+use std::{fs, net::TcpListener};
+
+fn codes(d: &[Diagnostic]) -> Vec<(&str, Severity, &str)> {
+  d.iter().map(|d| (d.field, d.severity, d.code)).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn settings_get_field_diagnostics_and_invalid_saves_are_refused() {
+  let user = tempfile::tempdir().unwrap();
+  std::env::set_var("HOME", user.path());
+  std::env::remove_var("ARK_HOME");
+  let home = user.path().join(".arknet");
+
+  assert!(check(&Settings::default(), &home).is_empty());
+
+  // ports: range, privileged, collision
+  let s = Settings { p2p_port: 0, rpc_port: 80, ..Settings::default() };
+  assert_eq!(codes(&check(&s, &home)), [
+    ("p2pPort", Severity::Error, "port-range"),
+    ("rpcPort", Severity::Warn, "port-privileged"),
+  ]);
+  let s = Settings { p2p_port: 9000, rpc_port: 9000, ..Settings::default() };
+  assert_eq!(codes(&check(&s, &home)), [("rpcPort", Severity::Error, "port-collision")]);
+
+  // disk: stopping before warning is allowed but flagged; 0 never stops
+  let s = Settings { disk_warn_mib: 100, disk_stop_mib: 500, ..Settings::default() };
+  assert_eq!(codes(&check(&s, &home)), [("diskWarnMib", Severity::Warn, "disk-thresholds")]);
+  assert!(check(&Settings { disk_warn_mib: 0, disk_stop_mib: 0, ..Settings::default() }, &home).is_empty());
+
+  // a miner needs a loadable C library and ArkPy; a relay only gets warnings
+  let miner = Settings { role: NodeRole::Miner, ..Settings::default() };
+  assert_eq!(codes(&check(&miner, &home)), [
+    ("cLibPath", Severity::Error, "required"),
+    ("arkPyPath", Severity::Error, "required"),
+  ]);
+  let (good, text, wrong_ext) = (user.path().join("libarknet.so"), user.path().join("notes.so"), user.path().join("arknet.txt"));
+  fs::write(&good, b"\x7fELF\x02\x01\x01").unwrap();
+  fs::write(&text, "hello").unwrap();
+  fs::write(&wrong_ext, b"\x7fELF").unwrap();
+  let ark_py = user.path().join("arkpy.py");
+  fs::write(&ark_py, "").unwrap();
+  let with = |lib: &std::path::Path, role| Settings {
+    c_lib_path: lib.to_string_lossy().into(),
+    ark_py_path: ark_py.to_string_lossy().into(),
+    role,
+    ..Settings::default()
+  };
+  assert!(check(&with(&good, NodeRole::Miner), &home).is_empty());
+  assert_eq!(codes(&check(&with(&text, NodeRole::Miner), &home)), [("cLibPath", Severity::Error, "not-loadable")]);
+  assert_eq!(codes(&check(&with(&wrong_ext, NodeRole::Miner), &home)), [("cLibPath", Severity::Error, "bad-extension")]);
+  let relay = Settings { c_lib_path: "/nope/libarknet.so".into(), ..Settings::default() };
+  assert_eq!(codes(&check(&relay, &home)), [("cLibPath", Severity::Warn, "not-found")]);
+
+  // save refuses errors unless forced; warnings don't block
+  let err = save_settings(miner.clone(), None).unwrap_err();
+  assert!(err.contains("cLibPath") && err.contains("arkPyPath"), "{err}");
+  assert!(!home.join("config.json").exists());
+  save_settings(relay, None).unwrap();
+  save_settings(miner, Some(true)).unwrap();
+  assert!(matches!(try_load_settings().unwrap().role, NodeRole::Miner));
+
+  // live state: nothing answering, and a foreign listener on the p2p port
+  let squatter = TcpListener::bind("127.0.0.1:0").unwrap();
+  let p2p = squatter.local_addr().unwrap().port();
+  let free = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+  let r = validate(&Settings { p2p_port: p2p, rpc_port: free, ..Settings::default() }, &home).await;
+  assert!(r.ok);
+  assert_eq!(codes(&r.diagnostics), [
+    ("rpcPort", Severity::Warn, "rpc-unreachable"),
+    ("p2pPort", Severity::Warn, "port-in-use"),
+  ]);
+
+  // a node answering on the rpc port: its ports are expected to be bound
+  let (_dir, node) = common::mock_node().await;
+  let r = validate(&Settings { p2p_port: p2p, rpc_port: node.rpc_port, ..Settings::default() }, &home).await;
+  assert!(r.diagnostics.is_empty(), "{:?}", r.diagnostics);
+  let rpc = validate_rpc(None, Some(node.rpc_port)).await.unwrap();
+  assert!(rpc.reachable && rpc.note.unwrap().contains("height 100"));
+  assert!(!validate_rpc(Some("127.0.0.1".into()), Some(free)).await.unwrap().reachable);
+}
@@ -0,0 +1,138 @@
+// tests/versions.rs
+use arkdesk_lib::versions;
+use std::{fs, path::Path, thread::sleep, time::Duration};
+
+/// Pretend-install `ver` the way the installer does: stage, fill, commit.
+fn put(home: &Path, ver: &str, keep: usize) -> String {
+  let staging = versions::staging_dir(home).unwrap();
+  fs::write(staging.join("arkd"), format!("arkd {ver}")).unwrap();
+  // installed_at has ms resolution; keep install order unambiguous
+  sleep(Duration::from_millis(3));
+  versions::commit(home, &staging, ver, Some(format!("arkd v{ver}")), keep).unwrap()
+}
+
+fn names(home: &Path) -> Vec<String> {
+  versions::list(home, 0).items.into_iter().map(|i| i.version).collect()
+}
+
+#[test]
+fn install_switches_current_and_records_previous() {
+  let home = tempfile::tempdir().unwrap();
+  let h = home.path();
+  assert!(versions::active_dir(h).is_none());
+
+  put(h, "0.1.0", 5);
+  put(h, "0.2.0", 5);
+  let ptr = versions::pointer(h).unwrap();
+  assert_eq!((ptr.version.as_str(), ptr.previous.as_deref()), ("0.2.0", Some("0.1.0")));
+  assert_eq!(versions::active_dir(h).unwrap(), versions::versions_dir(h).join("0.2.0"));
+
+  let list = versions::list(h, 5);
+  assert_eq!(names(h), ["0.2.0", "0.1.0"]);
+  assert!(list.items[0].active && list.items[1].previous);
+  assert_eq!(list.items[0].reported.as_deref(), Some("arkd v0.2.0"));
+  // no staging leftovers
+  assert!(fs::read_dir(versions::versions_dir(h)).unwrap().flatten()
+    .all(|e| !e.file_name().to_string_lossy().starts_with(".staging")));
+}
+
+#[test]
+fn rollback_and_activate() {
+  let home = tempfile::tempdir().unwrap();
+  let h = home.path();
+  for v in ["1.0.0", "1.1.0", "1.2.0"] { put(h, v, 5); }
+
+  assert_eq!(versions::rollback(h).unwrap(), "1.1.0");
+  // rolling back again flips back to the version we came from
+  assert_eq!(versions::rollback(h).unwrap(), "1.2.0");
+
+  versions::activate(h, "1.0.0").unwrap();
+  assert_eq!(versions::pointer(h).unwrap().previous.as_deref(), Some("1.2.0"));
+  assert!(versions::activate(h, "9.9.9").is_err());
+  assert!(versions::activate(h, "../etc").is_err());
+  assert!(versions::activate(h, "1.0.0/../1.1.0").is_err());
+}
+
+#[test]
+fn rollback_without_previous_uses_older_install() {
+  let home = tempfile::tempdir().unwrap();
+  let h = home.path();
+  put(h, "2.0.0", 5);
+  put(h, "2.1.0", 5);
+  fs::remove_dir_all(versions::versions_dir(h).join("2.0.0")).unwrap();
+  assert!(versions::rollback(h).is_err());
+
+  put(h, "2.2.0", 5);
+  // previous (2.1.0) is present, so it wins
+  assert_eq!(versions::rollback(h).unwrap(), "2.1.0");
+}
+
+#[test]
+fn prune_keeps_newest_and_protects_active() {
+  let home = tempfile::tempdir().unwrap();
+  let h = home.path();
+  for v in ["a1", "a2", "a3", "a4"] { put(h, v, 10); }
+  versions::activate(h, "a1").unwrap(); // previous = a4
+
+  let removed = versions::prune(h, 2);
+  assert_eq!(removed, ["a2"]);
+  assert_eq!(names(h), ["a4", "a3", "a1"]);
+
+  // commit prunes with the configured keep
+  put(h, "a5", 1);
+  assert_eq!(names(h), ["a5", "a1"]);
+}
+
+#[test]
+fn reinstall_same_version_replaces_tree() {
+  let home = tempfile::tempdir().unwrap();
+  let h = home.path();
+  put(h, "3.0.0", 5);
+  let staging = versions::staging_dir(h).unwrap();
+  fs::write(staging.join("arkd"), "rebuilt").unwrap();
+  versions::commit(h, &staging, "3.0.0", None, 5).unwrap();
+  assert_eq!(fs::read_to_string(versions::versions_dir(h).join("3.0.0/arkd")).unwrap(), "rebuilt");
+  assert_eq!(names(h), ["3.0.0"]);
+}
+
+#[test]
+fn version_names_are_path_safe() {
+  assert_eq!(versions::sanitize("0.4.2+git.abc"), "0.4.2+git.abc");
+  assert_eq!(versions::sanitize("../../x y"), "_.._x_y");
+  assert_eq!(versions::sanitize("  "), "unknown");
+}
+
+/// End to end through the installer: `arkd --version` names the dir and
+/// `resolve_arkd_path` follows `current`. Only test here that touches the environment.
+#[cfg(unix)]
+#[test]
+fn installer_detects_version_and_resolver_follows_current() {
+  use arkdesk_lib::{install, node_control::resolve_arkd_path, resources::Resources};
+  use std::os::unix::fs::PermissionsExt;
+
+  let home = tempfile::tempdir().unwrap();
+  let res = tempfile::tempdir().unwrap();
+  let src = res.path().join("bin").join(if cfg!(target_os = "macos") { "darwin" } else { "linux" });
+  fs::create_dir_all(&src).unwrap();
+  let write_bins = |ver: &str| {
+    for n in ["arkd", "cli", "call", "cc", "tests"] {
+      let p = src.join(n);
+      fs::write(&p, format!("#!/bin/sh\necho \"{n} v{ver} (deadbeef)\"\n")).unwrap();
+      fs::set_permissions(&p, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+  };
+  let res = Resources::new(Some(res.path().to_path_buf()));
+  std::env::set_var("ARK_HOME", home.path());
+
+  write_bins("0.7.1");
+  assert_eq!(install::install_version(&res, &src, home.path()).unwrap(), "0.7.1");
+  write_bins("0.8.0");
+  assert_eq!(install::install_version(&res, &src, home.path()).unwrap(), "0.8.0");
+
+  let active = versions::versions_dir(home.path()).join("0.8.0").join("arkd");
+  assert_eq!(resolve_arkd_path(None).unwrap(), active);
+
+  versions::rollback(home.path()).unwrap();
+  assert_eq!(resolve_arkd_path(None).unwrap(), versions::versions_dir(home.path()).join("0.7.1").join("arkd"));
+  assert_eq!(versions::list(home.path(), 3).items[1].reported.as_deref(), Some("arkd v0.7.1 (deadbeef)"));
+}
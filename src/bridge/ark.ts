@@ -21,6 +21,41 @@ export type RpcUpdate = {
   value: unknown;
 };
 
+/** `ark_bootstrap_fetch` result: what the source checkout now points at */
+export type BootstrapFetch = { srcDir: string; url: string; gitRef: string; commit: string; updated: boolean };
+/** `ark_bootstrap_build` result: products found and the arkd version they were installed as */
+export type BootstrapBuild = {
+  srcDir: string;
+  pin: BootstrapFetch | null;
+  products: Record<string, string>;
+  version: string;
+  versions: { current: string | null; previous: string | null };
+};
+
+/** `settings_import` result; `changes` may need a node restart to take effect */
+export type SettingsImport = {
+  changes: { field: string; from: unknown; to: unknown; effect: "live" | "nodeRestart" | "appRestart" }[];
+  diagnostics: { field: string; severity: "warn" | "error"; code: string; message: string }[];
+  profiles: string[];
+  endpoints: string[];
+  missingSecrets: string[];
+};
+
+/** `get_settings` error: config.json exists but could not be used */
+export type SettingsError = { kind: "read" | "parse"; path: string; backup: string | null; message: string };
+
+export function describeSettingsError(e: unknown): string {
+  if (!e || typeof e !== "object" || !("kind" in e)) return String(e);
+  const { kind, path, backup, message } = e as SettingsError;
+  return `${path} is ${kind === "read" ? "unreadable" : "invalid"}: ${message}${backup ? ` (copy saved to ${backup})` : ""}`;
+}
+
+/* ---------- settings bundles ---------- */
+export const ArkSettingsBundle = {
+  exportTo: (path: string, includeSecrets = false) => invoke<unknown>("settings_export", { path, includeSecrets }),
+  importFrom: (path: string) => invoke<SettingsImport>("settings_import", { path }),
+};
+
 /* ---------- endpoints ---------- */
 export const ArkEndpoints = {
   list: () => invoke<EndpointStore>("ark_endpoints_list"),
@@ -69,8 +104,9 @@ export const Ark = {
   run:         (id = "arknet", binOverride?: string)   => invoke<void>("ark_run", { id, bin_override: binOverride }),
   runKill:     (id = "arknet")                          => invoke<void>("ark_run_kill", { id }),
   runStatus:   (id = "arknet")                          => invoke<string | null>("ark_run_status", { id }),
-  bootstrapFetch: (url?: string, destDir?: string)      => invoke<void>("ark_bootstrap_fetch", { url, dest_dir: destDir }),
-  bootstrapBuild: (srcDir?: string)                     => invoke<void>("ark_bootstrap_build", { src_dir: srcDir }),
+  bootstrapFetch: (url?: string, destDir?: string, gitRef?: string) =>
+    invoke<BootstrapFetch>("ark_bootstrap_fetch", { url, dest_dir: destDir, git_ref: gitRef }),
+  bootstrapBuild: (srcDir?: string)                     => invoke<BootstrapBuild>("ark_bootstrap_build", { src_dir: srcDir }),
 
   // events
   onRpcUpdate: (cb: (payload: RpcUpdate) => void): Promise<UnlistenFn> =>
@@ -2,11 +2,36 @@
 import { useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { open } from "@tauri-apps/plugin-dialog";
 import Section from "../ui/Section";
 
-type CleanupReport = { removed: string[]; skipped: { path: string; reason: string }[] };
+type CleanupReport = { removed: string[]; skipped: { path: string; reason: string }[]; bytesFreed?: number; archive?: string | null };
 type Probe = { home: string; present: boolean; initialized: boolean; missing: string[] };
-type InstallEvt = { step: number; total: number; label: string; done: boolean; ok: boolean };
+type StepRecord = {
+  id: string;
+  label: string;
+  weight: number;
+  state: "pending" | "running" | "done" | "skipped" | "failed";
+  elapsedMs: number;
+  detail?: string;
+};
+type InstallEvt = {
+  kind: "plan" | "start" | "finish" | "skip" | "fail" | "summary";
+  id: string;
+  label: string;
+  step: number;
+  total: number;
+  pct: number;
+  done: boolean;
+  ok: boolean;
+  elapsedMs: number;
+  error?: string;
+  output?: string[];
+  log?: string;
+  steps?: StepRecord[];
+};
+type ProcLog = { id: string; stream: "stdout" | "stderr"; line: string };
+type FileIssue = { path: string; problem: "modified" | "missing" | "unlisted" | "untrusted"; detail: string };
 
 type Preflight = {
   home: string;
@@ -23,17 +48,43 @@ type Preflight = {
   // Windows-only (present but harmless elsewhere)
   missingDlls: string[];
   dllsOk: boolean;
+  // signed payload manifest (resources/integrity.json)
+  integrityIssues: FileIssue[];
+  integrityNotes: string[];
+  signatureOk: boolean;
+  integrityOk: boolean;
   ok: boolean;
 };
 
+type InstalledVersion = { version: string; path: string; installedAt: number; reported?: string | null; active: boolean; previous: boolean };
+type VersionList = { current?: string | null; previous?: string | null; keep: number; items: InstalledVersion[] };
+type ImportReport = {
+  bundle: { version: string; abiRev: number; platform: string };
+  version: string;
+  signatureOk: boolean;
+  arkpyUpdated: boolean;
+  notes: string[];
+  versions: VersionList;
+};
+
+type SelfTestCheck = {
+  id: string;
+  label: string;
+  state: "running" | "pass" | "fail" | "skip";
+  detail: string;
+  elapsedMs: number;
+};
+
 type SelfTest = {
-  binPath: string;
-  arkdOk: boolean;
-  arkdStdout: string;
-  arkdStderr: string;
-  python: string;
-  arkpyOk: boolean;
-  arkpyVersion: string;
+  ok: boolean;
+  /** some checks were skipped */
+  partial: boolean;
+  checks: SelfTestCheck[];
+  binPath: string | null;
+  python: string | null;
+  arkpyVersion: string | null;
+  log: string[];
+  elapsedMs: number;
 };
 
 const Chip = ({ ok, label }: { ok: boolean; label: string }) => (
@@ -76,16 +127,21 @@ export default function InstallPrompt({
 
   const [preflight, setPreflight] = useState<Preflight | null>(null);
   const [selftest, setSelftest] = useState<SelfTest | null>(null);
+  const [versions, setVersions] = useState<VersionList | null>(null);
 
   const [progress, setProgress] = useState<{ pct: number; label: string; active: boolean; done: boolean; visible: boolean; }>(
     { pct: 0, label: "", active: false, done: false, visible: false }
   );
   const unlistenRef = useRef<UnlistenFn | null>(null);
+  const procUnlistenRef = useRef<UnlistenFn | null>(null);
   const lastDllsOk = useRef<boolean | null>(null);
 
   useEffect(() => { setProbe({ home, present: false, initialized: false, missing }); }, [home, missing]);
-  useEffect(() => { runPreflight(); }, []);
-  useEffect(() => () => { if (unlistenRef.current) { unlistenRef.current(); unlistenRef.current = null; } }, []);
+  useEffect(() => { runPreflight(); loadVersions(); }, []);
+  useEffect(() => () => {
+    if (unlistenRef.current) { unlistenRef.current(); unlistenRef.current = null; }
+    stopProcLog();
+  }, []);
 
   // If DLL loader fails, auto-run selftest once to surface stderr immediately.
   useEffect(() => {
@@ -98,6 +154,8 @@ export default function InstallPrompt({
     }
   }, [preflight]);
 
+  const stopProcLog = () => { if (procUnlistenRef.current) { procUnlistenRef.current(); procUnlistenRef.current = null; } };
+
   const appendLog = (lines: string | string[]) =>
     setLog((prev) => (prev ? prev + "\n\n" : "") + (Array.isArray(lines) ? lines.join("\n") : lines));
 
@@ -106,27 +164,62 @@ export default function InstallPrompt({
     catch (e: any) { appendLog(String(e)); }
   };
 
+  const loadVersions = async () => {
+    try { setVersions(await invoke<VersionList>("install_list_versions")); }
+    catch (e: any) { appendLog(String(e)); }
+  };
+
+  const switchVersion = async (cmd: "install_activate" | "install_rollback", version?: string) => {
+    setBusy(true);
+    try {
+      const v = await invoke<VersionList>(cmd, version ? { version } : {});
+      setVersions(v);
+      appendLog(`[versions] active: ${v.current ?? "(none)"} — restart the node to use it`);
+    } catch (e: any) { appendLog(String(e)); }
+    finally { setBusy(false); }
+  };
+
+  const importBundle = async () => {
+    const path = await open({ multiple: false, directory: false, filters: [{ name: "Update bundle", extensions: ["zip"] }] });
+    if (typeof path !== "string") return;
+    setBusy(true);
+    appendLog(`[bundle] importing ${path}`);
+    try {
+      const r = await invoke<ImportReport>("install_import_bundle", { path });
+      setVersions(r.versions);
+      appendLog([
+        `[bundle] installed arkd ${r.version} (abiRev ${r.bundle.abiRev}, ${r.signatureOk ? "signed" : "unsigned"})`,
+        r.arkpyUpdated ? "[bundle] ArkPy upgraded from bundled wheels" : "",
+        ...r.notes.map((n) => `[bundle] ${n}`),
+        "[bundle] restart the node to use it",
+      ].filter(Boolean));
+    } catch (e: any) { appendLog(`[bundle] ${String(e)}`); }
+    finally { setBusy(false); }
+  };
+
   const recheck = async () => {
     const p = await invoke<Probe>("probe_install");
     setProbe(p);
     await runPreflight();
+    await loadVersions();
     if (p.initialized) onInstalled();
   };
 
   const runSelftest = async () => {
+    appendLog("[selftest] starting arkd on scratch data…");
+    const off = await listen<SelfTestCheck>("arknet://selftest_check", (e) => {
+      const c = e.payload;
+      if (c.state !== "running") appendLog(`[selftest] ${c.state.padEnd(4)} ${c.label}${c.detail ? ` — ${c.detail}` : ""}`);
+    });
     try {
       const res = await invoke<SelfTest>("install_selftest");
       setSelftest(res);
       appendLog([
-        "[selftest]",
-        `arkdOk=${res.arkdOk}`,
-        `arkpyOk=${res.arkpyOk} (v${res.arkpyVersion || "?"})`,
-        res.arkdStdout?.trim() ? `arkd: ${res.arkdStdout.trim()}` : "",
-        res.arkdStderr?.trim() ? `arkd(!): ${res.arkdStderr.trim()}` : "",
-        `binPath=${res.binPath}`,
-        `python=${res.python}`,
-      ].filter(Boolean) as string[]);
+        `[selftest] ${res.ok ? (res.partial ? "passed (some checks skipped)" : "passed") : "FAILED"} in ${(res.elapsedMs / 1000).toFixed(1)}s`,
+        ...(res.ok ? [] : res.log.slice(-40)),
+      ]);
     } catch (e: any) { appendLog(`selftest error: ${String(e)}`); }
+    finally { off(); }
   };
 
   const startInstall = async () => {
@@ -135,26 +228,70 @@ export default function InstallPrompt({
     setProgress({ pct: 0, label: "Starting…", active: true, done: false, visible: true });
     setBusy(true);
 
+    stopProcLog();
+    // venv/pip output, live
+    procUnlistenRef.current = await listen<ProcLog>("ark:proc:log", (e) => {
+      const { id, stream, line } = e.payload;
+      if (id.startsWith("install.")) appendLog(`  ${stream === "stderr" ? "!" : "|"} ${line}`);
+    });
     unlistenRef.current = await listen<InstallEvt>("arknet://install_progress", (e) => {
-      const { step, total, label, done } = e.payload;
-      const pct = Math.min(100, Math.round((step / Math.max(1, total)) * 100));
-      setProgress({ pct, label, active: !done, done, visible: true });
-      if (done) {
-        setBusy(false);
-        setTimeout(async () => { await recheck(); await runSelftest(); }, 150);
-        if (unlistenRef.current) { unlistenRef.current(); unlistenRef.current = null; }
+      const ev = e.payload;
+      const secs = (ev.elapsedMs / 1000).toFixed(1);
+      switch (ev.kind) {
+        case "start":
+          setProgress({ pct: ev.pct, label: `${ev.step}/${ev.total} ${ev.label}`, active: true, done: false, visible: true });
+          break;
+        case "finish":
+          appendLog(`[install] ${ev.label} — ${secs}s`);
+          setProgress((p) => ({ ...p, pct: ev.pct }));
+          break;
+        case "skip":
+          appendLog(`[install] ${ev.label}`);
+          setProgress((p) => ({ ...p, pct: ev.pct }));
+          break;
+        case "fail":
+          // the output was already streamed above; the error repeats its tail
+          appendLog(`[install] ${ev.label} failed after ${secs}s: ${ev.error ?? ""}`);
+          if (ev.log && !ev.error?.includes(ev.log)) appendLog(`[install] full output: ${ev.log}`);
+          break;
+        case "summary": {
+          const cancelled = ev.error === "install cancelled";
+          setBusy(false);
+          setProgress({
+            pct: ev.pct,
+            label: ev.ok ? ev.label : cancelled ? "Cancelled — Install / Repair resumes" : "Error",
+            active: false,
+            done: ev.ok,
+            visible: true,
+          });
+          if (cancelled) appendLog("Install cancelled; finished steps are kept and resume on the next run.");
+          else appendLog(`[install] ${ev.label} in ${secs}s`);
+          if (ev.ok) setTimeout(async () => { await recheck(); await runSelftest(); }, 150);
+          if (unlistenRef.current) { unlistenRef.current(); unlistenRef.current = null; }
+          stopProcLog();
+          break;
+        }
       }
     });
 
+    // the summary event reports the outcome; errors raised before any event (another
+    // install running) only arrive here
     try { await invoke("install_arknet_progress"); }
     catch (e: any) {
-      setBusy(false);
-      setProgress({ pct: 0, label: "Error", active: false, done: false, visible: true });
-      appendLog(String(e));
-      if (unlistenRef.current) { unlistenRef.current(); unlistenRef.current = null; }
+      if (unlistenRef.current) {
+        setBusy(false);
+        setProgress({ pct: 0, label: "Error", active: false, done: false, visible: true });
+        appendLog(String(e));
+        unlistenRef.current(); unlistenRef.current = null;
+      }
+      stopProcLog();
     }
   };
 
+  const cancelInstall = async () => {
+    try { await invoke<boolean>("install_cancel"); } catch (e: any) { appendLog(String(e)); }
+  };
+
   const cleanupSpurious = async () => {
     setBusy(true);
     try {
@@ -178,7 +315,8 @@ export default function InstallPrompt({
          preflight.parentWritable &&
          preflight.freeBytes >= preflight.needBytes &&
          preflight.binsOk &&
-         preflight.wheelsOk)
+         preflight.wheelsOk &&
+         preflight.integrityOk)
       : false;
 
   const installDisabled = busy || !uiOk;
@@ -191,6 +329,7 @@ export default function InstallPrompt({
     if (preflight.freeBytes < preflight.needBytes) reasons.push(`need ≥ ${formatBytes(preflight.needBytes)} free (have ${formatBytes(preflight.freeBytes)})`);
     if (!preflight.binsOk) reasons.push(`binaries missing: ${preflight.missingBins?.length ? preflight.missingBins.join(", ") : "unknown"}`);
     if (!preflight.wheelsOk) reasons.push(`wheels missing: ${preflight.missingWheels?.length ? preflight.missingWheels.join(", ") : "unknown"}`);
+    if (!preflight.integrityOk) reasons.push(`integrity check failed: ${preflight.integrityIssues.map((i) => i.path).join(", ")}`);
     return reasons.join("; ");
   })();
 
@@ -226,6 +365,7 @@ export default function InstallPrompt({
             <div className="flex items-center gap-2">
               <button onClick={recheck} className="px-3 py-2 rounded-md border border-border bg-white/5 hover:bg-white/10 text-[13px]">Re-check</button>
               <button onClick={reveal} className="px-3 py-2 rounded-md border border-border bg-white/5 hover:bg-white/10 text-[13px]">Reveal folder</button>
+              <button onClick={importBundle} disabled={busy} className="px-3 py-2 rounded-md border border-border bg-white/5 hover:bg-white/10 text-[13px] disabled:opacity-60" title="Install arkd/ArkPy from an offline update bundle (.zip)">Import update…</button>
               <button
                 onClick={startInstall}
                 disabled={installDisabled}
@@ -235,6 +375,9 @@ export default function InstallPrompt({
               >
                 {busy ? "Setting up…" : "Install / Repair"}
               </button>
+              {progress.active && (
+                <button onClick={cancelInstall} className="px-3 py-2 rounded-md border border-border bg-white/5 hover:bg-white/10 text-[13px]">Cancel</button>
+              )}
             </div>
           }
         >
@@ -255,10 +398,12 @@ export default function InstallPrompt({
                     <Chip ok={preflight.binsOk} label={preflight.binsOk ? "Binaries bundled" : "Binaries missing"} />
                     <Chip ok={preflight.wheelsOk} label={preflight.wheelsOk ? "Wheels present" : "Wheels missing"} />
                     {"dllsOk" in preflight && <Chip ok={preflight.dllsOk} label={preflight.dllsOk ? "Loader OK" : "Loader failed"} />}
+                    <Chip ok={preflight.integrityOk} label={preflight.integrityOk ? (preflight.signatureOk ? "Signature verified" : "Unsigned (dev)") : "Integrity failed"} />
                     {selftest && (
                       <>
-                        <Chip ok={selftest.arkdOk} label="arkd loads" />
-                        <Chip ok={selftest.arkpyOk} label={`ArkPy import${selftest.arkpyVersion ? ` v${selftest.arkpyVersion}` : ""}`} />
+                        {selftest.checks.filter((c) => c.state !== "skip").map((c) => (
+                          <Chip key={c.id} ok={c.state === "pass"} label={c.id === "arkpy" && selftest.arkpyVersion ? `${c.label} v${selftest.arkpyVersion}` : c.label} />
+                        ))}
                       </>
                     )}
                   </div>
@@ -296,6 +441,19 @@ export default function InstallPrompt({
                   </details>
                 ) : null}
 
+                {/* Integrity issues (signed manifest) */}
+                {preflight && !preflight.integrityOk && preflight.integrityIssues.length ? (
+                  <details className="mt-3 group open:animate-fade-in" open>
+                    <summary className="cursor-pointer text-[12px] text-white/70 select-none">Integrity check failed</summary>
+                    <ul className="mt-1 list-disc pl-5 text-[12px] text-white/80 break-all">
+                      {preflight.integrityIssues.map((i) => (<li key={i.path}><b>{i.problem}</b> {i.path} — {i.detail}</li>))}
+                    </ul>
+                    <div className="mt-1 text-[11px] text-white/55">
+                      The bundled payload does not match its signed manifest. Reinstall ArkDesk from a trusted download.
+                    </div>
+                  </details>
+                ) : null}
+
                 {/* DLL issues (Windows) */}
                 {preflight && preflight.dllsOk === false ? (
                   <details className="mt-3 group open:animate-fade-in">
@@ -396,12 +554,48 @@ export default function InstallPrompt({
                 </div>
               </div>
 
+              {versions && versions.items.length > 0 && (
+                <div className="rounded-lg border border-border p-4">
+                  <div className="flex items-center justify-between mb-2">
+                    <div className="text-[12px] text-white/60">Installed versions (keeping {versions.keep})</div>
+                    <button
+                      onClick={() => switchVersion("install_rollback")}
+                      disabled={busy || versions.items.length < 2}
+                      className="px-3 py-1.5 rounded-md border border-border bg-white/5 hover:bg-white/10 text-[12px] disabled:opacity-50"
+                    >
+                      Roll back
+                    </button>
+                  </div>
+                  <ul className="space-y-1.5">
+                    {versions.items.map((v) => (
+                      <li key={v.version} className="flex items-center justify-between gap-2 text-[13px]">
+                        <div className="min-w-0">
+                          <span className="text-white/90">{v.version}</span>
+                          {v.active && <span className="ml-2 text-[11px] text-emerald-300">active</span>}
+                          {v.previous && <span className="ml-2 text-[11px] text-white/55">previous</span>}
+                          {v.installedAt ? <div className="text-[11px] text-white/50">{new Date(v.installedAt).toLocaleString()}</div> : null}
+                        </div>
+                        {!v.active && (
+                          <button
+                            onClick={() => switchVersion("install_activate", v.version)}
+                            disabled={busy}
+                            className="px-2 py-1 rounded-md border border-border bg-white/5 hover:bg-white/10 text-[12px]"
+                          >
+                            Activate
+                          </button>
+                        )}
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              )}
+
               <div className="rounded-lg border border-border p-3">
                 <div className="flex items-center justify-between">
                   <div className="text-[12px] text-white/60 mb-1">Log</div>
                   {!!selftest && (
                     <div className="text-[11px] text-white/55">
-                      Bin: <code className="opacity-80">{selftest.binPath}</code> • Py: <code className="opacity-80">{selftest.python}</code>
+                      Bin: <code className="opacity-80">{selftest.binPath ?? "—"}</code> • Py: <code className="opacity-80">{selftest.python ?? "—"}</code>
                     </div>
                   )}
                 </div>
@@ -2,13 +2,15 @@
 import { useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
+import { describeSettingsError } from "../bridge/ark";
 import Section from "../ui/Section";
 import { Btn, Chip, Field, Input, formatBytes } from "../ui/atoms";
 import { useCached, formatAge } from "../hooks/useCached";
 
 type NodeRole = "relay" | "miner";
 type Settings = { arkPyPath: string; role: NodeRole };
-type ValidationReport = { arkPyExists: boolean };
+type Diagnostic = { field: string; severity: "warn" | "error"; code: string; message: string };
+type ValidationReport = { ok: boolean; diagnostics: Diagnostic[]; arkPyExists: boolean };
 type GpuInfo = { name: string; vramMb: number; vramBytes: number; driver?: string | null };
 type HostProbe = {
   os: "windows" | "linux" | "darwin" | "unknown";
@@ -22,6 +24,12 @@ type HostProbe = {
   gpus: GpuInfo[];
   warnings?: string[];
 };
+type PyEnvVerify = {
+  ok: boolean;
+  lock: string | null;
+  mismatches: { name: string; want: string | null; have: string | null }[];
+  info: { path: string; error: string | null };
+};
 
 const SmallSpinner = () => (
   <span
@@ -44,6 +52,7 @@ export default function MinerSettings() {
   const [saving, setSaving] = useState(false);
   const [err, setErr] = useState<string | null>(null);
   const [diagLog, setDiagLog] = useState<string>("");
+  const [invalid, setInvalid] = useState(false);
 
   // Load settings once
   useEffect(() => {
@@ -53,7 +62,7 @@ export default function MinerSettings() {
         setSettings(s);
         setDraft(s);
       } catch (e: any) {
-        setErr(String(e));
+        setErr(describeSettingsError(e));
         const fallback = { arkPyPath: "", role: "miner" } as Settings;
         setSettings(fallback);
         setDraft(fallback);
@@ -98,15 +107,18 @@ export default function MinerSettings() {
     }
   };
 
-  const onSave = async () => {
+  const onSave = async (force = false) => {
     if (!draft) return;
     setSaving(true);
+    setErr(null);
+    setInvalid(false);
     try {
-      await invoke("save_settings", { settings: draft });
+      await invoke("save_settings", { settings: draft, force });
       setSettings(draft);
       await refreshValidation();
     } catch (e: any) {
       setErr(String(e));
+      setInvalid(String(e).startsWith("invalid settings"));
     } finally {
       setSaving(false);
     }
@@ -118,7 +130,7 @@ export default function MinerSettings() {
 
   const installArkPy = async () => {
     try {
-      setDiagLog("(installing ArkPy…)");
+      setDiagLog("(installing ArkPy into the managed venv…)");
       await invoke("install_arkpy");
       await triggerRecheck();
       setDiagLog("ArkPy installed.");
@@ -127,11 +139,34 @@ export default function MinerSettings() {
     }
   };
 
+  const verifyPyEnv = async () => {
+    try {
+      setDiagLog("(verifying venv…)");
+      const v = await invoke<PyEnvVerify>("pyenv_verify");
+      const lines = v.mismatches.map((m) => `${m.name}: want ${m.want ?? "any"}, have ${m.have ?? "missing"}`);
+      if (v.info.error) lines.unshift(v.info.error);
+      setDiagLog(v.ok ? `Venv OK (${v.lock ? "matches lock file" : "no lock file bundled"})` : lines.join("\n"));
+    } catch (e: any) {
+      setDiagLog(String(e));
+    }
+  };
+
+  const repairPyEnv = async () => {
+    try {
+      setDiagLog("(rebuilding venv…)");
+      await invoke("pyenv_repair");
+      await triggerRecheck();
+      setDiagLog("Venv rebuilt.");
+    } catch (e: any) {
+      setDiagLog(String(e));
+    }
+  };
+
   const locatePython = async () => {
     try {
       setDiagLog("(locating Python…) ");
       const p = await invoke<string | null>("locate_python").catch(() => null);
-      setDiagLog(p ? `Python: ${p}` : "Python not found.");
+      setDiagLog(p ? `Python: ${p}` : "Managed venv not installed.");
       await triggerRecheck();
     } catch (e: any) {
       setDiagLog(String(e));
@@ -173,8 +208,10 @@ export default function MinerSettings() {
             <Btn onClick={triggerRecheck} disabled={hostBusy || reportBusy}>Re-check</Btn>
             <Btn onClick={installArkPy} disabled={!!host?.arkPyOk || hostBusy}>Install ArkPy</Btn>
             <Btn onClick={locatePython} disabled={hostBusy}>Locate Python</Btn>
+            <Btn onClick={verifyPyEnv} disabled={hostBusy}>Verify venv</Btn>
+            <Btn onClick={repairPyEnv} disabled={hostBusy}>Repair venv</Btn>
             <Btn
-              onClick={onSave}
+              onClick={() => onSave()}
               disabled={!dirty || saving}
               className="bg-primary/20 hover:bg-primary/30 border-primary/30"
               aria-busy={saving}
@@ -217,7 +254,7 @@ export default function MinerSettings() {
             </div>
           </Field>
 
-          <Field label="Python" hint="Interpreter & pip">
+          <Field label="Python" hint="Managed venv interpreter & pip">
             <div className="flex items-center gap-2">
               {hostBusy ? (
                 <span className="text-[12px] text-white/60 flex items-center gap-2"><SmallSpinner /> probing…</span>
@@ -269,9 +306,17 @@ export default function MinerSettings() {
             {(hostBusy ? "(probing…)\n" : "") +
               (reportBusy ? "(validating…)\n" : "") +
               (host?.warnings?.length ? host.warnings.join("\n") + "\n" : "") +
+              (report?.diagnostics?.length
+                ? report.diagnostics.map((d) => `${d.severity}: ${d.field}: ${d.message}`).join("\n") + "\n"
+                : "") +
               (diagLog || "(ready)")}
           </pre>
-          {err ? <div className="mt-2 text-rose-400 text-sm">{err}</div> : null}
+          {err ? (
+            <div className="mt-2 text-rose-400 text-sm">
+              {err}
+              {invalid ? <Btn className="ml-3" onClick={() => onSave(true)} disabled={saving}>Save anyway</Btn> : null}
+            </div>
+          ) : null}
         </div>
       </Section>
     </>
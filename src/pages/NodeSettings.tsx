@@ -1,7 +1,9 @@
 // src/pages/NodeSettings.tsx
-import { useEffect, useMemo, useState } from "react";
+import { useEffect, useMemo, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
+import { describeSettingsError } from "../bridge/ark";
 import Section from "../ui/Section";
 import { Btn, Chip, Field, Input, Light } from "../ui/atoms";
 
@@ -27,6 +29,15 @@ type NodeStatus = {
   rpcEndpoint: string; // derived "http://host:port"
 };
 
+/** What saving would take to apply (settings_preview / settings_apply) */
+type ApplyAction = "none" | "restartNode" | "restartApp";
+type ApplyPlan = {
+  changes: { field: string; effect: "live" | "nodeRestart" | "appRestart" }[];
+  action: ApplyAction;
+  needsConfirmation: boolean;
+};
+type ApplyReport = { plan: ApplyPlan; performed: ApplyAction; message: string };
+
 /** Validation result for current RPC endpoint */
 type ValidationReport = {
   rpcUrl: string;
@@ -36,8 +47,77 @@ type ValidationReport = {
   note?: string | null;
 };
 
-type CleanupReport = { removed: string[]; skipped: { path: string; reason: string }[] };
+type CleanupReport = {
+  removed: string[];
+  skipped: { path: string; reason: string }[];
+  dryRun?: boolean;
+  files?: { path: string; bytes: number }[];
+  bytesFreed?: number;
+  archive?: string | null;
+};
 type Probe = { home: string; present: boolean; initialized: boolean; missing: string[] };
+type UninstallItem = { kind: string; path: string; bytes: number; keep: boolean };
+type UninstallReport = CleanupReport & {
+  dryRun: boolean;
+  stoppedNode?: number | null;
+  items: UninstallItem[];
+  removeBytes: number;
+  keepBytes: number;
+  homeRemoved: boolean;
+};
+
+/** reset_node_data: clear parts of the node's data, keeping config and keys */
+type DataScope = "chain" | "mempool" | "peers" | "logs" | "pyenv" | "run";
+const DATA_SCOPES: DataScope[] = ["chain", "mempool", "peers", "logs", "pyenv", "run"];
+type ResetReport = {
+  dryRun: boolean;
+  runningNode?: number | null;
+  items: { scope: DataScope; path: string; bytes: number }[];
+  bytesFreed: number;
+  removed: string[];
+  skipped: { path: string; reason: string }[];
+};
+
+/** snapshot_*: checksummed archives of data/ */
+type SnapshotMeta = {
+  id: string;
+  label?: string | null;
+  createdAt: number;
+  network?: string | null;
+  arkdVersion?: string | null;
+  tipHeight?: number | null;
+  tipHash?: string | null;
+  dataBytes: number;
+  archiveBytes: number;
+  sha256: string;
+};
+type SnapshotRun = { snapshot: SnapshotMeta; stoppedNode?: number | null; restarted: boolean; stoppedExternal?: number | null };
+
+/** disk_usage and the low-disk watchdog (disk://low, disk://ok) */
+type DiskLevel = "ok" | "low" | "critical";
+type DiskUsage = {
+  areas: { area: string; path: string; bytes: number }[];
+  otherBytes: number;
+  totalBytes: number;
+  volume: string;
+  freeBytes?: number | null;
+  warnBytes: number;
+  stopBytes: number;
+  level: DiskLevel;
+};
+type DiskAlert = { level: DiskLevel; volume: string; freeBytes: number; warnBytes: number; stopBytes: number; stoppedNode?: number | null; error?: string | null };
+
+/** Where the Arknet home is and why (ark_home_info / ark_home_set) */
+type HomeInfo = { path: string; source: "default" | "app" | "file" | "env" | "cli"; default: string; saved?: string | null; exists: boolean };
+type HomeMove = { from: string; to: string; migrated: boolean; bytes: number; stoppedNode?: number | null; leftovers: { path: string; reason: string }[] };
+
+function fmtBytes(n: number) {
+  if (n < 1024) return `${n} B`;
+  const u = ["KB", "MB", "GB", "TB"];
+  let i = -1;
+  do { n /= 1024; i++; } while (n >= 1024 && i < u.length - 1);
+  return `${n.toFixed(1)} ${u[i]}`;
+}
 
 export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRole) => void }) {
   const [settings, setSettings] = useState<Settings | null>(null);
@@ -47,17 +127,32 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
   const [status, setStatus] = useState<NodeStatus | null>(null);
   const [report, setReport] = useState<ValidationReport | null>(null);
   const [err, setErr] = useState<string | null>(null);
+  const [invalid, setInvalid] = useState(false);
+  const [notice, setNotice] = useState<string | null>(null);
+  // full backend settings; the draft only carries the fields edited here
+  const rawRef = useRef<Record<string, unknown>>({});
 
   const [probe, setProbe] = useState<Probe | null>(null);
   const [dangerLog, setDangerLog] = useState<string>("");
   const [token, setToken] = useState<string>("");
   const [dzBusy, setDzBusy] = useState(false);
+  const [keepData, setKeepData] = useState(true);
+  const [keepKeys, setKeepKeys] = useState(true);
+  const [resetScopes, setResetScopes] = useState<DataScope[]>(["chain"]);
+  const [home, setHome] = useState<HomeInfo | null>(null);
+  const [homeMsg, setHomeMsg] = useState<string>("");
+  const [disk, setDisk] = useState<DiskUsage | null>(null);
+  const [diskAlert, setDiskAlert] = useState<DiskAlert | null>(null);
+  const [snaps, setSnaps] = useState<SnapshotMeta[]>([]);
+  const [snapLabel, setSnapLabel] = useState("");
+  const [snapMsg, setSnapMsg] = useState<string>("");
 
   // Initial load
   useEffect(() => {
     (async () => {
       try {
         const s = await invoke<Settings>("get_settings");
+        rawRef.current = s as unknown as Record<string, unknown>;
         // lightweight defaults if older settings exist
         const norm: Settings = {
           rpcHost: s.rpcHost || "127.0.0.1",
@@ -72,8 +167,9 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
         // validate endpoint & probe install info
         setReport(await invoke<ValidationReport>("validate_rpc").catch(() => null));
         setProbe(await invoke<Probe>("probe_install").catch(() => null));
+        setHome(await invoke<HomeInfo>("ark_home_info").catch(() => null));
       } catch (e: any) {
-        setErr(String(e));
+        setErr(describeSettingsError(e));
       }
     })();
   }, []);
@@ -117,12 +213,21 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
 
   const dirty = useMemo(() => JSON.stringify(settings) !== JSON.stringify(draft), [settings, draft]);
 
-  const onSave = async () => {
+  const onSave = async (force = false) => {
     if (!draft) return;
     setSaving(true);
     setErr(null);
+    setNotice(null);
+    setInvalid(false);
     try {
-      await invoke("save_settings", { settings: draft });
+      const merged = { ...rawRef.current, rpcPort: draft.rpcPort, role: draft.role };
+      const plan = await invoke<ApplyPlan>("settings_preview", { settings: merged });
+      const what = plan.action === "restartApp" ? "restart ArkDesk" : "restart the node";
+      const confirm = plan.needsConfirmation
+        && window.confirm(`Applying ${plan.changes.map((c) => c.field).join(", ")} will ${what}. Do it now?`);
+      const r = await invoke<ApplyReport>("settings_apply", { settings: merged, force, confirm });
+      setNotice(r.message);
+      rawRef.current = merged;
       setSettings(draft);
       // Re-validate and ping status
       setReport(await invoke<ValidationReport>("validate_rpc").catch(() => report));
@@ -130,6 +235,7 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
       if (settings?.role !== draft.role && onRoleSaved) onRoleSaved(draft.role);
     } catch (e: any) {
       setErr(String(e));
+      setInvalid(String(e).startsWith("invalid settings"));
     } finally {
       setSaving(false);
     }
@@ -152,18 +258,99 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
     }
   };
 
+  const moveHome = async (target?: string) => {
+    try {
+      const dir = target ?? await open({ directory: true, multiple: false, title: "Choose the Arknet home" });
+      if (!dir) return;
+      if (!window.confirm(`Move the Arknet home to ${dir}? The node is stopped while its files move.`)) return;
+      setDzBusy(true);
+      const r = await invoke<HomeMove>("ark_home_set", { path: String(dir), migrate: true });
+      const left = r.leftovers.length ? ` Could not remove: ${r.leftovers.map((s) => s.path).join(", ")}.` : "";
+      setHomeMsg(r.migrated ? `Moved ${fmtBytes(r.bytes)} to ${r.to}.${left}` : `Arknet home is now ${r.to}.`);
+      setHome(await invoke<HomeInfo>("ark_home_info").catch(() => home));
+      setProbe(await invoke<Probe>("probe_install").catch(() => probe));
+    } catch (e: any) {
+      setHomeMsg(String(e));
+    } finally {
+      setDzBusy(false);
+    }
+  };
+
+  const loadDisk = async () => setDisk(await invoke<DiskUsage>("disk_usage").catch(() => null));
+  useEffect(() => {
+    loadDisk();
+    const subs = [
+      listen<DiskAlert>("disk://low", (e) => { setDiskAlert(e.payload); loadDisk(); }),
+      listen<DiskAlert>("disk://ok", () => { setDiskAlert(null); loadDisk(); }),
+    ];
+    return () => { subs.forEach((p) => p.then((un) => un())); };
+  }, []);
+
+  const loadSnapshots = async () => setSnaps(await invoke<SnapshotMeta[]>("snapshot_list").catch(() => []));
+  useEffect(() => { loadSnapshots(); }, []);
+
+  const snapRunMsg = (verb: string, r: SnapshotRun) => {
+    const node = r.stoppedNode ? (r.restarted ? " Node was restarted." : " Node was stopped.") : "";
+    const ext = r.stoppedExternal ? ` arkd pid ${r.stoppedExternal} was shut down; start it again when ready.` : "";
+    return `${verb} ${r.snapshot.id} (${fmtBytes(r.snapshot.archiveBytes)}).${node}${ext}`;
+  };
+
+  const takeSnapshot = async () => {
+    setDzBusy(true);
+    setSnapMsg("Stopping the node and archiving data/…");
+    try {
+      const r = await invoke<SnapshotRun>("snapshot_create", { label: snapLabel || null });
+      setSnapMsg(snapRunMsg("Saved", r));
+      setSnapLabel("");
+    } catch (e: any) {
+      setSnapMsg(String(e));
+    } finally {
+      await loadSnapshots();
+      setDzBusy(false);
+    }
+  };
+
+  const restoreSnapshot = async (s: SnapshotMeta) => {
+    if (!window.confirm(`Replace the current data/ with snapshot ${s.label || s.id}? The node is stopped meanwhile.`)) return;
+    setDzBusy(true);
+    setSnapMsg(`Restoring ${s.id}…`);
+    try {
+      setSnapMsg(snapRunMsg("Restored", await invoke<SnapshotRun>("snapshot_restore", { id: s.id })));
+    } catch (e: any) {
+      setSnapMsg(String(e));
+    } finally {
+      setDzBusy(false);
+    }
+  };
+
+  const deleteSnapshot = async (s: SnapshotMeta) => {
+    if (!window.confirm(`Delete snapshot ${s.label || s.id}?`)) return;
+    try {
+      await invoke("snapshot_delete", { id: s.id });
+    } catch (e: any) {
+      setSnapMsg(String(e));
+    }
+    await loadSnapshots();
+  };
+
   const logCleanup = (r: CleanupReport) => {
-    const removed = r.removed.length ? r.removed.join("\n") : "(none)";
     const skipped = r.skipped.length
       ? r.skipped.map(s => `${s.path} — ${s.reason}`).join("\n")
       : "(none)";
-    setDangerLog(`Removed:\n${removed}\n\nSkipped:\n${skipped}`);
+    if (r.dryRun) {
+      const files = r.files?.length ? r.files.map((f) => `${fmtBytes(f.bytes).padStart(9)}  ${f.path}`).join("\n") : "(none)";
+      setDangerLog(`Dry run — would free ${fmtBytes(r.bytesFreed ?? 0)}:\n${files}\n\nSkipped:\n${skipped}`);
+      return;
+    }
+    const removed = r.removed.length ? r.removed.join("\n") : "(none)";
+    const backup = r.archive ? `\n\nBackup: ${r.archive}` : "";
+    setDangerLog(`Removed (${fmtBytes(r.bytesFreed ?? 0)}):\n${removed}${backup}\n\nSkipped:\n${skipped}`);
   };
 
-  const removeSpurious = async (force: boolean) => {
+  const removeSpurious = async (force: boolean, dryRun = false) => {
     setDzBusy(true);
     try {
-      const r = await invoke<CleanupReport>("cleanup_spurious_dirs", { force });
+      const r = await invoke<CleanupReport>("cleanup_spurious_dirs", { force, opts: { dryRun } });
       logCleanup(r);
       setProbe(await invoke<Probe>("probe_install").catch(() => probe));
     } catch (e: any) {
@@ -173,11 +360,37 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
     }
   };
 
-  const uninstallArknet = async () => {
+  const resetData = async (dryRun: boolean) => {
+    if (!dryRun && !window.confirm(`Delete ${resetScopes.join(", ")} data? Config and keys are kept.`)) return;
     setDzBusy(true);
     try {
-      const r = await invoke<CleanupReport>("wipe_ark_home", { confirm: token });
-      logCleanup(r);
+      const r = await invoke<ResetReport>("reset_node_data", { scopes: resetScopes, dryRun });
+      const lines = r.items.map((i) => `${i.scope.padEnd(8)} ${fmtBytes(i.bytes).padStart(9)}  ${i.path}`);
+      const skipped = r.skipped.length ? `\n\nFailed:\n${r.skipped.map((s) => `${s.path} — ${s.reason}`).join("\n")}` : "";
+      const head = r.dryRun ? `Dry run — would free ${fmtBytes(r.bytesFreed)}` : `Freed ${fmtBytes(r.bytesFreed)}`;
+      const node = r.runningNode ? `\narkd (pid ${r.runningNode}) is running; stop the node before resetting.` : "";
+      setDangerLog(`${head}${node}\n\n${lines.join("\n") || "(nothing to remove)"}${skipped}`);
+    } catch (e: any) {
+      setDangerLog(String(e));
+    } finally {
+      setDzBusy(false);
+    }
+  };
+
+  const logUninstall = (r: UninstallReport) => {
+    const lines = r.items.map((i) => `${i.keep ? "keep  " : r.dryRun ? "remove" : "removed"}  ${i.kind.padEnd(8)} ${fmtBytes(i.bytes).padStart(9)}  ${i.path}`);
+    const head = r.dryRun ? "Dry run — nothing deleted." : r.homeRemoved ? "Arknet home removed." : "Uninstalled (kept items remain).";
+    const node = r.stoppedNode ? `\nNode pid ${r.stoppedNode} ${r.dryRun ? "will be" : "was"} stopped first.` : "";
+    const skipped = r.skipped.length ? `\n\nLeft alone:\n${r.skipped.map((s) => `${s.path} — ${s.reason}`).join("\n")}` : "";
+    setDangerLog(`${head}${node}\nRemove ${fmtBytes(r.removeBytes)} • keep ${fmtBytes(r.keepBytes)}\n\n${lines.join("\n")}${skipped}`);
+  };
+
+  const uninstallArknet = async (dryRun: boolean) => {
+    setDzBusy(true);
+    try {
+      const r = await invoke<UninstallReport>("uninstall", { plan: { dryRun, keepData, keepKeys } });
+      logUninstall(r);
+      if (dryRun) return;
       const p = await invoke<Probe>("probe_install").catch(() => null);
       setProbe(p as any);
       setTimeout(() => window.location.reload(), 250);
@@ -213,7 +426,7 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
             <Chip ok={rpcOk} label={rpcOk ? "RPC reachable" : "RPC not reachable"} />
             <Btn onClick={() => setDraft(settings!)} disabled={!dirty}>Revert</Btn>
             <Btn
-              onClick={onSave}
+              onClick={() => onSave()}
               disabled={!dirty || saving}
               className="bg-primary/20 hover:bg-primary/30 border-primary/30"
               aria-busy={saving}
@@ -301,7 +514,13 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
           Effective RPC: <span className="text-white/80 break-all">{rpcUrl}</span>
         </div>
 
-        {err ? <div className="mt-3 text-rose-400 text-sm">{err}</div> : null}
+        {notice ? <div className="mt-3 text-white/70 text-sm">{notice}</div> : null}
+        {err ? (
+          <div className="mt-3 text-rose-400 text-sm">
+            {err}
+            {invalid ? <Btn className="ml-3" onClick={() => onSave(true)} disabled={saving}>Save anyway</Btn> : null}
+          </div>
+        ) : null}
       </Section>
 
       <Section title="Node Status" variant="card" surface={2} padding="lg" headerPadding="md">
@@ -344,6 +563,65 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
         )}
       </Section>
 
+      <Section title="Arknet Home" variant="card" surface={2} padding="lg" headerPadding="md">
+        <div className="flex flex-wrap items-center gap-2 text-[13px] text-white/70">
+          <span className="text-white/85 break-all">{home?.path ?? "…"}</span>
+          {home && home.source !== "default" ? <span className="text-white/50">({home.source === "app" ? "chosen here" : `set by ${home.source}`})</span> : null}
+          <Btn onClick={() => moveHome()} disabled={dzBusy || home?.source === "env" || home?.source === "cli"}>Move…</Btn>
+          {home?.saved ? <Btn onClick={() => moveHome(home.default)} disabled={dzBusy}>Back to default</Btn> : null}
+        </div>
+        {homeMsg ? <div className="mt-2 text-[12px] text-white/70">{homeMsg}</div> : null}
+        {diskAlert ? (
+          <div className="mt-3 rounded-md border border-rose-400/40 bg-rose-500/10 p-2 text-[12px] text-white/85">
+            {diskAlert.level === "critical" ? "Disk almost full" : "Disk space low"}: {fmtBytes(diskAlert.freeBytes)} free on {diskAlert.volume}.
+            {diskAlert.stoppedNode ? ` arkd (pid ${diskAlert.stoppedNode}) was stopped.` : ""}
+            {diskAlert.error ? ` Could not stop arkd: ${diskAlert.error}` : ""}
+          </div>
+        ) : null}
+        {disk ? (
+          <div className="mt-3 text-[12px] text-white/70">
+            <div className="flex flex-wrap items-center gap-x-4 gap-y-1">
+              {disk.areas.map((a) => (
+                <span key={a.area} title={a.path}>{a.area} <span className="text-white/90">{fmtBytes(a.bytes)}</span></span>
+              ))}
+              <span>other <span className="text-white/90">{fmtBytes(disk.otherBytes)}</span></span>
+              <Btn onClick={loadDisk} disabled={dzBusy}>Refresh</Btn>
+            </div>
+            <div className="mt-1 text-white/55">
+              Total {fmtBytes(disk.totalBytes)}
+              {disk.freeBytes != null ? ` • ${fmtBytes(disk.freeBytes)} free` : ""}
+              {` • warns below ${fmtBytes(disk.warnBytes)}`}
+              {disk.stopBytes ? `, stops the node below ${fmtBytes(disk.stopBytes)}` : ""}
+            </div>
+          </div>
+        ) : null}
+      </Section>
+
+      <Section title="Snapshots" variant="card" surface={2} padding="lg" headerPadding="md">
+        <div className="flex items-center gap-2">
+          <Input value={snapLabel} onChange={(e) => setSnapLabel(e.target.value)} placeholder="Label (optional)" />
+          <Btn onClick={takeSnapshot} disabled={dzBusy}>Take snapshot</Btn>
+        </div>
+        {snapMsg ? <div className="mt-2 text-[12px] text-white/70">{snapMsg}</div> : null}
+        <div className="mt-3 space-y-2">
+          {snaps.length === 0 ? <div className="text-[12px] text-white/50">No snapshots yet.</div> : null}
+          {snaps.map((s) => (
+            <div key={s.id} className="flex flex-wrap items-center gap-2 rounded-md border border-border p-2 text-[12px] text-white/75">
+              <span className="text-white/90">{s.label || s.id}</span>
+              <span>{new Date(s.createdAt * 1000).toLocaleString()}</span>
+              {s.tipHeight != null ? <span title={s.tipHash ?? undefined}>height {s.tipHeight}</span> : null}
+              {s.network ? <span>{s.network}</span> : null}
+              {s.arkdVersion ? <span>arkd {s.arkdVersion}</span> : null}
+              <span className="text-white/50">{fmtBytes(s.archiveBytes)}</span>
+              <div className="ml-auto flex items-center gap-2">
+                <Btn onClick={() => restoreSnapshot(s)} disabled={dzBusy}>Restore</Btn>
+                <Btn onClick={() => deleteSnapshot(s)} disabled={dzBusy}>Delete</Btn>
+              </div>
+            </div>
+          ))}
+        </div>
+      </Section>
+
       <Section
         title="Danger Zone"
         variant="card"
@@ -356,19 +634,45 @@ export default function NodeSettings({ onRoleSaved }: { onRoleSaved?: (r: NodeRo
           <div className="space-y-3">
             <div className="text-[13px] text-white/70">Remove spurious folders created by earlier builds.</div>
             <div className="flex items-center gap-2">
+              <Btn onClick={() => removeSpurious(true, true)} disabled={dzBusy}>Preview</Btn>
               <Btn onClick={() => removeSpurious(false)} disabled={dzBusy}>Remove if empty</Btn>
               <Btn onClick={() => removeSpurious(true)} disabled={dzBusy}>Force remove</Btn>
             </div>
           </div>
 
+          <div className="space-y-2">
+            <div className="text-[13px] text-white/70">Reset node data (stop the node first). Config and keys are kept.</div>
+            <div className="flex flex-wrap items-center gap-3 text-[13px] text-white/80">
+              {DATA_SCOPES.map((s) => (
+                <label key={s} className="flex items-center gap-1">
+                  <input
+                    type="checkbox"
+                    checked={resetScopes.includes(s)}
+                    onChange={(e) => setResetScopes(e.target.checked ? [...resetScopes, s] : resetScopes.filter((x) => x !== s))}
+                  />
+                  {s}
+                </label>
+              ))}
+            </div>
+            <div className="flex items-center gap-2">
+              <Btn onClick={() => resetData(true)} disabled={dzBusy || !resetScopes.length}>Preview</Btn>
+              <Btn onClick={() => resetData(false)} disabled={dzBusy || !resetScopes.length}>Reset</Btn>
+            </div>
+          </div>
+
           <div className="space-y-2">
             <div className="text-[13px] text-white/70">
-              Type <code className="px-1 rounded bg-white/10">ARKNET-NUKE</code> to uninstall Arknet (deletes bin/, data/, logs/, config.json).
+              Type <code className="px-1 rounded bg-white/10">ARKNET-NUKE</code> to uninstall Arknet (binaries, Python runtime, logs, config; stops the node first). Preview lists every item first.
+            </div>
+            <div className="flex items-center gap-4 text-[13px] text-white/80">
+              <label className="flex items-center gap-2"><input type="checkbox" checked={keepData} onChange={(e) => setKeepData(e.target.checked)} />Keep chain data</label>
+              <label className="flex items-center gap-2"><input type="checkbox" checked={keepKeys} onChange={(e) => setKeepKeys(e.target.checked)} />Keep keys</label>
             </div>
             <div className="flex items-center gap-2">
               <Input value={token} onChange={(e) => setToken(e.target.value)} placeholder="Confirmation token" />
+              <Btn onClick={() => uninstallArknet(true)} disabled={dzBusy}>Preview</Btn>
               <Btn
-                onClick={uninstallArknet}
+                onClick={() => uninstallArknet(false)}
                 disabled={dzBusy || token !== "ARKNET-NUKE"}
                 className="border-rose-400/40 bg-rose-500/10 hover:bg-rose-500/20"
               >